@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+
+use super::TimeSeries;
+
+/// Série de um fundo usada na comparação entre fundos.
+#[derive(Debug, Clone, Default)]
+pub struct FundSeries {
+    pub cnpj: String,
+    pub name: String,
+    pub quotas: TimeSeries,
+    pub net_worth: TimeSeries,
+}
+
+impl FundSeries {
+    /// Cotas a partir de `start` (inclusive).
+    pub fn quotas_from(&self, start: NaiveDate) -> TimeSeries {
+        self.quotas
+            .iter()
+            .copied()
+            .filter(|(date, _)| *date >= start)
+            .collect()
+    }
+
+    /// Retorno acumulado (em %) a partir da cota em `start`.
+    pub fn normalized(&self, start: NaiveDate) -> TimeSeries {
+        let quotas = self.quotas_from(start);
+        let Some(&(_, base)) = quotas.first() else {
+            return Vec::new();
+        };
+        quotas
+            .into_iter()
+            .map(|(date, quota)| (date, (quota / base - 1.0) * 100.0))
+            .collect()
+    }
+
+    /// Último patrimônio líquido informado.
+    pub fn last_net_worth(&self) -> Option<f64> {
+        self.net_worth.last().map(|(_, value)| *value)
+    }
+}
+
+/// Primeira data em que todos os fundos têm cota, usada como base comum.
+pub fn common_start(series: &[FundSeries]) -> Option<NaiveDate> {
+    series
+        .iter()
+        .filter_map(|s| s.quotas.first().map(|(date, _)| *date))
+        .max()
+}
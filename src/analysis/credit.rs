@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use super::portfolio::Position;
+
+/// Faixas de vencimento, pelo limite superior em dias a partir da data da carteira.
+const MATURITY_BUCKETS: [(i64, &str); 5] = [
+    (180, "Até 6 meses"),
+    (365, "6 a 12 meses"),
+    (730, "1 a 2 anos"),
+    (1825, "2 a 5 anos"),
+    (i64::MAX, "Acima de 5 anos"),
+];
+
+/// Limites de concentração por emissor, em % do patrimônio líquido.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub warning: f64,
+    pub limit: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Concentration {
+    Normal,
+    Warning,
+    Exceeded,
+}
+
+impl Limits {
+    pub fn classify(&self, share: f64) -> Concentration {
+        if share >= self.limit {
+            Concentration::Exceeded
+        } else if share >= self.warning {
+            Concentration::Warning
+        } else {
+            Concentration::Normal
+        }
+    }
+}
+
+/// Exposição a um emissor de crédito privado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuerExposure {
+    pub name: String,
+    pub document: Option<String>,
+    pub value: f64,
+    /// Percentual do patrimônio líquido.
+    pub share: f64,
+    pub positions: usize,
+    pub related_party: bool,
+}
+
+/// Exposição de crédito da carteira de um mês: emissores, vencimentos e posições em
+/// emissores ligados.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Credit {
+    pub net_worth: f64,
+    /// Emissores do maior para o menor valor.
+    pub issuers: Vec<IssuerExposure>,
+    /// Valor e percentual do patrimônio em cada faixa de vencimento; títulos vencidos
+    /// na data da carteira entram na primeira faixa.
+    pub maturities: Vec<(&'static str, f64, f64)>,
+    pub related_party: Vec<Position>,
+}
+
+impl Credit {
+    /// Considera de crédito as posições com emissor informado (`EMISSOR` ou
+    /// `CPF_CNPJ_EMISSOR`); os vencimentos incluem todas as posições com `DT_VENC`.
+    pub fn compute(positions: &[Position], net_worth: f64) -> Self {
+        let share = |value: f64| {
+            if net_worth > 0.0 {
+                value / net_worth * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        let mut by_issuer: HashMap<String, IssuerExposure> = HashMap::new();
+        for position in positions {
+            let key = match (&position.issuer_document, &position.issuer) {
+                (Some(document), _) => document.clone(),
+                (None, Some(issuer)) => issuer.clone(),
+                (None, None) => continue,
+            };
+            let exposure = by_issuer.entry(key).or_insert_with(|| IssuerExposure {
+                name: position
+                    .issuer
+                    .clone()
+                    .or_else(|| position.issuer_document.clone())
+                    .unwrap_or_default(),
+                document: position.issuer_document.clone(),
+                value: 0.0,
+                share: 0.0,
+                positions: 0,
+                related_party: false,
+            });
+            exposure.value += position.value;
+            exposure.positions += 1;
+            exposure.related_party |= position.related_party;
+        }
+        let mut issuers: Vec<IssuerExposure> = by_issuer
+            .into_values()
+            .map(|mut exposure| {
+                exposure.share = share(exposure.value);
+                exposure
+            })
+            .collect();
+        issuers.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+        let mut buckets = [0.0; MATURITY_BUCKETS.len()];
+        for position in positions {
+            let Some(maturity) = position.maturity else {
+                continue;
+            };
+            let days = (maturity - position.date).num_days();
+            let bucket = MATURITY_BUCKETS
+                .iter()
+                .position(|(limit, _)| days <= *limit)
+                .unwrap_or(MATURITY_BUCKETS.len() - 1);
+            buckets[bucket] += position.value;
+        }
+        let maturities = MATURITY_BUCKETS
+            .iter()
+            .zip(buckets)
+            .map(|((_, label), value)| (*label, value, share(value)))
+            .collect();
+
+        let mut related_party: Vec<Position> = positions
+            .iter()
+            .filter(|p| p.related_party)
+            .cloned()
+            .collect();
+        related_party.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+        Credit {
+            net_worth,
+            issuers,
+            maturities,
+            related_party,
+        }
+    }
+
+    /// Emissores acima do limite de alerta.
+    pub fn concentrated(&self, limits: Limits) -> usize {
+        self.issuers
+            .iter()
+            .filter(|i| limits.classify(i.share) != Concentration::Normal)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+    }
+
+    fn position(issuer: Option<&str>, document: Option<&str>, value: f64) -> Position {
+        Position {
+            date: date(),
+            kind: "Debêntures".to_string(),
+            asset: "ativo".to_string(),
+            issuer: issuer.map(str::to_string),
+            issuer_document: document.map(str::to_string),
+            maturity: None,
+            related_party: false,
+            fund: None,
+            value,
+        }
+    }
+
+    fn maturing(days: i64, value: f64) -> Position {
+        Position {
+            maturity: Some(date() + Duration::days(days)),
+            ..position(None, None, value)
+        }
+    }
+
+    #[test]
+    fn issuers_are_keyed_by_document_then_name() {
+        let mut related = position(Some("Banco A"), Some("00.000.000/0001-91"), 10.0);
+        related.related_party = true;
+        let positions = [
+            related,
+            // Mesmo documento com outro nome: mesmo emissor
+            position(Some("BANCO A S.A."), Some("00.000.000/0001-91"), 30.0),
+            // Sem documento, agrupado pelo nome
+            position(Some("Empresa B"), None, 20.0),
+            position(Some("Empresa B"), None, 5.0),
+            // Só o documento: é o nome exibido
+            position(None, Some("11.111.111/0001-11"), 15.0),
+            // Sem emissor: fora da exposição de crédito
+            position(None, None, 20.0),
+        ];
+        let credit = Credit::compute(&positions, 200.0);
+
+        let issuers: Vec<_> = credit
+            .issuers
+            .iter()
+            .map(|i| {
+                (
+                    i.name.as_str(),
+                    i.value,
+                    i.share,
+                    i.positions,
+                    i.related_party,
+                )
+            })
+            .collect();
+        assert_eq!(issuers.len(), 3);
+        assert_eq!(issuers[0].1, 40.0);
+        assert_eq!(issuers[0].2, 20.0);
+        assert_eq!(issuers[0].3, 2);
+        assert!(issuers[0].4);
+        assert_eq!(issuers[1], ("Empresa B", 25.0, 12.5, 2, false));
+        assert_eq!(issuers[2], ("11.111.111/0001-11", 15.0, 7.5, 1, false));
+        assert_eq!(credit.related_party.len(), 1);
+    }
+
+    #[test]
+    fn maturity_bucket_boundaries() {
+        let positions = [
+            maturing(-10, 1.0),
+            maturing(0, 2.0),
+            maturing(180, 4.0),
+            maturing(181, 8.0),
+            maturing(1826, 16.0),
+            position(Some("Sem vencimento"), None, 32.0),
+        ];
+        let credit = Credit::compute(&positions, 100.0);
+
+        let values: Vec<_> = credit.maturities.iter().map(|(_, v, _)| *v).collect();
+        // Vencidos e vencendo na data entram na primeira faixa, junto com o dia 180
+        assert_eq!(values, [7.0, 8.0, 0.0, 0.0, 16.0]);
+        assert!((credit.maturities[0].2 - 7.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_net_worth_has_no_shares() {
+        let positions = [position(Some("Banco A"), None, 10.0), maturing(30, 5.0)];
+        let credit = Credit::compute(&positions, 0.0);
+
+        assert_eq!(credit.issuers[0].value, 10.0);
+        assert_eq!(credit.issuers[0].share, 0.0);
+        assert_eq!(credit.maturities[0], ("Até 6 meses", 5.0, 0.0));
+    }
+
+    #[test]
+    fn classifies_concentration_at_the_limits() {
+        let limits = Limits {
+            warning: 10.0,
+            limit: 20.0,
+        };
+        assert_eq!(limits.classify(9.99), Concentration::Normal);
+        assert_eq!(limits.classify(10.0), Concentration::Warning);
+        assert_eq!(limits.classify(19.99), Concentration::Warning);
+        assert_eq!(limits.classify(20.0), Concentration::Exceeded);
+
+        let positions = [
+            position(Some("A"), None, 25.0),
+            position(Some("B"), None, 10.0),
+            position(Some("C"), None, 5.0),
+        ];
+        assert_eq!(Credit::compute(&positions, 100.0).concentrated(limits), 2);
+    }
+}
@@ -0,0 +1,252 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use chrono::NaiveDate;
+use polars::frame::DataFrame;
+
+use crate::provider::cvm::{
+    fidc::{ASSET_COLUMNS, NET_WORTH_COLUMN, OVERDUE_COLUMNS, QUOTA_CLASS_COLUMN},
+    fund::index::fold,
+};
+
+use super::{date_column, float_column, text_column, TimeSeries};
+
+/// Faixas de atraso agrupadas para os gráficos, como intervalos de `OVERDUE_COLUMNS`.
+pub const OVERDUE_GROUPS: [(&str, Range<usize>); 4] = [
+    ("Até 30 dias", 0..1),
+    ("31 a 90 dias", 1..3),
+    ("91 a 180 dias", 3..6),
+    ("Acima de 180 dias", 6..10),
+];
+
+/// Senioridade de uma classe ou série de cotas do FIDC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuotaKind {
+    Senior,
+    Mezzanine,
+    Subordinated,
+    Other,
+}
+
+impl QuotaKind {
+    /// Pelo nome informado ("Sênior 1", "Subordinada Mezanino", "Subordinada Júnior").
+    pub fn parse(class: &str) -> Self {
+        let class = fold(class);
+        if class.contains("senior") {
+            QuotaKind::Senior
+        } else if class.contains("mezanino") {
+            QuotaKind::Mezzanine
+        } else if class.contains("subordinad") || class.contains("junior") {
+            QuotaKind::Subordinated
+        } else {
+            QuotaKind::Other
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            QuotaKind::Senior => "Sênior",
+            QuotaKind::Mezzanine => "Mezanino",
+            QuotaKind::Subordinated => "Subordinada",
+            QuotaKind::Other => "Outra",
+        }
+    }
+}
+
+/// Carteira, patrimônio e inadimplência de um mês.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FidcMonth {
+    pub date: NaiveDate,
+    pub total_assets: Option<f64>,
+    /// Direitos creditórios com e sem aquisição substancial dos riscos.
+    pub receivables: Option<f64>,
+    pub net_worth: Option<f64>,
+    /// Inadimplência por faixa de atraso, na ordem de `OVERDUE_COLUMNS`.
+    pub overdue: Vec<f64>,
+}
+
+impl FidcMonth {
+    pub fn overdue_total(&self) -> f64 {
+        self.overdue.iter().sum()
+    }
+
+    /// Inadimplência como fração dos direitos creditórios.
+    pub fn delinquency(&self) -> Option<f64> {
+        self.receivables
+            .filter(|receivables| *receivables > 0.0)
+            .map(|receivables| self.overdue_total() / receivables)
+    }
+
+    /// Inadimplência de cada faixa de `OVERDUE_GROUPS`, como fração dos direitos
+    /// creditórios.
+    pub fn delinquency_by_group(&self) -> Vec<Option<f64>> {
+        let receivables = self.receivables.filter(|receivables| *receivables > 0.0);
+        OVERDUE_GROUPS
+            .iter()
+            .map(|(_, range)| {
+                let overdue: f64 = self.overdue.get(range.clone())?.iter().sum();
+                receivables.map(|receivables| overdue / receivables)
+            })
+            .collect()
+    }
+}
+
+/// Posição de uma classe de cotas em um mês.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuotaPoint {
+    pub date: NaiveDate,
+    pub quantity: Option<f64>,
+    pub value: Option<f64>,
+    /// Rentabilidade informada para o mês, em %.
+    pub monthly_return: Option<f64>,
+    pub shareholders: Option<f64>,
+}
+
+impl QuotaPoint {
+    pub fn net_worth(&self) -> Option<f64> {
+        Some(self.quantity? * self.value?)
+    }
+}
+
+/// Histórico mensal de uma classe ou série de cotas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaSeries {
+    pub class: String,
+    pub kind: QuotaKind,
+    pub points: Vec<QuotaPoint>,
+}
+
+impl QuotaSeries {
+    /// Rentabilidade acumulada (%) compondo a rentabilidade mensal informada.
+    pub fn cumulative_return(&self) -> TimeSeries {
+        let mut factor = 1.0;
+        self.points
+            .iter()
+            .filter_map(|point| {
+                factor *= 1.0 + point.monthly_return? / 100.0;
+                Some((point.date, (factor - 1.0) * 100.0))
+            })
+            .collect()
+    }
+}
+
+/// Histórico mensal de um FIDC, a partir de `Fidc::async_monthly`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FidcHistory {
+    pub name: Option<String>,
+    pub months: Vec<FidcMonth>,
+    /// Classes ordenadas da mais sênior para a mais subordinada.
+    pub quotas: Vec<QuotaSeries>,
+}
+
+impl FidcHistory {
+    pub fn from_dataframes(monthly: &DataFrame, quotas: &DataFrame) -> Self {
+        let month_dates = date_column(monthly, "AS_DATE");
+        let assets: Vec<Vec<Option<f64>>> = ASSET_COLUMNS
+            .iter()
+            .map(|name| float_column(monthly, name))
+            .collect();
+        let net_worth = float_column(monthly, NET_WORTH_COLUMN);
+        let overdue: Vec<Vec<Option<f64>>> = OVERDUE_COLUMNS
+            .iter()
+            .map(|(name, _)| float_column(monthly, name))
+            .collect();
+
+        let months = month_dates
+            .iter()
+            .enumerate()
+            .filter_map(|(row, date)| {
+                let receivables = match (assets[1][row], assets[2][row]) {
+                    (None, None) => None,
+                    (with_risk, without_risk) => {
+                        Some(with_risk.unwrap_or(0.0) + without_risk.unwrap_or(0.0))
+                    }
+                };
+                Some(FidcMonth {
+                    date: (*date)?,
+                    total_assets: assets[0][row],
+                    receivables,
+                    net_worth: net_worth[row],
+                    overdue: overdue
+                        .iter()
+                        .map(|bucket| bucket[row].unwrap_or(0.0))
+                        .collect(),
+                })
+            })
+            .collect();
+
+        let name = text_column(monthly, "DENOM_SOCIAL")
+            .into_iter()
+            .flatten()
+            .last();
+
+        let classes = text_column(quotas, QUOTA_CLASS_COLUMN);
+        let quota_dates = date_column(quotas, "AS_DATE");
+        let quantity = float_column(quotas, "TAB_X_QT_COTA");
+        let value = float_column(quotas, "TAB_X_VL_COTA");
+        let monthly_return = float_column(quotas, "TAB_X_VL_RENTAB_MES");
+        let shareholders = float_column(quotas, "TAB_X_NR_COTST");
+        let mut by_class: BTreeMap<String, Vec<QuotaPoint>> = BTreeMap::new();
+        for (row, class) in classes.into_iter().enumerate() {
+            let (Some(class), Some(date)) = (class, quota_dates[row]) else {
+                continue;
+            };
+            by_class.entry(class).or_default().push(QuotaPoint {
+                date,
+                quantity: quantity[row],
+                value: value[row],
+                monthly_return: monthly_return[row],
+                shareholders: shareholders[row],
+            });
+        }
+        let mut quotas: Vec<QuotaSeries> = by_class
+            .into_iter()
+            .map(|(class, mut points)| {
+                points.sort_by_key(|point| point.date);
+                QuotaSeries {
+                    kind: QuotaKind::parse(&class),
+                    class,
+                    points,
+                }
+            })
+            .collect();
+        quotas.sort_by_key(|series| series.kind);
+
+        FidcHistory {
+            name,
+            months,
+            quotas,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.months.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&FidcMonth> {
+        self.months.last()
+    }
+
+    /// Índice de subordinação (%) de cada mês: patrimônio das classes que absorvem
+    /// perdas antes das classes em `protected` sobre o patrimônio de todas as classes.
+    /// Com `protected` só com sênior, é a subordinação total (mezanino + subordinada).
+    pub fn subordination(&self, protected: &[QuotaKind]) -> TimeSeries {
+        let mut totals: BTreeMap<NaiveDate, (f64, f64)> = BTreeMap::new();
+        for series in &self.quotas {
+            for point in &series.points {
+                let Some(net_worth) = point.net_worth() else {
+                    continue;
+                };
+                let (subordinated, total) = totals.entry(point.date).or_default();
+                *total += net_worth;
+                if !protected.contains(&series.kind) {
+                    *subordinated += net_worth;
+                }
+            }
+        }
+        totals
+            .into_iter()
+            .filter(|(_, (_, total))| *total > 0.0)
+            .map(|(date, (subordinated, total))| (date, subordinated / total * 100.0))
+            .collect()
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::NaiveDate;
+use polars::frame::DataFrame;
+
+use super::{date_column, float_column, text_column};
+
+/// Informe mensal de um FII; percentuais em fração, como no arquivo da CVM.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FiiMonth {
+    pub date: NaiveDate,
+    pub net_worth: Option<f64>,
+    pub quotas: Option<f64>,
+    pub quota_value: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    pub effective_return: Option<f64>,
+    pub patrimonial_return: Option<f64>,
+    pub amortization: Option<f64>,
+    pub shareholders: Option<f64>,
+}
+
+impl FiiMonth {
+    /// Rendimento distribuído no mês, estimado pelo dividend yield sobre o patrimônio.
+    pub fn distribution(&self) -> Option<f64> {
+        Some(self.dividend_yield? * self.net_worth?)
+    }
+
+    /// Rendimento por cota, estimado pelo dividend yield sobre o valor patrimonial.
+    pub fn distribution_per_quota(&self) -> Option<f64> {
+        Some(self.dividend_yield? * self.quota_value?)
+    }
+}
+
+/// Perfil do FII no último informe do período.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FiiProfile {
+    pub name: Option<String>,
+    pub segment: Option<String>,
+    pub mandate: Option<String>,
+    pub management: Option<String>,
+    pub target_audience: Option<String>,
+}
+
+/// Histórico mensal de um FII, a partir de `Fii::async_monthly`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FiiHistory {
+    pub profile: FiiProfile,
+    pub months: Vec<FiiMonth>,
+}
+
+impl FiiHistory {
+    pub fn from_dataframe(df: &DataFrame) -> Self {
+        let dates = date_column(df, "AS_DATE");
+        let number = |name: &str| float_column(df, name);
+        let net_worth = number("Patrimonio_Liquido");
+        let quotas = number("Cotas_Emitidas");
+        let quota_value = number("Valor_Patrimonial_Cotas");
+        let dividend_yield = number("Percentual_Dividend_Yield_Mes");
+        let effective_return = number("Percentual_Rentabilidade_Efetiva_Mes");
+        let patrimonial_return = number("Percentual_Rentabilidade_Patrimonial_Mes");
+        let amortization = number("Percentual_Amortizacao_Cotas_Mes");
+        let shareholders = number("Total_Numero_Cotistas");
+
+        let mut months: Vec<FiiMonth> = dates
+            .into_iter()
+            .enumerate()
+            .filter_map(|(row, date)| {
+                Some(FiiMonth {
+                    date: date?,
+                    net_worth: net_worth[row],
+                    quotas: quotas[row],
+                    quota_value: quota_value[row],
+                    dividend_yield: dividend_yield[row],
+                    effective_return: effective_return[row],
+                    patrimonial_return: patrimonial_return[row],
+                    amortization: amortization[row],
+                    shareholders: shareholders[row],
+                })
+            })
+            .collect();
+        months.sort_by_key(|month| month.date);
+
+        // Perfil do último informe
+        let text = |name: &str| text_column(df, name).pop().flatten();
+        let profile = FiiProfile {
+            name: text("Nome_Fundo"),
+            segment: text("Segmento_Atuacao"),
+            mandate: text("Mandato"),
+            management: text("Tipo_Gestao"),
+            target_audience: text("Publico_Alvo"),
+        };
+
+        FiiHistory { profile, months }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.months.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&FiiMonth> {
+        self.months.last()
+    }
+
+    /// Dividend yield somado nos últimos 12 informes.
+    pub fn dividend_yield_12m(&self) -> Option<f64> {
+        let yields: Vec<f64> = self
+            .months
+            .iter()
+            .rev()
+            .take(12)
+            .filter_map(|month| month.dividend_yield)
+            .collect();
+        (!yields.is_empty()).then(|| yields.iter().sum())
+    }
+
+    /// Rendimentos estimados distribuídos no período.
+    pub fn total_distribution(&self) -> f64 {
+        self.months.iter().filter_map(FiiMonth::distribution).sum()
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::Datelike;
+use polars::frame::DataFrame;
+
+use super::{date_series, TimeSeries};
+
+/// Patrimônio, captação líquida e cotistas de um fundo, a partir de `Informe::async_flows`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Flows {
+    pub net_worth: TimeSeries,
+    /// Captações menos resgates de cada dia.
+    pub net_inflows: TimeSeries,
+    /// Captação líquida somada por mês (ano, mês).
+    pub monthly_net_inflows: Vec<((i32, u32), f64)>,
+    pub shareholders: TimeSeries,
+}
+
+impl Flows {
+    pub fn from_dataframe(df: &DataFrame) -> Self {
+        let net_inflows = date_series(df, "AS_DATE", "CAPTC_LIQ");
+
+        let mut monthly_net_inflows: Vec<((i32, u32), f64)> = Vec::new();
+        for (date, value) in &net_inflows {
+            let key = (date.year(), date.month());
+            match monthly_net_inflows.last_mut() {
+                Some((month, total)) if *month == key => *total += value,
+                _ => monthly_net_inflows.push((key, *value)),
+            }
+        }
+
+        Flows {
+            net_worth: date_series(df, "AS_DATE", "VL_PATRIM_LIQ"),
+            net_inflows,
+            monthly_net_inflows,
+            shareholders: date_series(df, "AS_DATE", "NR_COTST"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.net_worth.is_empty() && self.shareholders.is_empty()
+    }
+
+    /// Captação líquida acumulada no período.
+    pub fn total_net_inflow(&self) -> f64 {
+        self.net_inflows.iter().map(|(_, value)| value).sum()
+    }
+
+    /// Primeiro e último valor de uma série, para a variação no período.
+    pub fn first_last(series: &TimeSeries) -> Option<(f64, f64)> {
+        Some((series.first()?.1, series.last()?.1))
+    }
+}
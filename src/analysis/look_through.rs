@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use super::portfolio::Position;
+
+/// Por que as cotas de um fundo investido não foram abertas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Skip {
+    /// O fundo já aparece no caminho desde o fundo consultado.
+    Cycle,
+    /// A profundidade máxima foi atingida.
+    MaxDepth,
+    /// O fundo não entregou carteira no mês ou não informou patrimônio.
+    NotFound,
+}
+
+impl Skip {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Skip::Cycle => "Ciclo",
+            Skip::MaxDepth => "Profundidade máxima",
+            Skip::NotFound => "Carteira não encontrada",
+        }
+    }
+}
+
+/// Posição final da carteira consolidada, em R$ proporcionais ao fundo consultado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exposure {
+    pub kind: String,
+    pub issuer: String,
+    pub value: f64,
+    /// Nível em que a posição foi encontrada (0 = carteira do próprio fundo).
+    pub depth: u32,
+}
+
+/// Fundo investido cujas cotas não foram abertas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skipped {
+    pub cnpj: String,
+    pub name: String,
+    pub value: f64,
+    pub reason: Skip,
+}
+
+/// Carteira de um fundo com as cotas de outros fundos substituídas pelas carteiras
+/// desses fundos no mesmo mês, escaladas pelo peso da posição.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LookThrough {
+    pub cnpj: String,
+    pub net_worth: f64,
+    pub exposures: Vec<Exposure>,
+    /// Fundos investidos abertos, com o valor investido (em R$ do fundo consultado).
+    pub resolved: Vec<(String, f64)>,
+    pub skipped: Vec<Skipped>,
+}
+
+impl LookThrough {
+    /// Abre recursivamente as cotas de fundos até `max_depth` níveis. `fetch` devolve as
+    /// posições e o patrimônio líquido de um fundo no mês, ou `None` se não houver carteira.
+    pub fn resolve(
+        cnpj: &str,
+        max_depth: u32,
+        mut fetch: impl FnMut(&str) -> Option<(Vec<Position>, f64)>,
+    ) -> Option<Self> {
+        let (positions, net_worth) = fetch(cnpj)?;
+        let mut look = LookThrough {
+            cnpj: cnpj.to_string(),
+            net_worth,
+            ..Default::default()
+        };
+
+        // Carteiras pendentes: posições, fator para R$ do fundo consultado e caminho
+        let mut pending = vec![(positions, 1.0, vec![cnpj.to_string()])];
+        while let Some((positions, factor, path)) = pending.pop() {
+            let depth = path.len() as u32 - 1;
+            for position in positions {
+                let value = position.value * factor;
+                let skip = match &position.fund {
+                    None => None,
+                    Some(fund) if path.contains(fund) => Some(Skip::Cycle),
+                    Some(_) if depth >= max_depth => Some(Skip::MaxDepth),
+                    Some(fund) => match fetch(fund) {
+                        Some((children, pl)) if pl > 0.0 => {
+                            look.resolved.push((fund.clone(), value));
+                            let mut path = path.clone();
+                            path.push(fund.clone());
+                            pending.push((children, value / pl, path));
+                            continue;
+                        }
+                        _ => Some(Skip::NotFound),
+                    },
+                };
+                if let (Some(reason), Some(fund)) = (skip, &position.fund) {
+                    look.skipped.push(Skipped {
+                        cnpj: fund.clone(),
+                        name: position.asset.clone(),
+                        value,
+                        reason,
+                    });
+                }
+                look.exposures.push(Exposure {
+                    issuer: position.issuer_or_asset().to_string(),
+                    kind: position.kind,
+                    value,
+                    depth,
+                });
+            }
+        }
+        Some(look)
+    }
+
+    /// Exposição consolidada por tipo de aplicação, da maior para a menor.
+    pub fn by_kind(&self) -> Vec<(String, f64)> {
+        group(&self.exposures, |e| &e.kind)
+    }
+
+    /// Exposição consolidada por emissor, da maior para a menor.
+    pub fn by_issuer(&self) -> Vec<(String, f64)> {
+        group(&self.exposures, |e| &e.issuer)
+    }
+}
+
+fn group<'a>(
+    exposures: &'a [Exposure],
+    key: impl Fn(&'a Exposure) -> &'a str,
+) -> Vec<(String, f64)> {
+    let mut totals: HashMap<&str, f64> = HashMap::new();
+    for exposure in exposures {
+        *totals.entry(key(exposure)).or_default() += exposure.value;
+    }
+    let mut groups: Vec<(String, f64)> = totals
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    groups.sort_by(|a, b| b.1.total_cmp(&a.1));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn position(kind: &str, asset: &str, fund: Option<&str>, value: f64) -> Position {
+        Position {
+            date: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            kind: kind.to_string(),
+            asset: asset.to_string(),
+            issuer: None,
+            issuer_document: None,
+            maturity: None,
+            related_party: false,
+            fund: fund.map(str::to_string),
+            value,
+        }
+    }
+
+    fn quota(fund: &str, value: f64) -> Position {
+        position("Cotas de Fundos", fund, Some(fund), value)
+    }
+
+    fn resolve(
+        cnpj: &str,
+        max_depth: u32,
+        portfolios: &HashMap<&str, (Vec<Position>, f64)>,
+    ) -> LookThrough {
+        LookThrough::resolve(cnpj, max_depth, |fund| portfolios.get(fund).cloned()).unwrap()
+    }
+
+    #[test]
+    fn cycle_is_skipped() {
+        let portfolios = HashMap::from([
+            ("A", (vec![quota("B", 100.0)], 100.0)),
+            ("B", (vec![quota("A", 50.0)], 50.0)),
+        ]);
+        let look = resolve("A", 5, &portfolios);
+
+        assert_eq!(look.resolved, vec![("B".to_string(), 100.0)]);
+        assert_eq!(look.skipped.len(), 1);
+        assert_eq!(look.skipped[0].cnpj, "A");
+        assert_eq!(look.skipped[0].reason, Skip::Cycle);
+        assert_eq!(look.skipped[0].value, 100.0);
+    }
+
+    #[test]
+    fn chain_deeper_than_max_depth_is_skipped() {
+        let portfolios = HashMap::from([
+            ("A", (vec![quota("B", 100.0)], 100.0)),
+            ("B", (vec![quota("C", 100.0)], 100.0)),
+            ("C", (vec![quota("D", 100.0)], 100.0)),
+            (
+                "D",
+                (
+                    vec![position("Títulos Públicos", "LFT", None, 100.0)],
+                    100.0,
+                ),
+            ),
+        ]);
+        let look = resolve("A", 2, &portfolios);
+
+        let resolved: Vec<&str> = look.resolved.iter().map(|(c, _)| c.as_str()).collect();
+        assert_eq!(resolved, vec!["B", "C"]);
+        assert_eq!(look.skipped.len(), 1);
+        assert_eq!(look.skipped[0].cnpj, "D");
+        assert_eq!(look.skipped[0].reason, Skip::MaxDepth);
+        assert_eq!(look.exposures.len(), 1);
+        assert_eq!(look.exposures[0].depth, 2);
+    }
+
+    #[test]
+    fn weights_scale_by_net_worth() {
+        // A tem 300 de PL, 100 em cotas de B (25% do PL de B) e 200 em títulos
+        let portfolios = HashMap::from([
+            (
+                "A",
+                (
+                    vec![
+                        quota("B", 100.0),
+                        position("Títulos Públicos", "LTN", None, 200.0),
+                    ],
+                    300.0,
+                ),
+            ),
+            (
+                "B",
+                (
+                    vec![
+                        position("Títulos Públicos", "LTN", None, 240.0),
+                        position("Ações", "PETR4", None, 160.0),
+                    ],
+                    400.0,
+                ),
+            ),
+        ]);
+        let look = resolve("A", 5, &portfolios);
+
+        assert!(look.skipped.is_empty());
+        assert_eq!(look.net_worth, 300.0);
+        assert_eq!(
+            look.by_kind(),
+            vec![
+                ("Títulos Públicos".to_string(), 260.0),
+                ("Ações".to_string(), 40.0),
+            ]
+        );
+        let stocks = look.exposures.iter().find(|e| e.kind == "Ações").unwrap();
+        assert_eq!(stocks.depth, 1);
+        assert_eq!(stocks.value, 40.0);
+    }
+}
@@ -0,0 +1,291 @@
+use chrono::NaiveDate;
+
+use super::{
+    align_rates, compound, daily_returns, mean, monthly_compound, monthly_returns, std_dev,
+    TimeSeries, BUSINESS_DAYS,
+};
+
+/// Indicadores de desempenho de um fundo no período, comparados ao CDI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Retorno acumulado no período.
+    pub total_return: f64,
+    pub annualized_return: f64,
+    pub annualized_volatility: Option<f64>,
+    pub sharpe: Option<f64>,
+    pub sortino: Option<f64>,
+    pub max_drawdown: Option<Drawdown>,
+    /// Retorno acumulado do CDI nos mesmos dias da série do fundo.
+    pub cdi_return: f64,
+    /// Retorno do fundo como percentual do CDI (ex.: 1.1 = 110% do CDI).
+    pub percent_of_cdi: Option<f64>,
+    pub best_month: Option<MonthReturn>,
+    pub worst_month: Option<MonthReturn>,
+    /// Fração dos meses em que o fundo superou o CDI.
+    pub hit_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drawdown {
+    /// Queda em relação ao pico (valor negativo).
+    pub depth: f64,
+    pub start: NaiveDate,
+    pub trough: NaiveDate,
+    /// Primeiro dia em que a cota voltou ao pico, se recuperou.
+    pub recovery: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonthReturn {
+    pub year: i32,
+    pub month: u32,
+    pub value: f64,
+}
+
+impl Metrics {
+    /// Calcula os indicadores a partir da série de cotas e das taxas diárias do CDI.
+    /// Retorna `None` se houver menos de duas cotas.
+    pub fn compute(quotas: &[(NaiveDate, f64)], cdi: &[(NaiveDate, f64)]) -> Option<Self> {
+        if quotas.len() < 2 {
+            return None;
+        }
+        let (start_date, first) = quotas[0];
+        let (end_date, last) = quotas[quotas.len() - 1];
+
+        let returns = daily_returns(quotas);
+        let values: Vec<f64> = returns.iter().map(|(_, r)| *r).collect();
+        let cdi_rates = align_rates(&returns, cdi);
+        let excess: Vec<f64> = values
+            .iter()
+            .zip(cdi_rates.iter())
+            .map(|(r, c)| r - c)
+            .collect();
+
+        let total_return = last / first - 1.0;
+        let annualized_return =
+            (1.0 + total_return).powf(BUSINESS_DAYS / values.len() as f64) - 1.0;
+        let annualized_volatility = std_dev(&values).map(|sd| sd * BUSINESS_DAYS.sqrt());
+
+        let excess_mean = mean(&excess).unwrap_or(0.0);
+        let sharpe = std_dev(&excess)
+            .filter(|sd| *sd > 0.0)
+            .map(|sd| excess_mean / sd * BUSINESS_DAYS.sqrt());
+        let downside = downside_deviation(&excess);
+        let sortino = downside
+            .filter(|dd| *dd > 0.0)
+            .map(|dd| excess_mean / dd * BUSINESS_DAYS.sqrt());
+
+        let cdi_return = compound(&cdi_rates);
+        let percent_of_cdi = if cdi_return > 0.0 {
+            Some(total_return / cdi_return)
+        } else {
+            None
+        };
+
+        let months = monthly_returns(quotas);
+        let month_return =
+            |((year, month), value): ((i32, u32), f64)| MonthReturn { year, month, value };
+        let best_month = months
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(month_return);
+        let worst_month = months
+            .iter()
+            .copied()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(month_return);
+
+        let cdi_months = monthly_compound(&returns, cdi);
+        let compared: Vec<bool> = months
+            .iter()
+            .filter_map(|(key, value)| cdi_months.get(key).map(|cdi| value > cdi))
+            .collect();
+        let hit_ratio = if compared.is_empty() {
+            None
+        } else {
+            Some(compared.iter().filter(|hit| **hit).count() as f64 / compared.len() as f64)
+        };
+
+        Some(Metrics {
+            start_date,
+            end_date,
+            total_return,
+            annualized_return,
+            annualized_volatility,
+            sharpe,
+            sortino,
+            max_drawdown: max_drawdown(quotas),
+            cdi_return,
+            percent_of_cdi,
+            best_month,
+            worst_month,
+            hit_ratio,
+        })
+    }
+}
+
+/// Maior queda da série, com as datas do pico, do fundo e da recuperação.
+pub fn max_drawdown(values: &[(NaiveDate, f64)]) -> Option<Drawdown> {
+    let mut peak = *values.first()?;
+    let mut worst: Option<(Drawdown, f64)> = None;
+
+    for &(date, value) in values {
+        if value >= peak.1 {
+            peak = (date, value);
+            continue;
+        }
+        let depth = value / peak.1 - 1.0;
+        if worst.map_or(true, |(dd, _)| depth < dd.depth) {
+            let drawdown = Drawdown {
+                depth,
+                start: peak.0,
+                trough: date,
+                recovery: None,
+            };
+            worst = Some((drawdown, peak.1));
+        }
+    }
+
+    let (mut drawdown, peak_value) = worst?;
+    drawdown.recovery = values
+        .iter()
+        .find(|(date, value)| *date > drawdown.trough && *value >= peak_value)
+        .map(|(date, _)| *date);
+    Some(drawdown)
+}
+
+/// Queda de cada ponto em relação ao maior valor anterior (0 nos picos).
+pub fn underwater(values: &[(NaiveDate, f64)]) -> TimeSeries {
+    let mut peak = f64::MIN;
+    values
+        .iter()
+        .filter(|(_, value)| *value > 0.0)
+        .map(|&(date, value)| {
+            peak = peak.max(value);
+            (date, value / peak - 1.0)
+        })
+        .collect()
+}
+
+fn downside_deviation(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let sum = values.iter().map(|v| v.min(0.0).powi(2)).sum::<f64>();
+    Some((sum / values.len() as f64).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn computes_returns_volatility_and_drawdown() {
+        let quotas = [
+            (date(2024, 1, 2), 1.00),
+            (date(2024, 1, 3), 1.01),
+            (date(2024, 1, 4), 0.99),
+            (date(2024, 1, 5), 1.02),
+        ];
+        let cdi: Vec<_> = quotas.iter().map(|(d, _)| (*d, 0.0004)).collect();
+        let metrics = Metrics::compute(&quotas, &cdi).unwrap();
+
+        assert_eq!(metrics.start_date, date(2024, 1, 2));
+        assert_eq!(metrics.end_date, date(2024, 1, 5));
+        assert!(close(metrics.total_return, 0.02));
+        // Três retornos diários anualizados em 252 dias úteis
+        assert!(close(
+            metrics.annualized_return,
+            1.02_f64.powf(252.0 / 3.0) - 1.0
+        ));
+
+        let returns = [0.01, 0.99 / 1.01 - 1.0, 1.02 / 0.99 - 1.0];
+        let mean = returns.iter().sum::<f64>() / 3.0;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 2.0;
+        assert!(close(
+            metrics.annualized_volatility.unwrap(),
+            variance.sqrt() * 252.0_f64.sqrt()
+        ));
+
+        // O primeiro dia da série não tem retorno, então o CDI conta três dias
+        assert!(close(metrics.cdi_return, 1.0004_f64.powi(3) - 1.0));
+        assert!(close(
+            metrics.percent_of_cdi.unwrap(),
+            0.02 / (1.0004_f64.powi(3) - 1.0)
+        ));
+
+        let drawdown = metrics.max_drawdown.unwrap();
+        assert!(close(drawdown.depth, 0.99 / 1.01 - 1.0));
+        assert_eq!(drawdown.start, date(2024, 1, 3));
+        assert_eq!(drawdown.trough, date(2024, 1, 4));
+        assert_eq!(drawdown.recovery, Some(date(2024, 1, 5)));
+    }
+
+    #[test]
+    fn needs_two_quotas() {
+        assert_eq!(Metrics::compute(&[(date(2024, 1, 2), 1.0)], &[]), None);
+    }
+
+    #[test]
+    fn partial_months_compare_only_the_fund_days() {
+        // Fundo começa no fim de janeiro e o período termina no início de fevereiro
+        let quotas = [
+            (date(2024, 1, 30), 1.000),
+            (date(2024, 1, 31), 1.002),
+            (date(2024, 2, 1), 1.004),
+        ];
+        // CDI de janeiro e fevereiro inteiros: no mês completo ele superaria o fundo
+        let cdi: Vec<_> = date(2024, 1, 1)
+            .iter_days()
+            .take_while(|d| *d <= date(2024, 2, 29))
+            .map(|d| (d, 0.001))
+            .collect();
+        let metrics = Metrics::compute(&quotas, &cdi).unwrap();
+
+        assert_eq!(metrics.hit_ratio, Some(1.0));
+    }
+
+    #[test]
+    fn drawdown_without_recovery() {
+        let values = [
+            (date(2024, 1, 2), 1.0),
+            (date(2024, 1, 3), 1.2),
+            (date(2024, 1, 4), 0.9),
+            (date(2024, 1, 5), 1.1),
+        ];
+        let drawdown = max_drawdown(&values).unwrap();
+        assert!(close(drawdown.depth, 0.9 / 1.2 - 1.0));
+        assert_eq!(drawdown.start, date(2024, 1, 3));
+        assert_eq!(drawdown.trough, date(2024, 1, 4));
+        assert_eq!(drawdown.recovery, None);
+
+        let rising = [(date(2024, 1, 2), 1.0), (date(2024, 1, 3), 1.1)];
+        assert_eq!(max_drawdown(&rising), None);
+    }
+
+    #[test]
+    fn deviations() {
+        assert!(close(
+            std_dev(&[1.0, 2.0, 3.0, 4.0]).unwrap(),
+            (5.0_f64 / 3.0).sqrt()
+        ));
+        assert_eq!(std_dev(&[1.0]), None);
+        // Só as perdas contam, divididas pelo total de observações
+        assert!(close(
+            downside_deviation(&[0.01, -0.02, 0.03, -0.01]).unwrap(),
+            (0.0005_f64 / 4.0).sqrt()
+        ));
+        assert_eq!(downside_deviation(&[]), None);
+    }
+}
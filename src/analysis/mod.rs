@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+use polars::frame::DataFrame;
+
+pub mod compare;
+pub mod credit;
+pub mod fidc;
+pub mod fii;
+pub mod flows;
+pub mod look_through;
+pub mod metrics;
+pub mod portfolio;
+pub mod returns;
+pub mod rolling;
+pub mod screener;
+
+/// Série temporal ordenada por data.
+pub type TimeSeries = Vec<(NaiveDate, f64)>;
+
+/// Dias úteis em um ano, usados para anualizar retornos e volatilidade.
+pub const BUSINESS_DAYS: f64 = 252.0;
+
+/// Extrai a série de cotas (`AS_DATE`, `valor_float`) de `Informe::async_profit`.
+pub fn quotas(df: &DataFrame) -> TimeSeries {
+    date_series(df, "AS_DATE", "valor_float")
+        .into_iter()
+        .filter(|(_, quota)| *quota > 0.0)
+        .collect()
+}
+
+/// Extrai o fator acumulado (`AS_DATE`, `CUMULATIVE_PRODUCT`) de `Informe::async_profit`.
+pub fn cumulative_product(df: &DataFrame) -> TimeSeries {
+    date_series(df, "AS_DATE", "CUMULATIVE_PRODUCT")
+}
+
+/// Converte o retorno acumulado em % (`as_date`, `value`) de um índice em fator acumulado.
+pub fn benchmark_growth(df: &DataFrame) -> TimeSeries {
+    date_series(df, "as_date", "value")
+        .into_iter()
+        .map(|(date, value)| (date, 1.0 + value / 100.0))
+        .collect()
+}
+
+/// Extrai o patrimônio líquido (`AS_DATE`, `VL_PATRIM_LIQ`) de `Informe::async_profit`.
+pub fn net_worth(df: &DataFrame) -> TimeSeries {
+    date_series(df, "AS_DATE", "VL_PATRIM_LIQ")
+}
+
+/// Extrai a taxa diária do CDI (`as_date`, `retorno`) de `indices::sgs::async_cdi`.
+pub fn cdi_rates(df: &DataFrame) -> TimeSeries {
+    date_series(df, "as_date", "retorno")
+}
+
+fn date_series(df: &DataFrame, date_column: &str, value_column: &str) -> TimeSeries {
+    let (Ok(dates), Ok(values)) = (df.column(date_column), df.column(value_column)) else {
+        return Vec::new();
+    };
+    let (Ok(dates), Ok(values)) = (dates.date(), values.f64()) else {
+        return Vec::new();
+    };
+
+    let mut series: TimeSeries = dates
+        .as_date_iter()
+        .zip(values)
+        .filter_map(|(date, value)| Some((date?, value?)))
+        .collect();
+    series.sort_by_key(|(date, _)| *date);
+    series
+}
+
+/// Textos da coluna sem espaços nas pontas; vazios, ausentes ou coluna inexistente
+/// viram `None`.
+pub(crate) fn text_column(df: &DataFrame, name: &str) -> Vec<Option<String>> {
+    match df.column(name).ok().and_then(|c| c.utf8().ok()) {
+        Some(values) => values
+            .into_iter()
+            .map(|v| {
+                v.map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string)
+            })
+            .collect(),
+        None => vec![None; df.height()],
+    }
+}
+
+/// Valores da coluna, ou só `None` se ela não existir ou não for `Float64`.
+pub(crate) fn float_column(df: &DataFrame, name: &str) -> Vec<Option<f64>> {
+    match df.column(name).ok().and_then(|c| c.f64().ok()) {
+        Some(values) => values.into_iter().collect(),
+        None => vec![None; df.height()],
+    }
+}
+
+/// Datas da coluna, ou só `None` se ela não existir ou não for `Date`.
+pub(crate) fn date_column(df: &DataFrame, name: &str) -> Vec<Option<NaiveDate>> {
+    match df.column(name).ok().and_then(|c| c.date().ok()) {
+        Some(values) => values.as_date_iter().collect(),
+        None => vec![None; df.height()],
+    }
+}
+
+/// Retornos diários `q[t] / q[t-1] - 1`, datados pelo dia do retorno.
+pub fn daily_returns(quotas: &[(NaiveDate, f64)]) -> TimeSeries {
+    quotas
+        .windows(2)
+        .map(|w| (w[1].0, w[1].1 / w[0].1 - 1.0))
+        .collect()
+}
+
+/// Compõe uma série de taxas diárias em um retorno acumulado no período.
+pub fn compound<'a>(rates: impl IntoIterator<Item = &'a f64>) -> f64 {
+    rates.into_iter().fold(1.0, |acc, rate| acc * (1.0 + rate)) - 1.0
+}
+
+/// Taxa do benchmark para cada dia da série do fundo (0 quando não houver dado).
+pub fn align_rates(days: &[(NaiveDate, f64)], rates: &[(NaiveDate, f64)]) -> Vec<f64> {
+    let by_date: HashMap<NaiveDate, f64> = rates.iter().copied().collect();
+    days.iter()
+        .map(|(date, _)| by_date.get(date).copied().unwrap_or(0.0))
+        .collect()
+}
+
+/// Retorno de cada mês a partir da última cota do mês anterior.
+/// O primeiro mês usa a primeira cota disponível como base.
+pub fn monthly_returns(quotas: &[(NaiveDate, f64)]) -> Vec<((i32, u32), f64)> {
+    let mut months: Vec<((i32, u32), f64)> = Vec::new();
+    let Some(&(_, first)) = quotas.first() else {
+        return months;
+    };
+
+    let mut base = first;
+    let mut last: Option<((i32, u32), f64)> = None;
+    for &(date, quota) in quotas {
+        let key = (date.year(), date.month());
+        match last {
+            Some((month, close)) if month != key => {
+                months.push((month, close / base - 1.0));
+                base = close;
+            }
+            _ => {}
+        }
+        last = Some((key, quota));
+    }
+    if let Some((month, close)) = last {
+        months.push((month, close / base - 1.0));
+    }
+    months
+}
+
+/// Retorno de cada mês de uma série de taxas diárias, composto só nos dias de `days`
+/// (os retornos diários do fundo). Assim um mês parcial do fundo, no início ou no fim
+/// do período, é comparado aos mesmos dias do índice, como em `align_rates`.
+pub fn monthly_compound(
+    days: &[(NaiveDate, f64)],
+    rates: &[(NaiveDate, f64)],
+) -> HashMap<(i32, u32), f64> {
+    let by_date: HashMap<NaiveDate, f64> = rates.iter().copied().collect();
+    let mut months: HashMap<(i32, u32), f64> = HashMap::new();
+    for (date, _) in days {
+        let Some(rate) = by_date.get(date) else {
+            continue;
+        };
+        let acc = months.entry((date.year(), date.month())).or_insert(1.0);
+        *acc *= 1.0 + rate;
+    }
+    months.values_mut().for_each(|acc| *acc -= 1.0);
+    months
+}
+
+pub(crate) fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Desvio padrão amostral.
+pub(crate) fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values)?;
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use polars::frame::DataFrame;
+
+use super::{date_column, float_column, text_column};
+
+/// Diferença relativa mínima para considerar que uma posição mudou de tamanho.
+pub const RESIZE_THRESHOLD: f64 = 0.01;
+
+/// Colunas que identificam o ativo, em ordem de preferência.
+const ASSET_COLUMNS: [&str; 6] = [
+    "NM_FUNDO_COTA",
+    "DS_ATIVO",
+    "CD_ATIVO",
+    "TP_TITPUB",
+    "CD_SELIC",
+    "CD_ISIN",
+];
+
+/// Posição de uma carteira da CDA (`Portfolio::async_assets`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub date: NaiveDate,
+    /// Tipo de aplicação (`TP_APLIC`).
+    pub kind: String,
+    pub asset: String,
+    pub issuer: Option<String>,
+    /// CPF ou CNPJ do emissor (`CPF_CNPJ_EMISSOR`).
+    pub issuer_document: Option<String>,
+    pub maturity: Option<NaiveDate>,
+    /// Emissor ligado ao gestor ou administrador (`EMISSOR_LIGADO`).
+    pub related_party: bool,
+    /// CNPJ do fundo investido, nas posições em cotas de fundos (`CNPJ_FUNDO_COTA`).
+    pub fund: Option<String>,
+    pub value: f64,
+}
+
+impl Position {
+    /// Lê as posições do DataFrame de aplicações, ignorando linhas sem data ou valor.
+    pub fn from_dataframe(df: &DataFrame) -> Vec<Position> {
+        let text = |name: &str| text_column(df, name);
+        let kinds = text("TP_APLIC");
+        let issuers = text("EMISSOR");
+        let documents = text("CPF_CNPJ_EMISSOR");
+        let related = text("EMISSOR_LIGADO");
+        let funds = text("CNPJ_FUNDO_COTA");
+        let maturities = date_column(df, "DT_VENC");
+        let assets: Vec<Vec<Option<String>>> = ASSET_COLUMNS.iter().map(|c| text(c)).collect();
+        let dates = date_column(df, "DT_COMPTC");
+        let values = float_column(df, "VL_MERC_POS_FINAL");
+
+        (0..df.height())
+            .filter_map(|row| {
+                let kind = kinds[row].clone().unwrap_or_else(|| "Outros".to_string());
+                let mut asset = assets
+                    .iter()
+                    .find_map(|column| column[row].clone())
+                    .unwrap_or_else(|| kind.clone());
+                if let Some(maturity) = maturities[row] {
+                    asset = format!("{} {}", asset, maturity.format("%d/%m/%Y"));
+                }
+                Some(Position {
+                    date: dates[row]?,
+                    kind,
+                    asset,
+                    issuer: issuers[row].clone(),
+                    issuer_document: documents[row].clone(),
+                    maturity: maturities[row],
+                    related_party: related[row].as_deref() == Some("S"),
+                    fund: funds[row].clone(),
+                    value: values[row]?,
+                })
+            })
+            .collect()
+    }
+
+    /// Emissor da posição; sem emissor informado, o próprio ativo.
+    pub fn issuer_or_asset(&self) -> &str {
+        self.issuer.as_deref().unwrap_or(&self.asset)
+    }
+}
+
+/// Como as posições são agrupadas na evolução da carteira.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    Kind,
+    Issuer,
+    Asset,
+}
+
+impl Grouping {
+    pub const ALL: [Grouping; 3] = [Grouping::Kind, Grouping::Issuer, Grouping::Asset];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Grouping::Kind => "Tipo de aplicação",
+            Grouping::Issuer => "Emissor",
+            Grouping::Asset => "Ativo",
+        }
+    }
+
+    fn key<'a>(&self, position: &'a Position) -> &'a str {
+        match self {
+            Grouping::Kind => &position.kind,
+            Grouping::Issuer => position.issuer_or_asset(),
+            Grouping::Asset => &position.asset,
+        }
+    }
+}
+
+/// Participação (%) de cada grupo no total da carteira, mês a mês.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evolution {
+    pub months: Vec<NaiveDate>,
+    /// Grupos em ordem decrescente de participação média, com um valor por mês.
+    pub groups: Vec<(String, Vec<f64>)>,
+}
+
+impl Evolution {
+    /// Agrupa as posições por mês; além dos `top` maiores grupos, o restante é somado
+    /// em "Outros".
+    pub fn compute(positions: &[Position], grouping: Grouping, top: usize) -> Self {
+        let mut totals: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        let mut by_group: HashMap<&str, BTreeMap<NaiveDate, f64>> = HashMap::new();
+        for position in positions {
+            *totals.entry(position.date).or_default() += position.value;
+            *by_group
+                .entry(grouping.key(position))
+                .or_default()
+                .entry(position.date)
+                .or_default() += position.value;
+        }
+
+        let months: Vec<NaiveDate> = totals.keys().copied().collect();
+        let mut groups: Vec<(String, Vec<f64>)> = by_group
+            .into_iter()
+            .map(|(name, values)| {
+                let shares = months
+                    .iter()
+                    .map(|month| {
+                        let total = totals[month];
+                        match values.get(month) {
+                            Some(value) if total != 0.0 => value / total * 100.0,
+                            _ => 0.0,
+                        }
+                    })
+                    .collect();
+                (name.to_string(), shares)
+            })
+            .collect();
+        let average = |values: &[f64]| values.iter().sum::<f64>() / values.len().max(1) as f64;
+        groups.sort_by(|a, b| average(&b.1).total_cmp(&average(&a.1)));
+
+        if groups.len() > top + 1 {
+            let others = groups.split_off(top);
+            let mut sum = vec![0.0; months.len()];
+            for (_, values) in &others {
+                sum.iter_mut().zip(values).for_each(|(acc, v)| *acc += v);
+            }
+            groups.push(("Outros".to_string(), sum));
+        }
+
+        Evolution { months, groups }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    Resized,
+}
+
+impl Change {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Change::Added => "Incluída",
+            Change::Removed => "Zerada",
+            Change::Resized => "Alterada",
+        }
+    }
+}
+
+/// Variação de uma posição entre dois meses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionChange {
+    pub kind: String,
+    pub asset: String,
+    pub before: f64,
+    pub after: f64,
+    pub change: Change,
+}
+
+/// Posições incluídas, zeradas e alteradas (acima de `RESIZE_THRESHOLD`) de `from`
+/// para `to`, ordenadas pela variação absoluta.
+pub fn diff(positions: &[Position], from: NaiveDate, to: NaiveDate) -> Vec<PositionChange> {
+    let month = |date: NaiveDate| {
+        let mut values: HashMap<(&str, &str), f64> = HashMap::new();
+        for p in positions.iter().filter(|p| p.date == date) {
+            *values.entry((&p.kind, &p.asset)).or_default() += p.value;
+        }
+        values
+    };
+    let before = month(from);
+    let after = month(to);
+
+    let mut changes: Vec<PositionChange> = Vec::new();
+    for (&(kind, asset), &old) in &before {
+        let new = after.get(&(kind, asset)).copied();
+        // Sem valor anterior não há variação relativa: só muda se passou a ter valor
+        let resized = |new: f64| {
+            if old == 0.0 {
+                new != 0.0
+            } else {
+                (new / old - 1.0).abs() >= RESIZE_THRESHOLD
+            }
+        };
+        let change = match new {
+            None => Some(Change::Removed),
+            Some(new) if resized(new) => Some(Change::Resized),
+            Some(_) => None,
+        };
+        if let Some(change) = change {
+            changes.push(PositionChange {
+                kind: kind.to_string(),
+                asset: asset.to_string(),
+                before: old,
+                after: new.unwrap_or(0.0),
+                change,
+            });
+        }
+    }
+    for (&(kind, asset), &new) in &after {
+        if !before.contains_key(&(kind, asset)) {
+            changes.push(PositionChange {
+                kind: kind.to_string(),
+                asset: asset.to_string(),
+                before: 0.0,
+                after: new,
+                change: Change::Added,
+            });
+        }
+    }
+    changes.sort_by(|a, b| {
+        (b.after - b.before)
+            .abs()
+            .total_cmp(&(a.after - a.before).abs())
+    });
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(m: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, m, 28).unwrap()
+    }
+
+    fn position(month: u32, kind: &str, asset: &str, value: f64) -> Position {
+        Position {
+            date: date(month),
+            kind: kind.to_string(),
+            asset: asset.to_string(),
+            issuer: None,
+            issuer_document: None,
+            maturity: None,
+            related_party: false,
+            fund: None,
+            value,
+        }
+    }
+
+    #[test]
+    fn folds_groups_beyond_top_into_others() {
+        let positions = [
+            position(1, "A", "a", 40.0),
+            position(1, "B", "b", 30.0),
+            position(1, "C", "c", 20.0),
+            position(1, "D", "d", 10.0),
+            position(2, "A", "a", 50.0),
+            position(2, "C", "c", 50.0),
+        ];
+        let evolution = Evolution::compute(&positions, Grouping::Kind, 2);
+
+        assert_eq!(evolution.months, [date(1), date(2)]);
+        let names: Vec<_> = evolution.groups.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["A", "C", "Outros"]);
+        assert_eq!(evolution.groups[0].1, [40.0, 50.0]);
+        assert_eq!(evolution.groups[1].1, [20.0, 50.0]);
+        // B e D somados; D não existe no segundo mês
+        assert_eq!(evolution.groups[2].1, [40.0, 0.0]);
+    }
+
+    #[test]
+    fn single_remaining_group_is_not_folded() {
+        let positions = [
+            position(1, "A", "a", 60.0),
+            position(1, "B", "b", 30.0),
+            position(1, "C", "c", 10.0),
+        ];
+        let evolution = Evolution::compute(&positions, Grouping::Kind, 2);
+
+        let names: Vec<_> = evolution.groups.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn diff_reports_changes_from_the_threshold() {
+        let positions = [
+            position(1, "Títulos Públicos", "kept", 100.0),
+            position(1, "Títulos Públicos", "resized", 100.0),
+            position(1, "Títulos Públicos", "removed", 30.0),
+            position(1, "Títulos Públicos", "zero", 0.0),
+            position(1, "Títulos Públicos", "filled", 0.0),
+            position(
+                2,
+                "Títulos Públicos",
+                "kept",
+                100.0 * (1.0 + RESIZE_THRESHOLD / 2.0),
+            ),
+            position(
+                2,
+                "Títulos Públicos",
+                "resized",
+                100.0 * (1.0 + RESIZE_THRESHOLD),
+            ),
+            position(2, "Títulos Públicos", "zero", 0.0),
+            position(2, "Títulos Públicos", "filled", 5.0),
+            position(2, "Títulos Públicos", "added", 20.0),
+        ];
+        let changes = diff(&positions, date(1), date(2));
+
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.asset.as_str(), c.change))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("removed", Change::Removed),
+                ("added", Change::Added),
+                ("filled", Change::Resized),
+                ("resized", Change::Resized),
+            ]
+        );
+        assert_eq!(changes[0].after, 0.0);
+        assert_eq!(changes[1].before, 0.0);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use polars::{
+    error::PolarsError,
+    frame::DataFrame,
+    prelude::{NamedFrom, Series},
+};
+
+use super::{daily_returns, monthly_compound, monthly_returns};
+
+/// Retorno de um mês (ou período) do fundo e do CDI no mesmo intervalo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub fund: f64,
+    pub cdi: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearRow {
+    pub year: i32,
+    /// Janeiro a dezembro; `None` para meses sem cotas no período.
+    pub months: [Option<Cell>; 12],
+    /// Acumulado no ano.
+    pub ytd: Option<Cell>,
+    /// Acumulado nos 12 meses encerrados no último mês disponível do ano.
+    pub twelve_months: Option<Cell>,
+}
+
+/// Matriz de rentabilidade mensal: linhas = anos, colunas = meses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReturnsTable {
+    pub years: Vec<YearRow>,
+}
+
+impl ReturnsTable {
+    pub fn compute(quotas: &[(NaiveDate, f64)], cdi: &[(NaiveDate, f64)]) -> Self {
+        let fund_months: BTreeMap<(i32, u32), f64> = monthly_returns(quotas).into_iter().collect();
+        let cdi_months: HashMap<(i32, u32), f64> = monthly_compound(&daily_returns(quotas), cdi);
+        let cell = |key: &(i32, u32), fund: f64| Cell {
+            fund,
+            cdi: cdi_months.get(key).copied(),
+        };
+
+        let mut years: BTreeMap<i32, YearRow> = BTreeMap::new();
+        for (key, value) in &fund_months {
+            let row = years.entry(key.0).or_insert_with(|| YearRow {
+                year: key.0,
+                months: [None; 12],
+                ytd: None,
+                twelve_months: None,
+            });
+            row.months[key.1 as usize - 1] = Some(cell(key, *value));
+        }
+
+        for row in years.values_mut() {
+            let months: Vec<Cell> = row.months.iter().flatten().copied().collect();
+            row.ytd = compound_cells(&months);
+
+            let Some(last) = row.months.iter().rposition(Option::is_some) else {
+                continue;
+            };
+            let window: Vec<Cell> = (0..12)
+                .rev()
+                .map(|back| shift_month((row.year, last as u32 + 1), back))
+                .filter_map(|key| fund_months.get(&key).map(|value| cell(&key, *value)))
+                .collect();
+            if window.len() == 12 {
+                row.twelve_months = compound_cells(&window);
+            }
+        }
+
+        ReturnsTable {
+            years: years.into_values().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.years.is_empty()
+    }
+
+    /// Maior retorno mensal em módulo, usado para a escala de cores.
+    pub fn max_abs_month(&self) -> f64 {
+        self.years
+            .iter()
+            .flat_map(|row| row.months.iter().flatten())
+            .map(|cell| cell.fund.abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Uma linha por ano e colunas `<MÊS>_FUNDO`/`<MÊS>_CDI` (em %), para exportação.
+    pub fn to_dataframe(&self) -> Result<DataFrame, PolarsError> {
+        let mut columns = vec![Series::new(
+            "ANO",
+            self.years.iter().map(|row| row.year).collect::<Vec<_>>(),
+        )];
+        let mut push = |name: &str, cells: Vec<Option<Cell>>| {
+            let fund: Vec<Option<f64>> = cells.iter().map(|c| c.map(|c| c.fund * 100.0)).collect();
+            let cdi: Vec<Option<f64>> = cells
+                .iter()
+                .map(|c| c.and_then(|c| c.cdi).map(|v| v * 100.0))
+                .collect();
+            columns.push(Series::new(&format!("{}_FUNDO", name), fund));
+            columns.push(Series::new(&format!("{}_CDI", name), cdi));
+        };
+
+        for (i, name) in MONTHS.iter().enumerate() {
+            push(name, self.years.iter().map(|row| row.months[i]).collect());
+        }
+        push("ANO", self.years.iter().map(|row| row.ytd).collect());
+        push(
+            "12M",
+            self.years.iter().map(|row| row.twelve_months).collect(),
+        );
+
+        DataFrame::new(columns)
+    }
+}
+
+pub const MONTHS: [&str; 12] = [
+    "JAN", "FEV", "MAR", "ABR", "MAI", "JUN", "JUL", "AGO", "SET", "OUT", "NOV", "DEZ",
+];
+
+fn compound_cells(cells: &[Cell]) -> Option<Cell> {
+    if cells.is_empty() {
+        return None;
+    }
+    let fund = cells.iter().fold(1.0, |acc, c| acc * (1.0 + c.fund)) - 1.0;
+    let cdi = cells
+        .iter()
+        .map(|c| c.cdi)
+        .try_fold(1.0, |acc, c| c.map(|c| acc * (1.0 + c)))
+        .map(|acc| acc - 1.0);
+    Some(Cell { fund, cdi })
+}
+
+fn shift_month((year, month): (i32, u32), back: u32) -> (i32, u32) {
+    let index = year * 12 + month as i32 - 1 - back as i32;
+    (index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Months;
+
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    /// Cotas no último dia de cada mês, de dezembro/2022 a fevereiro/2024, rendendo 1%
+    /// ao mês.
+    fn month_ends() -> Vec<(NaiveDate, f64)> {
+        let mut first = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let mut quotas = Vec::new();
+        let mut quota = 1.0;
+        while first <= NaiveDate::from_ymd_opt(2024, 3, 1).unwrap() {
+            quotas.push((first.pred_opt().unwrap(), quota));
+            quota *= 1.01;
+            first = first.checked_add_months(Months::new(1)).unwrap();
+        }
+        quotas
+    }
+
+    #[test]
+    fn shifts_months_across_years() {
+        assert_eq!(shift_month((2024, 2), 0), (2024, 2));
+        assert_eq!(shift_month((2024, 2), 2), (2023, 12));
+        assert_eq!(shift_month((2024, 1), 13), (2022, 12));
+        assert_eq!(shift_month((2024, 12), 11), (2024, 1));
+    }
+
+    #[test]
+    fn year_to_date_and_twelve_months() {
+        let quotas = month_ends();
+        let cdi: Vec<_> = quotas.iter().map(|(d, _)| (*d, 0.001)).collect();
+        let table = ReturnsTable::compute(&quotas, &cdi);
+
+        let years: Vec<_> = table.years.iter().map(|row| row.year).collect();
+        assert_eq!(years, [2022, 2023, 2024]);
+
+        // Dezembro/2022 só tem a cota base: retorno zero e sem dias para o CDI
+        let first = &table.years[0];
+        assert_eq!(
+            first.months[11],
+            Some(Cell {
+                fund: 0.0,
+                cdi: None
+            })
+        );
+        assert_eq!(first.ytd.unwrap().cdi, None);
+        assert_eq!(first.twelve_months, None);
+
+        let full = &table.years[1];
+        let ytd = full.ytd.unwrap();
+        assert!(close(ytd.fund, 1.01_f64.powi(12) - 1.0));
+        assert!(close(ytd.cdi.unwrap(), 1.001_f64.powi(12) - 1.0));
+        assert_eq!(full.twelve_months, full.ytd);
+
+        // Os 12 meses de 2024 vão de março/2023 a fevereiro/2024
+        let partial = &table.years[2];
+        assert!(partial.months[2..].iter().all(Option::is_none));
+        assert!(close(partial.ytd.unwrap().fund, 1.01_f64.powi(2) - 1.0));
+        let twelve = partial.twelve_months.unwrap();
+        assert!(close(twelve.fund, 1.01_f64.powi(12) - 1.0));
+        assert!(close(twelve.cdi.unwrap(), 1.001_f64.powi(12) - 1.0));
+    }
+}
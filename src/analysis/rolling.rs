@@ -0,0 +1,134 @@
+use chrono::{Months, NaiveDate};
+
+use super::{align_rates, compound, daily_returns, mean, std_dev, TimeSeries, BUSINESS_DAYS};
+
+/// Janelas disponíveis, em meses.
+pub const WINDOWS: [u32; 3] = [12, 24, 36];
+
+/// Indicadores em janela móvel, datados pelo último dia de cada janela.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rolling {
+    pub months: u32,
+    /// Retorno do fundo na janela.
+    pub returns: TimeSeries,
+    /// Retorno do CDI nos mesmos dias da janela.
+    pub cdi_returns: TimeSeries,
+    /// Volatilidade anualizada dos retornos diários da janela.
+    pub volatility: TimeSeries,
+    /// Sharpe anualizado do excesso de retorno sobre o CDI na janela.
+    pub sharpe: TimeSeries,
+}
+
+impl Rolling {
+    /// Calcula os indicadores para cada dia com histórico suficiente para uma janela
+    /// completa de `months` meses. A base de cada janela é a última cota até o dia
+    /// que abre a janela.
+    pub fn compute(quotas: &[(NaiveDate, f64)], cdi: &[(NaiveDate, f64)], months: u32) -> Self {
+        let mut rolling = Rolling {
+            months,
+            ..Default::default()
+        };
+        let returns = daily_returns(quotas);
+        let cdi_rates = align_rates(&returns, cdi);
+
+        // `base` aponta para a cota que abre a janela; os retornos da janela são
+        // `returns[base..end]`, pois `returns[i]` é o retorno de `quotas[i + 1]`
+        let mut base = 0;
+        for end in 1..quotas.len() {
+            let (date, quota) = quotas[end];
+            let Some(opening) = date.checked_sub_months(Months::new(months)) else {
+                continue;
+            };
+            if quotas[0].0 > opening {
+                continue;
+            }
+            while base + 1 < end && quotas[base + 1].0 <= opening {
+                base += 1;
+            }
+
+            let values: Vec<f64> = returns[base..end].iter().map(|(_, r)| *r).collect();
+            let cdi = &cdi_rates[base..end];
+            let excess: Vec<f64> = values.iter().zip(cdi).map(|(r, c)| r - c).collect();
+
+            rolling.returns.push((date, quota / quotas[base].1 - 1.0));
+            rolling.cdi_returns.push((date, compound(cdi)));
+            if let Some(sd) = std_dev(&values) {
+                rolling.volatility.push((date, sd * BUSINESS_DAYS.sqrt()));
+            }
+            if let Some(sd) = std_dev(&excess).filter(|sd| *sd > 0.0) {
+                let excess_mean = mean(&excess).unwrap_or(0.0);
+                rolling
+                    .sharpe
+                    .push((date, excess_mean / sd * BUSINESS_DAYS.sqrt()));
+            }
+        }
+        rolling
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn windows_start_at_the_last_quota_before_opening() {
+        let quotas = [
+            (date(2024, 1, 10), 1.00),
+            (date(2024, 1, 20), 1.01),
+            (date(2024, 2, 9), 1.02),
+            (date(2024, 2, 10), 1.03),
+            (date(2024, 2, 15), 1.04),
+            (date(2024, 2, 25), 1.05),
+        ];
+        let cdi: Vec<_> = quotas.iter().map(|(d, _)| (*d, 0.001)).collect();
+        let rolling = Rolling::compute(&quotas, &cdi, 1);
+
+        // 09/02 abre em 09/01, antes da primeira cota; 10/02 abre exatamente nela
+        let dates: Vec<_> = rolling.returns.iter().map(|(d, _)| *d).collect();
+        assert_eq!(
+            dates,
+            [date(2024, 2, 10), date(2024, 2, 15), date(2024, 2, 25)]
+        );
+
+        assert!(close(rolling.returns[0].1, 0.03));
+        assert!(close(rolling.returns[1].1, 0.04));
+        // A janela de 25/02 abre em 25/01 e tem como base a cota de 20/01
+        assert!(close(rolling.returns[2].1, 1.05 / 1.01 - 1.0));
+
+        assert!(close(rolling.cdi_returns[0].1, 1.001_f64.powi(3) - 1.0));
+        assert!(close(rolling.cdi_returns[2].1, 1.001_f64.powi(4) - 1.0));
+        assert_eq!(rolling.volatility.len(), 3);
+        assert_eq!(rolling.months, 1);
+    }
+
+    #[test]
+    fn short_history_has_no_windows() {
+        let quotas = [(date(2024, 1, 10), 1.00), (date(2024, 2, 9), 1.01)];
+        let rolling = Rolling::compute(&quotas, &[], 1);
+
+        assert!(rolling.returns.is_empty());
+        assert!(rolling.volatility.is_empty());
+        assert!(rolling.sharpe.is_empty());
+    }
+
+    #[test]
+    fn single_return_window_has_no_volatility() {
+        let quotas = [(date(2024, 2, 29), 1.00), (date(2024, 3, 31), 1.02)];
+        let rolling = Rolling::compute(&quotas, &[], 1);
+
+        // 31/03 menos um mês cai em 29/02
+        assert_eq!(rolling.returns.len(), 1);
+        assert!(close(rolling.returns[0].1, 0.02));
+        assert!(close(rolling.cdi_returns[0].1, 0.0));
+        assert!(rolling.volatility.is_empty());
+        assert!(rolling.sharpe.is_empty());
+    }
+}
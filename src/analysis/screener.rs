@@ -0,0 +1,240 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use chrono::Datelike;
+use polars::frame::DataFrame;
+use serde::{Deserialize, Serialize};
+
+use super::{float_column, text_column};
+use crate::provider::cvm::fund::{index::fold, AnbimaClass, Class, Fund, Participant, Situation};
+
+/// Fundo do cadastro com os indicadores calculados dos informes diários.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenerRow {
+    pub fund: Fund,
+    /// Rentabilidade nos últimos 12 meses, em %.
+    pub return_12m: Option<f64>,
+    pub net_worth: Option<f64>,
+    pub shareholders: Option<f64>,
+}
+
+impl ScreenerRow {
+    /// Junta o cadastro ao resumo de `Informe::async_summary` pelo CNPJ; fundos sem
+    /// informe no período ficam sem indicadores.
+    pub fn join(funds: Vec<Fund>, summary: &DataFrame) -> Vec<ScreenerRow> {
+        let cnpjs = text_column(summary, "CNPJ_FUNDO");
+        let returns = float_column(summary, "RETURN_12M");
+        let net_worths = float_column(summary, "VL_PATRIM_LIQ");
+        let shareholders = float_column(summary, "NR_COTST");
+        let by_cnpj: HashMap<&str, usize> = cnpjs
+            .iter()
+            .enumerate()
+            .filter_map(|(row, cnpj)| cnpj.as_deref().map(|c| (c, row)))
+            .collect();
+
+        funds
+            .into_iter()
+            .map(|fund| {
+                let row = by_cnpj.get(fund.cnpj.as_str()).copied();
+                ScreenerRow {
+                    return_12m: row.and_then(|r| returns[r]),
+                    net_worth: row.and_then(|r| net_worths[r]),
+                    shareholders: row.and_then(|r| shareholders[r]),
+                    fund,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Intervalo de valores; limites em branco não restringem.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Range {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl Range {
+    /// Sem valor, o fundo só passa se nenhum limite estiver definido.
+    fn contains(&self, value: Option<f64>) -> bool {
+        match value {
+            Some(v) => {
+                self.min.map_or(true, |min| v >= min) && self.max.map_or(true, |max| v <= max)
+            }
+            None => self.min.is_none() && self.max.is_none(),
+        }
+    }
+}
+
+/// Filtro do screener sobre uma coluna do cadastro ou um indicador calculado. Nos
+/// filtros de escolha, `None` não restringe.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Filter {
+    Situation(Option<Situation>),
+    Class(Option<Class>),
+    AnbimaClass(Option<AnbimaClass>),
+    Condominium(Option<String>),
+    TargetAudience(Option<String>),
+    /// Fundos sem a informação contam como não exclusivos.
+    Exclusive(Option<bool>),
+    /// Parte do nome do gestor, sem diferenciar acentos e maiúsculas.
+    Gestor(String),
+    Admin(String),
+    AdminFee(Range),
+    PerformanceFee(Range),
+    /// Ano de constituição.
+    Constitution(Range),
+    Return12m(Range),
+    NetWorth(Range),
+    Shareholders(Range),
+}
+
+impl Filter {
+    /// Um filtro de cada tipo, na ordem exibida no menu.
+    pub fn all() -> Vec<Filter> {
+        vec![
+            Filter::Situation(None),
+            Filter::Class(None),
+            Filter::AnbimaClass(None),
+            Filter::Condominium(None),
+            Filter::TargetAudience(None),
+            Filter::Exclusive(None),
+            Filter::Gestor(String::new()),
+            Filter::Admin(String::new()),
+            Filter::AdminFee(Range::default()),
+            Filter::PerformanceFee(Range::default()),
+            Filter::Constitution(Range::default()),
+            Filter::Return12m(Range::default()),
+            Filter::NetWorth(Range::default()),
+            Filter::Shareholders(Range::default()),
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Filter::Situation(_) => "Situação",
+            Filter::Class(_) => "Classe",
+            Filter::AnbimaClass(_) => "Classe ANBIMA",
+            Filter::Condominium(_) => "Condomínio",
+            Filter::TargetAudience(_) => "Público-alvo",
+            Filter::Exclusive(_) => "Exclusivo",
+            Filter::Gestor(_) => "Gestor",
+            Filter::Admin(_) => "Administrador",
+            Filter::AdminFee(_) => "Taxa de administração (%)",
+            Filter::PerformanceFee(_) => "Taxa de performance (%)",
+            Filter::Constitution(_) => "Ano de constituição",
+            Filter::Return12m(_) => "Rentabilidade 12 meses (%)",
+            Filter::NetWorth(_) => "Patrimônio líquido",
+            Filter::Shareholders(_) => "Cotistas",
+        }
+    }
+
+    /// Valor do cadastro comparado pelos filtros de escolha entre valores existentes.
+    pub fn choice<'a>(&self, fund: &'a Fund) -> Option<&'a str> {
+        match self {
+            Filter::Situation(_) => fund.situation.as_ref().map(Situation::as_str),
+            Filter::Class(_) => fund.class.as_ref().map(Class::as_str),
+            Filter::AnbimaClass(_) => fund.anbima_class.as_ref().map(AnbimaClass::as_str),
+            Filter::Condominium(_) => fund.condominium.as_deref(),
+            Filter::TargetAudience(_) => fund.target_audience.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn matches(&self, row: &ScreenerRow) -> bool {
+        let fund = &row.fund;
+        let contains = |participant: &Option<Participant>, text: &str| {
+            text.trim().is_empty()
+                || participant
+                    .as_ref()
+                    .is_some_and(|p| fold(&p.name).contains(&fold(text.trim())))
+        };
+        match self {
+            Filter::Situation(value) => chosen(value, &fund.situation),
+            Filter::Class(value) => chosen(value, &fund.class),
+            Filter::AnbimaClass(value) => chosen(value, &fund.anbima_class),
+            Filter::Condominium(value) => chosen(value, &fund.condominium),
+            Filter::TargetAudience(value) => chosen(value, &fund.target_audience),
+            Filter::Exclusive(value) => chosen(value, &Some(fund.exclusive.unwrap_or(false))),
+            Filter::Gestor(text) => contains(&fund.gestor, text),
+            Filter::Admin(text) => contains(&fund.admin, text),
+            Filter::AdminFee(range) => range.contains(fund.admin_fee),
+            Filter::PerformanceFee(range) => range.contains(fund.performance_fee),
+            Filter::Constitution(range) => {
+                range.contains(fund.constitution_date.map(|d| d.year() as f64))
+            }
+            Filter::Return12m(range) => range.contains(row.return_12m),
+            Filter::NetWorth(range) => range.contains(row.net_worth),
+            Filter::Shareholders(range) => range.contains(row.shareholders),
+        }
+    }
+}
+
+fn chosen<T: PartialEq>(expected: &Option<T>, value: &Option<T>) -> bool {
+    expected.is_none() || expected == value
+}
+
+/// Coluna de ordenação dos resultados.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortKey {
+    #[default]
+    Name,
+    AdminFee,
+    Constitution,
+    Return12m,
+    NetWorth,
+    Shareholders,
+}
+
+impl SortKey {
+    /// Compara duas linhas; valores ausentes ficam no fim em qualquer direção.
+    fn compare(&self, a: &ScreenerRow, b: &ScreenerRow, descending: bool) -> Ordering {
+        let direction = |ordering: Ordering| {
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+        let number = |value: fn(&ScreenerRow) -> Option<f64>| match (value(a), value(b)) {
+            (Some(x), Some(y)) => direction(x.total_cmp(&y)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        match self {
+            SortKey::Name => direction(a.fund.denomination.cmp(&b.fund.denomination)),
+            SortKey::AdminFee => number(|r| r.fund.admin_fee),
+            SortKey::Constitution => number(|r| {
+                r.fund
+                    .constitution_date
+                    .map(|d| d.num_days_from_ce() as f64)
+            }),
+            SortKey::Return12m => number(|r| r.return_12m),
+            SortKey::NetWorth => number(|r| r.net_worth),
+            SortKey::Shareholders => number(|r| r.shareholders),
+        }
+    }
+}
+
+/// Conjunto nomeado de filtros e ordenação, que pode ser salvo e reaplicado.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Screen {
+    pub name: String,
+    pub filters: Vec<Filter>,
+    pub sort: SortKey,
+    pub descending: bool,
+}
+
+impl Screen {
+    /// Índices das linhas que passam em todos os filtros, na ordem escolhida.
+    pub fn apply(&self, rows: &[ScreenerRow]) -> Vec<usize> {
+        let mut selected: Vec<usize> = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| self.filters.iter().all(|f| f.matches(row)))
+            .map(|(index, _)| index)
+            .collect();
+        selected.sort_by(|&a, &b| self.sort.compare(&rows[a], &rows[b], self.descending));
+        selected
+    }
+}
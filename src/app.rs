@@ -1,10 +1,20 @@
-use crate::{provider::cvm::fund, util};
+use crate::{
+    analysis::{self, compare::FundSeries, screener::ScreenerRow},
+    catalog,
+    downloader::{self, DownloadState},
+    provider::cvm::fund::{self, Fund},
+    util,
+};
 
+use chrono::NaiveDate;
 use egui::FontId;
 use egui_dock::{DockArea, DockState, NodeIndex, Style, TabAddAlign};
 use egui_toast::{Toast, ToastOptions};
-use polars::frame::DataFrame;
-use std::collections::HashMap;
+use polars::{
+    frame::DataFrame,
+    prelude::{CsvWriter, SerWriter},
+};
+use std::{collections::HashMap, fs::File};
 use tokio::sync::mpsc::{self, UnboundedSender};
 use tokio::time::{timeout, Duration};
 use tokio_util::sync::CancellationToken;
@@ -13,13 +23,26 @@ use crate::{
     history::History,
     message::Message,
     provider::{
-        cvm::{fund::Register, informe::Informe, portfolio::Portfolio},
-        indices::{self},
+        cvm::{
+            fidc::Fidc,
+            fii::Fii,
+            fund::{Filters, Register},
+            informe::Informe,
+            portfolio::Portfolio,
+            registry::Registry,
+        },
+        indices::{
+            self,
+            benchmark::{self, BenchmarkSeries},
+        },
     },
     ui::{
         fund::{
             modal::{asset::AssetDetail, search::Search},
-            tab::{dashboard::DashboardTab, FundTab},
+            tab::{
+                compare::CompareTab, dashboard::DashboardTab, fidc::FidcTab, fii::FiiTab,
+                screener::ScreenerTab, FundTab,
+            },
         },
         modal::about::About,
         tabs::{home_tab::HomeTab, Tab, TabType, TabViewer},
@@ -44,14 +67,25 @@ pub struct TemplateApp {
     #[serde(skip)]
     register: Register,
     #[serde(skip)]
+    registry: Registry,
+    #[serde(skip)]
     informe: Informe,
     #[serde(skip)]
     portfolio: Portfolio,
     #[serde(skip)]
+    fii: Fii,
+    #[serde(skip)]
+    fidc: Fidc,
+    /// Índices de referência configurados, lidos uma vez na abertura.
+    #[serde(skip)]
+    benchmarks: Vec<benchmark::Benchmark>,
+    #[serde(skip)]
     downloads: HashMap<String, CancellationToken>,
     #[serde(skip)]
     search: Search,
     pub open_logs: bool,
+    /// Usa somente os dados em cache, sem consultar as fontes.
+    pub offline: bool,
     #[serde(skip)]
     asset_detail_modal: AssetDetail,
     open_list_tab: bool,
@@ -66,7 +100,10 @@ pub struct TemplateApp {
     pub status: String,
 
     #[serde(skip)]
-    downloading: bool,
+    pub downloading: bool,
+
+    #[serde(skip)]
+    pub download_states: Vec<(String, DownloadState)>,
 }
 
 impl Default for TemplateApp {
@@ -100,9 +137,14 @@ impl Default for TemplateApp {
             channel,
             history,
             register,
+            registry: Registry::new(),
             informe,
             portfolio,
+            fii: Fii::new(),
+            fidc: Fidc::new(),
+            benchmarks: handle_result("índices", benchmark::options::load()),
             open_logs: false,
+            offline: catalog::is_offline(),
             downloads: HashMap::new(),
             search,
             asset_detail_modal: AssetDetail {
@@ -114,6 +156,7 @@ impl Default for TemplateApp {
             started_watch: false,
             status: String::from(""),
             downloading: false,
+            download_states: Vec::new(),
         }
     }
 }
@@ -129,83 +172,141 @@ impl TemplateApp {
         Default::default()
     }
 
-    pub fn add_tab(&mut self, cnpj: String, df: DataFrame) {
-        let tabs: Vec<_> = self
-            .tree
-            .iter_all_tabs()
-            .map(|(_, tab)| tab.to_owned())
-            .collect();
-
-        if let Some(index) = tabs
-            .iter()
-            .position(|tb| tb.title().text().contains(&cnpj.clone()))
-        {
-            let main_surface = self.tree.main_surface_mut();
-            main_surface.set_active_tab(NodeIndex(0), egui_dock::TabIndex(index));
-        } else {
-            let main_surface = self.tree.main_surface_mut();
-            main_surface.set_focused_node(egui_dock::NodeIndex(2));
-            let new_fund_tab = FundTab::new(cnpj.clone(), df.clone(), self.channel.0.clone());
-            main_surface.push_to_focused_leaf(TabType::Fund(new_fund_tab));
+    /// Ativa a aba aberta que satisfaz `is_open` ou, se não houver, adiciona a criada
+    /// por `create` ao nó principal.
+    fn focus_or_push(
+        &mut self,
+        is_open: impl Fn(&TabType) -> bool,
+        create: impl FnOnce() -> TabType,
+    ) {
+        let index = self.tree.iter_all_tabs().position(|(_, tab)| is_open(tab));
+        let main_surface = self.tree.main_surface_mut();
+        match index {
+            Some(index) => {
+                main_surface.set_active_tab(NodeIndex(0), egui_dock::TabIndex(index));
+            }
+            None => {
+                main_surface.set_focused_node(egui_dock::NodeIndex(2));
+                main_surface.push_to_focused_leaf(create());
+            }
         }
+    }
 
-        if let Ok(s) = df.column("DENOM_SOCIAL") {
-            let value = s.get(0).unwrap();
-            let name = value.get_str().unwrap();
-            self.history.add(cnpj.clone(), name.to_string());
-            let _ = self.history.save();
-            let _ = self.history.load();
-        }
+    pub fn add_tab(&mut self, fund: Fund, df: DataFrame) {
+        let cnpj = fund.cnpj.clone();
+
+        self.history.add(cnpj.clone(), fund.denomination.clone());
+        let _ = self.history.save();
+        let _ = self.history.load();
+
+        let sender = self.channel.0.clone();
+        let limits = self.portfolio.limits();
+        let benchmarks = self.benchmarks.clone();
+        self.focus_or_push(
+            |tb| matches!(tb, TabType::Fund(_)) && tb.title().text() == cnpj,
+            || TabType::Fund(FundTab::new(fund, df, limits, benchmarks, sender)),
+        );
     }
 
     pub fn add_dashboard_tab(&mut self) {
-        let tabs: Vec<_> = self
-            .tree
-            .iter_all_tabs()
-            .map(|(_, tab)| tab.to_owned())
-            .collect();
-
-        if let Some(index) = tabs
-            .iter()
-            .position(|tb| tb.title().text().contains("Dashboard"))
-        {
-            let main_surface = self.tree.main_surface_mut();
-            main_surface.set_active_tab(NodeIndex(0), egui_dock::TabIndex(index));
-        } else {
-            let main_surface = self.tree.main_surface_mut();
-            main_surface.set_focused_node(egui_dock::NodeIndex(2));
-            let dash_tab = DashboardTab {
-                title: "Dashboard".to_string(),
-                by_year: DataFrame::empty(),
-                by_situation: DataFrame::empty(),
-                by_class: DataFrame::empty(),
-            };
-            main_surface.push_to_focused_leaf(TabType::Dashboard(dash_tab));
-        }
+        self.focus_or_push(
+            |tb| tb.title().text().contains("Dashboard"),
+            || {
+                TabType::Dashboard(DashboardTab {
+                    title: "Dashboard".to_string(),
+                    by_year: DataFrame::empty(),
+                    by_situation: DataFrame::empty(),
+                    by_class: DataFrame::empty(),
+                })
+            },
+        );
+    }
+
+    pub fn add_compare_tab(&mut self) {
+        let sender = self.channel.0.clone();
+        let history = self.history.clone();
+        self.focus_or_push(
+            |tb| matches!(tb, TabType::Compare(_)),
+            || TabType::Compare(CompareTab::new("Comparar".to_string(), sender, history)),
+        );
+    }
+
+    pub fn add_screener_tab(&mut self) {
+        let sender = self.channel.0.clone();
+        self.focus_or_push(
+            |tb| matches!(tb, TabType::Screener(_)),
+            || {
+                let mut screener_tab = ScreenerTab::new("Screener".to_string(), sender);
+                screener_tab.send_screener_message();
+                TabType::Screener(screener_tab)
+            },
+        );
+    }
+
+    pub fn add_fii_tab(&mut self, cnpj: String, name: String) {
+        let title = FiiTab::title_for(&cnpj);
+        let sender = self.channel.0.clone();
+        self.focus_or_push(
+            |tb| matches!(tb, TabType::Fii(_)) && tb.title().text() == title,
+            || {
+                let mut fii_tab = FiiTab::new(cnpj, name, sender);
+                fii_tab.report.send_message();
+                TabType::Fii(fii_tab)
+            },
+        );
+    }
+
+    pub fn add_fidc_tab(&mut self, cnpj: String, name: String) {
+        let title = FidcTab::title_for(&cnpj);
+        let sender = self.channel.0.clone();
+        self.focus_or_push(
+            |tb| matches!(tb, TabType::Fidc(_)) && tb.title().text() == title,
+            || {
+                let mut fidc_tab = FidcTab::new(cnpj, name, sender);
+                fidc_tab.report.send_message();
+                TabType::Fidc(fidc_tab)
+            },
+        );
     }
 
     fn handle_update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let ctxc = ctx.clone();
         let sender = self.channel.0.clone();
 
+        if !self.started_watch {
+            self.started_watch = true;
+            tokio::spawn(downloader::schedule(sender.clone(), ctxc.clone()));
+        }
+
         if let Ok(message) = self.channel.1.try_recv() {
             match message {
                 Message::OpenSearchWindow(value) => {
                     self.search.set_loading(true);
-                    let _ = sender.send(Message::SearchFunds("".to_string(), None));
+                    let _ = sender.send(Message::SearchFunds(
+                        "".to_string(),
+                        self.search.filters.clone(),
+                    ));
                     self.asset_detail_modal.open_window = false;
                     self.search.open(value)
                 }
-                Message::OpenTab(cnpj, df) => {
+                Message::OpenTab(fund, df) => {
                     self.tab_viewer.open_window = false;
                     self.search.open(false);
-                    self.add_tab(cnpj, df);
+                    self.add_tab(*fund, df);
                 }
                 Message::NewTab(cnpj) => {
                     let r = self.register.clone();
+                    let registry = self.registry.clone();
                     tokio::spawn(async move {
-                        if let Err(err) =
-                            handle_fund_data(cnpj.clone(), true, r.clone(), &sender, &ctxc).await
+                        if let Err(err) = handle_fund_data(
+                            cnpj.clone(),
+                            true,
+                            r.clone(),
+                            &registry,
+                            &sender,
+                            &ctxc,
+                        )
+                        .await
                         {
                             log::error!("Erro ao obter dados do fundo {}", err);
                             util::toaster().add(Toast {
@@ -222,9 +323,15 @@ impl TemplateApp {
                                 options: ToastOptions::default().duration_in_seconds(10.0),
                             });
 
-                            if let Err(err) =
-                                handle_fund_data(cnpj.clone(), false, r.clone(), &sender, &ctxc)
-                                    .await
+                            if let Err(err) = handle_fund_data(
+                                cnpj.clone(),
+                                false,
+                                r.clone(),
+                                &registry,
+                                &sender,
+                                &ctxc,
+                            )
+                            .await
                             {
                                 log::error!("Erro ao obter dados do fundo {}", err);
                                 util::toaster().add(Toast {
@@ -237,40 +344,23 @@ impl TemplateApp {
                     });
                 }
 
-                Message::Profit(cnpj, start_date, end_date) => {
+                Message::Profit(cnpj, start_date, end_date, benchmarks) => {
                     let informe = self.informe.clone();
                     let sender_clone = sender.clone();
                     let ctx_clone = ctxc.clone();
 
                     tokio::spawn(async move {
-                        let cdi_future = timeout(
-                            Duration::from_secs(15),
-                            indices::cdi::async_dataframe(start_date, end_date),
-                        );
-                        let ibov_future = timeout(
-                            Duration::from_secs(15),
-                            indices::ibovespa::async_dataframe(start_date, end_date),
-                        );
-                        let profitability_future = timeout(
+                        // Cada índice de referência é buscado em sua própria tarefa
+                        let handles: Vec<_> = benchmarks
+                            .into_iter()
+                            .map(|b| tokio::spawn(fetch_benchmark(b, start_date, end_date)))
+                            .collect();
+
+                        let profitability_result = timeout(
                             Duration::from_secs(15),
                             informe.async_profit(cnpj.clone(), start_date, end_date),
-                        );
-
-                        let (profitability_result, cdi_result, ibov_result) =
-                            tokio::join!(profitability_future, cdi_future, ibov_future);
-
-                        let cdi_dataframe = match cdi_result {
-                            Ok(res) => handle_result("cdi", res),
-                            Err(_) => {
-                                log::error!("Timeout ao obter dados do CDI");
-                                util::toaster().add(Toast {
-                                    kind: egui_toast::ToastKind::Warning,
-                                    text: "Tempo limite atingido ao obter dados do CDI.".into(),
-                                    options: ToastOptions::default().duration_in_seconds(3.0),
-                                });
-                                DataFrame::empty()
-                            }
-                        };
+                        )
+                        .await;
 
                         let profitability_dataframe = match profitability_result {
                             Ok(res) => handle_result("fundo", res),
@@ -286,37 +376,53 @@ impl TemplateApp {
                             }
                         };
 
-                        let ibov_dataframe = match ibov_result {
-                            Ok(res) => handle_result("ibov", res),
-                            Err(_) => {
-                                log::error!("Timeout ao obter dados do IBOV");
-                                util::toaster().add(Toast {
-                                    kind: egui_toast::ToastKind::Warning,
-                                    text: "Tempo limite atingido ao obter dados do IBOV.".into(),
-                                    options: ToastOptions::default().duration_in_seconds(3.0),
-                                });
-                                DataFrame::empty()
+                        let mut series = Vec::new();
+                        for handle in handles {
+                            match handle.await {
+                                Ok(res) => series.extend(res),
+                                Err(err) => log::error!("Falha ao obter índice: {}", err),
                             }
+                        }
+
+                        // Os indicadores usam o CDI mesmo que ele não esteja entre os índices
+                        let cdi_dataframe = match series.iter().find(|s| {
+                            s.benchmark.source == benchmark::Source::Cdi
+                                && s.benchmark.spread.is_none()
+                        }) {
+                            Some(cdi) => cdi.series.clone(),
+                            None => match timeout(
+                                Duration::from_secs(15),
+                                indices::sgs::async_cdi(start_date, end_date),
+                            )
+                            .await
+                            {
+                                Ok(res) => handle_result("cdi", res),
+                                Err(_) => {
+                                    log::error!("Timeout ao obter dados do CDI");
+                                    DataFrame::empty()
+                                }
+                            },
                         };
 
                         let _ = sender_clone.send(Message::ProfitResult(
                             cnpj.clone(),
                             profitability_dataframe,
                             cdi_dataframe,
-                            ibov_dataframe,
+                            series,
                         ));
                         ctx_clone.request_repaint();
                     });
                 }
 
-                Message::ProfitResult(cnpj, df, cdi, ibov) => {
+                Message::ProfitResult(cnpj, df, cdi, benchmarks) => {
                     let tabs: Vec<_> = self.tree.iter_all_tabs_mut().map(|(_, tab)| tab).collect();
                     for tb in tabs {
                         if let TabType::Fund(stb) = tb {
                             if *stb.title().text().to_string() == cnpj {
                                 stb.set_profit_dataframe(df.clone());
                                 stb.set_cdi_dataframe(cdi.clone());
-                                stb.set_ibov_dataframe(ibov.clone());
+                                stb.set_benchmarks(benchmarks.clone());
+                                stb.profit_ui.update_analysis();
                                 stb.set_profit_loading(false);
                                 ctx.request_repaint();
                                 break;
@@ -324,6 +430,267 @@ impl TemplateApp {
                         }
                     }
                 }
+                Message::Benchmark(cnpj, start_date, end_date, b) => {
+                    let sender_clone = sender.clone();
+                    let ctx_clone = ctxc.clone();
+                    tokio::spawn(async move {
+                        let name = b.name.clone();
+                        let series = fetch_benchmark(b, start_date, end_date).await;
+                        let _ = sender_clone.send(Message::BenchmarkResult(
+                            cnpj, start_date, end_date, name, series,
+                        ));
+                        ctx_clone.request_repaint();
+                    });
+                }
+                Message::BenchmarkResult(cnpj, start_date, end_date, name, series) => {
+                    let tabs: Vec<_> = self.tree.iter_all_tabs_mut().map(|(_, tab)| tab).collect();
+                    for tb in tabs {
+                        if let TabType::Fund(stb) = tb {
+                            if *stb.title().text().to_string() == cnpj {
+                                stb.profit_ui
+                                    .add_benchmark((start_date, end_date), &name, series);
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
+                Message::Rolling(cnpj, start_date, end_date, months) => {
+                    let informe = self.informe.clone();
+                    let sender_clone = sender.clone();
+                    let ctx_clone = ctxc.clone();
+
+                    tokio::spawn(async move {
+                        // A primeira janela completa termina no início do período
+                        let history_start = start_date
+                            .checked_sub_months(chrono::Months::new(months))
+                            .unwrap_or(start_date);
+                        let profitability_future = timeout(
+                            Duration::from_secs(60),
+                            informe.async_profit(cnpj.clone(), history_start, end_date),
+                        );
+                        let cdi_future = timeout(
+                            Duration::from_secs(60),
+                            indices::sgs::async_cdi(history_start, end_date),
+                        );
+                        let (profitability_result, cdi_result) =
+                            tokio::join!(profitability_future, cdi_future);
+
+                        let profitability_dataframe = match profitability_result {
+                            Ok(res) => handle_result("janela móvel", res.map(Some)),
+                            Err(_) => {
+                                log::error!("Timeout ao obter histórico da janela móvel");
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao obter histórico do fundo."
+                                        .into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                None
+                            }
+                        };
+                        let cdi_dataframe = match cdi_result {
+                            Ok(res) => handle_result("cdi", res.map(Some)),
+                            Err(_) => {
+                                log::error!("Timeout ao obter dados do CDI");
+                                None
+                            }
+                        };
+
+                        let _ = sender_clone.send(Message::RollingResult(
+                            cnpj,
+                            start_date,
+                            end_date,
+                            months,
+                            profitability_dataframe.zip(cdi_dataframe),
+                        ));
+                        ctx_clone.request_repaint();
+                    });
+                }
+
+                Message::RollingResult(cnpj, start_date, end_date, months, frames) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Fund(stb) = tab {
+                            if *stb.title().text().to_string() == cnpj {
+                                stb.profit_ui
+                                    .set_rolling((start_date, end_date, months), frames.as_ref());
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
+                Message::PortfolioEvolution(cnpj, end_date, months) => {
+                    let portfolio = self.portfolio.clone();
+                    let sender_clone = sender.clone();
+                    let ctx_clone = ctxc.clone();
+
+                    tokio::spawn(async move {
+                        let result = timeout(
+                            Duration::from_secs(60),
+                            portfolio.async_evolution(cnpj.clone(), end_date, months),
+                        )
+                        .await;
+
+                        let frames = match result {
+                            Ok(res) => handle_result("evolução da carteira", res),
+                            Err(_) => {
+                                log::error!("Timeout ao obter evolução da carteira de {}", cnpj);
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao obter evolução da carteira."
+                                        .into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                Vec::new()
+                            }
+                        };
+
+                        let _ = sender_clone.send(Message::PortfolioEvolutionResult(
+                            cnpj, end_date, months, frames,
+                        ));
+                        ctx_clone.request_repaint();
+                    });
+                }
+
+                Message::PortfolioEvolutionResult(cnpj, end_date, months, frames) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Fund(stb) = tab {
+                            if *stb.title().text().to_string() == cnpj {
+                                stb.portfolio_ui
+                                    .evolution
+                                    .set_portfolios((end_date, months), &frames);
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                Message::LookThrough(cnpj, year, month) => {
+                    let portfolio = self.portfolio.clone();
+                    let sender_clone = sender.clone();
+                    let ctx_clone = ctxc.clone();
+
+                    tokio::spawn(async move {
+                        let result = timeout(
+                            Duration::from_secs(60),
+                            portfolio.async_look_through(cnpj.clone(), year.clone(), month.clone()),
+                        )
+                        .await;
+
+                        let look = match result {
+                            Ok(res) => handle_result("look-through", res),
+                            Err(_) => {
+                                log::error!("Timeout ao consolidar a carteira de {}", cnpj);
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao consolidar a carteira.".into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                Default::default()
+                            }
+                        };
+
+                        let _ =
+                            sender_clone.send(Message::LookThroughResult(cnpj, year, month, look));
+                        ctx_clone.request_repaint();
+                    });
+                }
+
+                Message::LookThroughResult(cnpj, year, month, look) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Fund(stb) = tab {
+                            if *stb.title().text().to_string() == cnpj {
+                                stb.portfolio_ui
+                                    .look_through
+                                    .set_look_through((year, month), look);
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                Message::Flows(cnpj, start_date, end_date) => {
+                    let informe = self.informe.clone();
+                    let sender_clone = sender.clone();
+                    let ctx_clone = ctxc.clone();
+
+                    tokio::spawn(async move {
+                        let result = timeout(
+                            Duration::from_secs(30),
+                            informe.async_flows(cnpj.clone(), start_date, end_date),
+                        )
+                        .await;
+
+                        let flows = match result {
+                            Ok(res) => handle_result("patrimônio", res),
+                            Err(_) => {
+                                log::error!("Timeout ao obter patrimônio do fundo {}", cnpj);
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao obter patrimônio do fundo."
+                                        .into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                DataFrame::empty()
+                            }
+                        };
+
+                        let _ = sender_clone.send(Message::FlowsResult(cnpj, flows));
+                        ctx_clone.request_repaint();
+                    });
+                }
+
+                Message::FlowsResult(cnpj, df) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Fund(stb) = tab {
+                            if *stb.title().text().to_string() == cnpj {
+                                stb.flows_ui.set_flows(&df);
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
+                Message::Registry(cnpj) => {
+                    let registry = self.registry.clone();
+                    tokio::spawn(async move {
+                        let result = timeout(
+                            Duration::from_secs(60),
+                            registry.async_find_by_cnpj(cnpj.clone()),
+                        )
+                        .await;
+
+                        let fund = match result {
+                            Ok(res) => handle_result("registro de classes", res),
+                            Err(_) => {
+                                log::error!("Timeout ao obter classes do fundo {}", cnpj);
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao obter classes do fundo.".into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                None
+                            }
+                        };
+
+                        let _ = sender.send(Message::RegistryResult(cnpj, fund.map(Box::new)));
+                        ctxc.request_repaint();
+                    });
+                }
+                Message::RegistryResult(cnpj, fund) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Fund(stb) = tab {
+                            if *stb.title().text().to_string() == cnpj {
+                                stb.classes_ui.set_fund(fund.map(|f| *f));
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
                 Message::Assets(cnpj, year, month) => {
                     let portfolio = self.portfolio.clone();
                     let sender_clone = sender.clone();
@@ -408,6 +775,7 @@ impl TemplateApp {
                                 tab.set_assets_dataframe(assets.clone());
                                 tab.set_top_assets_dataframe(top_assets.clone());
                                 tab.set_pl_dataframe(patrimonio_liquido.clone());
+                                tab.portfolio_ui.update_credit();
                                 tab.set_assets_loading(false);
                                 ctxc.request_repaint();
                                 break;
@@ -419,11 +787,11 @@ impl TemplateApp {
                     self.search.set_loading(false);
                     self.search.set_result(df);
                 }
-                Message::SearchFunds(keyword, class) => {
+                Message::SearchFunds(keyword, filters) => {
                     let keyword = keyword.clone();
                     let r = self.register.clone();
                     tokio::spawn(async move {
-                        let res = r.async_find(Some(keyword), class, None, None).await;
+                        let res = r.async_find(Some(keyword), filters, None).await;
                         match res {
                             Ok(df) => {
                                 let _ = sender.send(Message::ResultFunds(df));
@@ -473,12 +841,326 @@ impl TemplateApp {
                         }
                     }
                 }
+                Message::OpenCompareTab => {
+                    self.add_compare_tab();
+                }
+                Message::Compare(cnpjs, start_date, end_date) => {
+                    let informe = self.informe.clone();
+                    let register = self.register.clone();
+                    tokio::spawn(async move {
+                        let result = timeout(
+                            Duration::from_secs(30),
+                            informe.async_profits(&cnpjs, start_date, end_date),
+                        )
+                        .await;
+
+                        let profits = match result {
+                            Ok(res) => handle_result("comparação", res),
+                            Err(_) => {
+                                log::error!("Timeout ao comparar fundos");
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao comparar fundos.".into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                Vec::new()
+                            }
+                        };
+
+                        let mut funds = Vec::new();
+                        for (cnpj, profit) in profits {
+                            let df = match profit {
+                                Ok(df) => df,
+                                Err(err) => {
+                                    log::error!("Sem dados do fundo {}: {}", cnpj, err);
+                                    util::toaster().add(Toast {
+                                        kind: egui_toast::ToastKind::Warning,
+                                        text: format!("Sem dados do fundo {}", cnpj).into(),
+                                        options: ToastOptions::default().duration_in_seconds(3.0),
+                                    });
+                                    continue;
+                                }
+                            };
+                            let name = register
+                                .async_fund_by_cnpj(cnpj.clone(), true)
+                                .await
+                                .ok()
+                                .flatten()
+                                .map(|fund| fund.denomination)
+                                .unwrap_or_else(|| cnpj.clone());
+                            funds.push(FundSeries {
+                                cnpj,
+                                name,
+                                quotas: analysis::quotas(&df),
+                                net_worth: analysis::net_worth(&df),
+                            });
+                        }
+
+                        let _ = sender.send(Message::CompareResult(funds));
+                        ctxc.request_repaint();
+                    });
+                }
+                Message::CompareResult(funds) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Compare(tab) = tab {
+                            tab.set_funds(funds);
+                            ctx.request_repaint();
+                            break;
+                        }
+                    }
+                }
+                Message::CompareSearch(query) => {
+                    let register = self.register.clone();
+                    tokio::spawn(async move {
+                        let res = register
+                            .async_funds(Some(query.clone()), Filters::default(), Some(8))
+                            .await;
+                        let funds = handle_result("busca de fundos", res);
+                        let _ = sender.send(Message::CompareSearchResult(query, funds));
+                        ctxc.request_repaint();
+                    });
+                }
+                Message::CompareSearchResult(query, funds) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Compare(tab) = tab {
+                            tab.set_suggestions(&query, funds);
+                            ctx.request_repaint();
+                            break;
+                        }
+                    }
+                }
+                Message::OpenFiiTab(cnpj, name) => {
+                    self.add_fii_tab(cnpj, name);
+                }
+                Message::Fii(cnpj, start_date, end_date) => {
+                    let fii = self.fii.clone();
+                    tokio::spawn(async move {
+                        let result = timeout(
+                            Duration::from_secs(60),
+                            fii.async_monthly(cnpj.clone(), start_date, end_date),
+                        )
+                        .await;
+
+                        let monthly = match result {
+                            Ok(res) => handle_result("informe mensal do FII", res),
+                            Err(_) => {
+                                log::error!("Timeout ao obter informe mensal do FII {}", cnpj);
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao obter informe mensal do FII."
+                                        .into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                DataFrame::empty()
+                            }
+                        };
+
+                        let _ = sender.send(Message::FiiResult(cnpj, monthly));
+                        ctxc.request_repaint();
+                    });
+                }
+                Message::FiiResult(cnpj, df) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Fii(tab) = tab {
+                            if tab.report.cnpj == cnpj {
+                                tab.set_monthly(&df);
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
+                Message::OpenFidcTab(cnpj, name) => {
+                    self.add_fidc_tab(cnpj, name);
+                }
+                Message::Fidc(cnpj, start_date, end_date) => {
+                    let fidc = self.fidc.clone();
+                    tokio::spawn(async move {
+                        let result = timeout(
+                            Duration::from_secs(60),
+                            fidc.async_monthly(cnpj.clone(), start_date, end_date),
+                        )
+                        .await;
+
+                        let (monthly, quotas) = match result {
+                            Ok(res) => handle_result("informe mensal do FIDC", res),
+                            Err(_) => {
+                                log::error!("Timeout ao obter informe mensal do FIDC {}", cnpj);
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao obter informe mensal do FIDC."
+                                        .into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                (DataFrame::empty(), DataFrame::empty())
+                            }
+                        };
+
+                        let _ = sender.send(Message::FidcResult(cnpj, monthly, quotas));
+                        ctxc.request_repaint();
+                    });
+                }
+                Message::FidcResult(cnpj, monthly, quotas) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Fidc(tab) = tab {
+                            if tab.report.cnpj == cnpj {
+                                tab.set_monthly(&monthly, &quotas);
+                                ctx.request_repaint();
+                                break;
+                            }
+                        }
+                    }
+                }
+                Message::OpenScreenerTab => {
+                    self.add_screener_tab();
+                }
+                Message::Screener => {
+                    let informe = self.informe.clone();
+                    let register = self.register.clone();
+                    tokio::spawn(async move {
+                        let end_date = chrono::offset::Utc::now().date_naive();
+                        let start_date = end_date - chrono::Duration::days(365);
+                        let register = handle_result("cadastro", register.async_register().await);
+                        let summary = match timeout(
+                            Duration::from_secs(60),
+                            informe.async_summary(start_date, end_date),
+                        )
+                        .await
+                        {
+                            Ok(res) => handle_result("indicadores dos informes", res),
+                            Err(_) => {
+                                log::error!("Timeout ao calcular indicadores do screener");
+                                util::toaster().add(Toast {
+                                    kind: egui_toast::ToastKind::Warning,
+                                    text: "Tempo limite atingido ao calcular indicadores.".into(),
+                                    options: ToastOptions::default().duration_in_seconds(3.0),
+                                });
+                                DataFrame::default()
+                            }
+                        };
+                        let rows = ScreenerRow::join(Fund::iter(&register).collect(), &summary);
+                        let _ = sender.send(Message::ScreenerResult(rows));
+                        ctxc.request_repaint();
+                    });
+                }
+                Message::ScreenerResult(rows) => {
+                    for (_, tab) in self.tree.iter_all_tabs_mut() {
+                        if let TabType::Screener(tab) = tab {
+                            tab.set_rows(rows);
+                            ctx.request_repaint();
+                            break;
+                        }
+                    }
+                }
+                Message::ExportCsv(file_name, mut df) => {
+                    let ctx_clone = ctxc.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let path = util::export_path(&file_name);
+                        let result =
+                            File::create(&path)
+                                .map_err(|err| err.to_string())
+                                .and_then(|file| {
+                                    CsvWriter::new(file)
+                                        .has_header(true)
+                                        .with_delimiter(b';')
+                                        .finish(&mut df)
+                                        .map_err(|err| err.to_string())
+                                });
+
+                        let (kind, text) = match result {
+                            Ok(_) => (
+                                egui_toast::ToastKind::Success,
+                                format!("Arquivo exportado para {}", path.display()),
+                            ),
+                            Err(err) => {
+                                log::error!("Erro ao exportar {}: {}", path.display(), err);
+                                (
+                                    egui_toast::ToastKind::Error,
+                                    format!("Erro ao exportar {}", file_name),
+                                )
+                            }
+                        };
+                        util::toaster().add(Toast {
+                            kind,
+                            text: text.into(),
+                            options: ToastOptions::default().duration_in_seconds(3.0),
+                        });
+                        ctx_clone.request_repaint();
+                    });
+                }
                 Message::StartDownload => {
-                    if !self.downloading {
-                        tokio::spawn(async move {});
+                    if catalog::is_offline() {
+                        log::info!("Modo offline: sincronização ignorada");
+                    } else if !self.downloading {
+                        let jobs = downloader::jobs();
+                        self.downloads = jobs
+                            .iter()
+                            .map(|job| (job.name.clone(), CancellationToken::new()))
+                            .collect();
+                        self.download_states = jobs
+                            .iter()
+                            .map(|job| (job.name.clone(), DownloadState::Queued))
+                            .collect();
                         self.downloading = true;
+                        tokio::spawn(downloader::run(jobs, self.downloads.clone(), sender, ctxc));
+                    }
+                }
+                Message::DownloadProgress(name, state) => {
+                    self.status = match &state {
+                        DownloadState::Downloading { bytes, total } => match total {
+                            Some(total) => format!(
+                                "{} ({} de {})",
+                                name,
+                                util::format_bytes(*bytes),
+                                util::format_bytes(*total)
+                            ),
+                            None => format!("{} ({})", name, util::format_bytes(*bytes)),
+                        },
+                        DownloadState::Processing => format!("{} (processando)", name),
+                        _ => self.status.clone(),
+                    };
+                    if let Some((_, current)) =
+                        self.download_states.iter_mut().find(|(n, _)| *n == name)
+                    {
+                        *current = state;
                     }
                 }
+                Message::DownloadFinished => {
+                    self.downloading = false;
+                    let register = self.register.clone();
+                    tokio::spawn(async move { register.invalidate().await });
+                    self.downloads.clear();
+                    self.status.clear();
+                    let failed = self
+                        .download_states
+                        .iter()
+                        .filter(|(_, state)| matches!(state, DownloadState::Failed(_)))
+                        .count();
+                    let (kind, text) = if failed > 0 {
+                        (
+                            egui_toast::ToastKind::Warning,
+                            format!("Sincronização concluída com {} falha(s)", failed),
+                        )
+                    } else {
+                        (
+                            egui_toast::ToastKind::Success,
+                            "Dados sincronizados".to_string(),
+                        )
+                    };
+                    util::toaster().add(Toast {
+                        kind,
+                        text: text.into(),
+                        options: ToastOptions::default().duration_in_seconds(3.0),
+                    });
+                }
+                Message::CancelDownload(name) => match name {
+                    Some(name) => {
+                        if let Some(token) = self.downloads.get(&name) {
+                            token.cancel();
+                        }
+                    }
+                    None => self.downloads.values().for_each(CancellationToken::cancel),
+                },
             }
         }
     }
@@ -514,6 +1196,12 @@ impl TemplateApp {
             if ui.button("Pesquisar").clicked() {
                 let _ = self.channel.0.send(Message::OpenSearchWindow(true));
             }
+            if ui.button("Comparar").clicked() {
+                let _ = self.channel.0.send(Message::OpenCompareTab);
+            }
+            if ui.button("Screener").clicked() {
+                let _ = self.channel.0.send(Message::OpenScreenerTab);
+            }
         });
     }
 
@@ -575,20 +1263,56 @@ where
     }
 }
 
+/// Série do índice de referência no período, ou `None` (com aviso) se a consulta falhou
+/// ou passou do tempo limite.
+async fn fetch_benchmark(
+    b: benchmark::Benchmark,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Option<BenchmarkSeries> {
+    let name = b.name.clone();
+    match timeout(
+        Duration::from_secs(15),
+        benchmark::async_series(b, start_date, end_date),
+    )
+    .await
+    {
+        Ok(res) => handle_result(&name, res.map(Some)),
+        Err(_) => {
+            log::error!("Timeout ao obter dados do {}", name);
+            util::toaster().add(Toast {
+                kind: egui_toast::ToastKind::Warning,
+                text: format!("Tempo limite atingido ao obter dados do {}.", name).into(),
+                options: ToastOptions::default().duration_in_seconds(3.0),
+            });
+            None
+        }
+    }
+}
+
+/// Abre a aba do fundo pelo cadastro legado; fundos registrados só após a Resolução
+/// CVM 175 são buscados no registro de fundos e classes.
 async fn handle_fund_data(
     cnpj: String,
     use_cache: bool,
     r: Register,
+    registry: &Registry,
     sender: &UnboundedSender<Message>,
     ctxc: &egui::Context,
 ) -> Result<(), fund::Error> {
-    let res = r.async_find_by_cnpj(cnpj.clone(), use_cache).await;
-    match res {
-        Ok(fund_dataframe) => {
-            let _ = sender.send(Message::OpenTab(cnpj, fund_dataframe));
-            ctxc.request_repaint();
-            Ok(())
+    let fund_dataframe = r.async_find_by_cnpj(cnpj.clone(), use_cache).await?;
+    let (fund, fund_dataframe) = match Fund::from_row(&fund_dataframe, 0) {
+        Some(fund) => (fund, fund_dataframe),
+        None => {
+            let registered = registry
+                .async_find_by_cnpj(cnpj.clone())
+                .await
+                .map_err(|err| fund::Error::NotFound(format!("{}: {}", cnpj, err)))?
+                .ok_or_else(|| fund::Error::NotFound(cnpj.clone()))?;
+            (registered.to_fund(&cnpj), registered.details.clone())
         }
-        Err(err) => Err(err),
-    }
+    };
+    let _ = sender.send(Message::OpenTab(Box::new(fund), fund_dataframe));
+    ctxc.request_repaint();
+    Ok(())
 }
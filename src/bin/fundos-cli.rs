@@ -0,0 +1,262 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use fundos::{
+    analysis::{self, returns::ReturnsTable},
+    catalog,
+    provider::{
+        cvm::{
+            fund::{index::fold, Class, Filters, Register, Situation},
+            informe::Informe,
+            portfolio::Portfolio,
+        },
+        indices,
+    },
+};
+use polars::{
+    frame::DataFrame,
+    lazy::dsl::col,
+    prelude::{CsvWriter, IntoLazy, JsonFormat, JsonWriter, SerWriter},
+};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Consulta os dados abertos da CVM sem abrir a interface gráfica.
+#[derive(Parser)]
+#[command(name = "fundos-cli", version, about)]
+struct Cli {
+    /// Formato de saída
+    #[arg(short, long, value_enum, default_value_t = Format::Table, global = true)]
+    format: Format,
+
+    /// Arquivo de saída (padrão: saída padrão)
+    #[arg(short, long, global = true)]
+    output: Option<PathBuf>,
+
+    /// Usa somente os dados em cache, sem consultar as fontes
+    #[arg(long, global = true)]
+    offline: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Pesquisa fundos pelo nome ou CNPJ
+    Search {
+        /// Termo de busca
+        keyword: Option<String>,
+        /// Classe do fundo (ex.: renda-fixa, acoes, multimercado, fidc-np)
+        #[arg(short, long, value_parser = class_arg)]
+        class: Option<Class>,
+        /// Situação do fundo (ex.: em-liquidacao, cancelado); padrão: em-funcionamento
+        #[arg(short, long, value_parser = situation_arg)]
+        situation: Option<Situation>,
+        /// Quantidade máxima de resultados
+        #[arg(short, long)]
+        limit: Option<u32>,
+    },
+    /// Rentabilidade diária e acumulada de um fundo no período
+    Profit {
+        cnpj: String,
+        /// Data inicial (AAAA-MM-DD)
+        #[arg(long)]
+        from: NaiveDate,
+        /// Data final (AAAA-MM-DD)
+        #[arg(long)]
+        to: NaiveDate,
+        /// Tabela de rentabilidade mensal comparada ao CDI
+        #[arg(long)]
+        monthly: bool,
+    },
+    /// Composição da carteira de um fundo em um mês
+    Portfolio {
+        cnpj: String,
+        /// Mês de referência (AAAA-MM)
+        #[arg(long)]
+        month: String,
+        /// Agrupa as aplicações por tipo
+        #[arg(long)]
+        top: bool,
+    },
+    /// Estatísticas gerais do cadastro de fundos
+    Stats {
+        #[arg(long, value_enum, default_value_t = StatsBy::Year)]
+        by: StatsBy,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+/// Valor do argumento: o rótulo em minúsculas, sem acentos e com hífens.
+fn arg_value(label: &str) -> String {
+    fold(label)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Um dos `options` pelo valor do argumento derivado de `label`.
+fn choice<T: Clone>(
+    value: &str,
+    options: &[T],
+    label: impl Fn(&T) -> &str,
+) -> std::result::Result<T, String> {
+    options
+        .iter()
+        .find(|option| arg_value(label(option)) == value)
+        .cloned()
+        .ok_or_else(|| {
+            let values: Vec<_> = options.iter().map(|o| arg_value(label(o))).collect();
+            format!("valores possíveis: {}", values.join(", "))
+        })
+}
+
+fn class_arg(value: &str) -> std::result::Result<Class, String> {
+    choice(value, &Class::ALL, Class::label)
+}
+
+fn situation_arg(value: &str) -> std::result::Result<Situation, String> {
+    choice(value, &Situation::ALL, Situation::label)
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StatsBy {
+    Year,
+    Situation,
+    Class,
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let cli = Cli::parse();
+    if let Err(err) = run(cli).await {
+        eprintln!("Erro: {}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    catalog::set_offline(cli.offline);
+    let mut df = match cli.command {
+        Command::Search {
+            keyword,
+            class,
+            situation,
+            limit,
+        } => {
+            let register = Register::new();
+            register
+                .async_find(
+                    keyword,
+                    Filters {
+                        class,
+                        situation: situation.or(Filters::active().situation),
+                        ..Default::default()
+                    },
+                    limit,
+                )
+                .await?
+                .lazy()
+                .select([
+                    col("CNPJ_FUNDO"),
+                    col("DENOM_SOCIAL"),
+                    col("CLASSE"),
+                    col("SIT"),
+                ])
+                .collect()?
+        }
+        Command::Profit {
+            cnpj,
+            from,
+            to,
+            monthly: true,
+        } => {
+            let informe = Informe::new();
+            let profit = informe.async_profit(cnpj, from, to).await?;
+            let cdi = indices::sgs::async_cdi(from, to).await?;
+            ReturnsTable::compute(&analysis::quotas(&profit), &analysis::cdi_rates(&cdi))
+                .to_dataframe()?
+        }
+        Command::Profit { cnpj, from, to, .. } => {
+            let informe = Informe::new();
+            informe
+                .async_profit(cnpj, from, to)
+                .await?
+                .lazy()
+                .select([
+                    col("CNPJ_FUNDO"),
+                    col("DT_COMPTC"),
+                    col("valor_float").alias("VL_QUOTA"),
+                    col("DAILY_RETURN"),
+                    col("RENT_ACUM"),
+                ])
+                .collect()?
+        }
+        Command::Portfolio { cnpj, month, top } => {
+            let (year, month) = month
+                .split_once('-')
+                .ok_or("mês deve estar no formato AAAA-MM")?;
+            let portfolio = Portfolio::new();
+            let (_, assets, top_assets) = portfolio
+                .async_assets(cnpj, year.to_string(), month.to_string(), top)
+                .await?;
+            if top {
+                top_assets
+            } else {
+                assets
+            }
+        }
+        Command::Stats { by } => {
+            let register = Register::new();
+            let (by_year, by_situation, by_class) = register.async_stats().await?;
+            match by {
+                StatsBy::Year => by_year,
+                StatsBy::Situation => by_situation,
+                StatsBy::Class => by_class,
+            }
+        }
+    };
+
+    match cli.output {
+        Some(path) => write(&mut df, cli.format, File::create(path)?),
+        None => write(&mut df, cli.format, io::stdout().lock()),
+    }
+}
+
+fn write<W: Write>(df: &mut DataFrame, format: Format, mut writer: W) -> Result<()> {
+    match format {
+        Format::Table => {
+            std::env::set_var("POLARS_FMT_MAX_ROWS", "-1");
+            writeln!(writer, "{}", df)?;
+        }
+        Format::Csv => {
+            CsvWriter::new(&mut writer)
+                .has_header(true)
+                .with_delimiter(b';')
+                .finish(df)?;
+        }
+        Format::Json => {
+            JsonWriter::new(&mut writer)
+                .with_json_format(JsonFormat::Json)
+                .finish(df)?;
+        }
+    }
+    Ok(())
+}
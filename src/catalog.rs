@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use cached_path::Cache;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// Registro de um conjunto de dados baixado (cadastro, informe do mês, carteira do mês,
+/// CDI, Ibovespa...).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Entry {
+    pub url: String,
+    /// Momento (timestamp Unix) em que a versão em cache foi baixada da fonte.
+    pub downloaded_at: Option<i64>,
+    /// Última consulta à fonte que terminou com sucesso.
+    pub checked_at: Option<i64>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Linhas de dados da versão em cache, quando conhecidas.
+    pub rows: Option<usize>,
+    /// Erro da última tentativa de atualização, se ela falhou.
+    pub last_error: Option<String>,
+    pub failed_at: Option<i64>,
+}
+
+impl Entry {
+    /// A última tentativa de atualização falhou e os dados exibidos vêm do cache.
+    /// Conjuntos que nunca foram baixados (ex.: mês que só existe no histórico) não contam.
+    pub fn is_stale(&self) -> bool {
+        match (self.failed_at, self.checked_at) {
+            (Some(failed), Some(checked)) => failed > checked,
+            (Some(_), None) => self.downloaded_at.is_some(),
+            _ => false,
+        }
+    }
+}
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+static CATALOG: Lazy<Mutex<BTreeMap<String, Entry>>> = Lazy::new(|| {
+    let entries = load().unwrap_or_else(|err| {
+        if err.kind() != io::ErrorKind::NotFound {
+            log::error!("Erro ao carregar catálogo de dados: {}", err);
+        }
+        BTreeMap::new()
+    });
+    Mutex::new(entries)
+});
+
+fn filename() -> PathBuf {
+    env::temp_dir().join("cache/catalog.json")
+}
+
+fn load() -> io::Result<BTreeMap<String, Entry>> {
+    let file = File::open(filename())?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+fn save(entries: &BTreeMap<String, Entry>) -> io::Result<()> {
+    let filename = filename();
+    if let Some(parent) = filename.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let writer = BufWriter::new(File::create(filename)?);
+    serde_json::to_writer_pretty(writer, entries)?;
+    Ok(())
+}
+
+fn update(dataset: &str, f: impl FnOnce(&mut Entry)) {
+    let mut entries = CATALOG.lock().unwrap();
+    f(entries.entry(dataset.to_string()).or_default());
+    if let Err(err) = save(&entries) {
+        log::error!("Erro ao salvar catálogo de dados: {}", err);
+    }
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// No modo offline nenhuma fonte é consultada; somente os dados em cache são usados.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn entries() -> Vec<(String, Entry)> {
+    let entries = CATALOG.lock().unwrap();
+    entries
+        .iter()
+        .map(|(dataset, entry)| (dataset.clone(), entry.clone()))
+        .collect()
+}
+
+pub fn entry(dataset: &str) -> Option<Entry> {
+    CATALOG.lock().unwrap().get(dataset).cloned()
+}
+
+pub fn set_rows(dataset: &str, rows: usize) {
+    update(dataset, |entry| entry.rows = Some(rows));
+}
+
+/// Atualização bem-sucedida feita fora do `cached_path` (ex.: Yahoo Finanças).
+pub fn record_download(dataset: &str, url: &str, rows: Option<usize>) {
+    let now = Utc::now().timestamp();
+    update(dataset, |entry| {
+        entry.url = url.to_string();
+        entry.downloaded_at = Some(now);
+        entry.checked_at = Some(now);
+        entry.rows = rows;
+    });
+}
+
+pub fn record_failure(dataset: &str, url: &str, err: &dyn std::fmt::Display) {
+    log::error!("Falha ao atualizar {}: {}", dataset, err);
+    update(dataset, |entry| {
+        entry.url = url.to_string();
+        entry.last_error = Some(err.to_string());
+        entry.failed_at = Some(Utc::now().timestamp());
+    });
+}
+
+/// Baixa (ou reaproveita) um recurso com o `cached_path`, registrando o resultado no
+/// catálogo. Se a fonte falhar, ou no modo offline, usa a última versão em cache.
+/// Bloqueante: deve ser chamada dentro de `spawn_blocking`.
+pub fn fetch(
+    dataset: &str,
+    url: &str,
+    options: &cached_path::Options,
+) -> Result<PathBuf, cached_path::Error> {
+    let mut online_err = None;
+    if !is_offline() {
+        let res = Cache::builder()
+            .progress_bar(Some(cached_path::ProgressBar::Full))
+            .build()
+            .and_then(|cache| cache.cached_path_with_options(url, options));
+        match res {
+            Ok(path) => {
+                let meta = read_meta(&path);
+                let previous = entry(dataset).unwrap_or_default();
+                let new_version =
+                    previous.etag != meta.etag || previous.downloaded_at != meta.downloaded_at;
+                let last_modified = if new_version || previous.last_modified.is_none() {
+                    last_modified(url)
+                } else {
+                    None
+                };
+                update(dataset, |entry| {
+                    if new_version {
+                        entry.rows = None;
+                    }
+                    if last_modified.is_some() {
+                        entry.last_modified = last_modified;
+                    }
+                    entry.url = url.to_string();
+                    entry.checked_at = Some(Utc::now().timestamp());
+                    entry.downloaded_at = meta.downloaded_at.or(entry.downloaded_at);
+                    entry.etag = meta.etag;
+                });
+                return Ok(path);
+            }
+            Err(err) => {
+                record_failure(dataset, url, &err);
+                online_err = Some(err);
+            }
+        }
+    }
+
+    // Sem versão em cache, o erro relevante é o da consulta à fonte
+    let path = Cache::builder()
+        .progress_bar(Some(cached_path::ProgressBar::Full))
+        .offline(true)
+        .build()
+        .and_then(|cache| cache.cached_path_with_options(url, options))
+        .map_err(|err| online_err.unwrap_or(err))?;
+    let meta = read_meta(&path);
+    update(dataset, |entry| {
+        entry.url = url.to_string();
+        entry.downloaded_at = entry.downloaded_at.or(meta.downloaded_at);
+        entry.etag = entry.etag.take().or(meta.etag);
+    });
+    Ok(path)
+}
+
+/// Chave do conjunto de dados a partir do subdiretório e do nome do arquivo na URL,
+/// ex.: `infdiario/inf_diario_fi_202401`.
+pub fn dataset_key(subdir: &str, url: &str) -> String {
+    let name = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|url| url.rsplit('/').next())
+        .and_then(|name| name.split('.').next())
+        .unwrap_or(url);
+    format!("{}/{}", subdir, name)
+}
+
+/// Idade de um timestamp Unix em texto curto, ex.: "há 3 dias".
+pub fn age(timestamp: i64) -> String {
+    let elapsed = chrono::Duration::seconds(Utc::now().timestamp() - timestamp);
+    if elapsed.num_minutes() < 1 {
+        "agora".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("há {} min", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("há {} h", elapsed.num_hours())
+    } else if elapsed.num_days() == 1 {
+        "há 1 dia".to_string()
+    } else {
+        format!("há {} dias", elapsed.num_days())
+    }
+}
+
+/// Campos do arquivo `.meta` que o `cached_path` grava ao lado de cada recurso.
+#[derive(Deserialize)]
+struct CachedMeta {
+    etag: Option<String>,
+    creation_time: f64,
+}
+
+#[derive(Default)]
+struct Meta {
+    etag: Option<String>,
+    downloaded_at: Option<i64>,
+}
+
+/// `Last-Modified` informado pela fonte; o `cached_path` guarda apenas o ETag.
+fn last_modified(url: &str) -> Option<String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .ok()?;
+    let response = client.head(url).send().ok()?;
+    response
+        .headers()
+        .get(reqwest::header::LAST_MODIFIED)?
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
+fn read_meta(path: &Path) -> Meta {
+    // Recursos extraídos ficam em `<recurso>-extracted`, com o `.meta` no recurso original
+    let resource = path
+        .to_str()
+        .and_then(|p| p.strip_suffix("-extracted"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.to_path_buf());
+    let meta_path = PathBuf::from(format!("{}.meta", resource.display()));
+
+    let Ok(file) = File::open(meta_path) else {
+        return Meta::default();
+    };
+    let Ok(meta) = serde_json::from_reader::<_, CachedMeta>(BufReader::new(file)) else {
+        return Meta::default();
+    };
+    Meta {
+        etag: meta.etag,
+        downloaded_at: Some(meta.creation_time as i64),
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{Datelike, NaiveDate};
+use tokio::{
+    sync::{mpsc::UnboundedSender, Semaphore},
+    task::spawn_blocking,
+    time::{interval, sleep, Duration, MissedTickBehavior},
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    catalog,
+    message::Message,
+    provider::cvm::{fidc, fii, fund, informe, portfolio, registry, try_download},
+};
+
+pub mod options;
+
+/// Situação de um arquivo na sincronização em segundo plano.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadState {
+    Queued,
+    Downloading {
+        bytes: u64,
+        total: Option<u64>,
+    },
+    /// Extraindo, convertendo para UTF-8 e gravando o Parquet.
+    Processing,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+impl DownloadState {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            DownloadState::Done | DownloadState::Failed(_) | DownloadState::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Cadastro,
+    Registro,
+    Fii,
+    Fidc,
+    Informe,
+    Carteira,
+}
+
+/// Um arquivo a sincronizar, identificado pela mesma chave usada no catálogo.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub name: String,
+    url: String,
+    subdir: String,
+    kind: Kind,
+}
+
+/// Cadastro de fundos, registro de classes, informes mensais de FII do ano, informes
+/// mensais de FIDC dos dois meses anteriores (o do mês atual ainda não foi publicado) e
+/// os zips de informes diários e carteiras do mês atual e do anterior.
+pub fn jobs() -> Vec<Job> {
+    let mut jobs = Vec::new();
+    let today = chrono::offset::Local::now().date_naive();
+    let previous = previous_month(today);
+
+    match fund::options::load() {
+        Ok(options) => jobs.push(Job {
+            name: catalog::dataset_key(&options.path, &options.url),
+            url: options.url,
+            subdir: options.path,
+            kind: Kind::Cadastro,
+        }),
+        Err(err) => log::error!("Erro ao carregar configuração do cadastro: {}", err),
+    }
+
+    match registry::options::load() {
+        Ok(options) => jobs.push(Job {
+            name: catalog::dataset_key(&options.path, &options.url),
+            url: options.url,
+            subdir: options.path,
+            kind: Kind::Registro,
+        }),
+        Err(err) => log::error!("Erro ao carregar configuração do registro: {}", err),
+    }
+
+    match fii::options::load() {
+        Ok(options) => {
+            for url in options.urls(today, today) {
+                jobs.push(Job {
+                    name: catalog::dataset_key(&options.path, &url),
+                    url,
+                    subdir: options.path.clone(),
+                    kind: Kind::Fii,
+                });
+            }
+        }
+        Err(err) => log::error!("Erro ao carregar configuração dos informes de FII: {}", err),
+    }
+
+    match fidc::options::load() {
+        Ok(options) => {
+            for (url, _) in options.urls_with_dates(previous_month(previous), previous) {
+                jobs.push(Job {
+                    name: catalog::dataset_key(&options.path, &url),
+                    url,
+                    subdir: options.path.clone(),
+                    kind: Kind::Fidc,
+                });
+            }
+        }
+        Err(err) => log::error!(
+            "Erro ao carregar configuração dos informes de FIDC: {}",
+            err
+        ),
+    }
+
+    match informe::options::load() {
+        Ok(options) => {
+            for (url, _) in options.urls_with_dates(Some(previous), Some(today)) {
+                jobs.push(Job {
+                    name: catalog::dataset_key(&options.path, &url),
+                    url,
+                    subdir: options.path.clone(),
+                    kind: Kind::Informe,
+                });
+            }
+        }
+        Err(err) => log::error!("Erro ao carregar configuração dos informes: {}", err),
+    }
+
+    match portfolio::options::load() {
+        Ok(options) => {
+            for (url, _) in options.urls_with_dates(Some(previous), Some(today)) {
+                jobs.push(Job {
+                    name: catalog::dataset_key(&options.path, &url),
+                    url,
+                    subdir: options.path.clone(),
+                    kind: Kind::Carteira,
+                });
+            }
+        }
+        Err(err) => log::error!("Erro ao carregar configuração das carteiras: {}", err),
+    }
+
+    jobs
+}
+
+/// Envia `Message::StartDownload` ao iniciar (se configurado) e a cada intervalo.
+pub async fn schedule(sender: UnboundedSender<Message>, ctx: egui::Context) {
+    let options = options::load().unwrap_or_else(|err| {
+        log::error!("Erro ao carregar configuração da sincronização: {}", err);
+        options::Options::default()
+    });
+
+    if options.on_startup {
+        let _ = sender.send(Message::StartDownload);
+        ctx.request_repaint();
+    }
+    if options.interval_minutes == 0 {
+        return;
+    }
+
+    let period = Duration::from_secs(options.interval_minutes * 60);
+    let mut ticker = interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker.tick().await; // o primeiro tick é imediato
+    loop {
+        ticker.tick().await;
+        if sender.send(Message::StartDownload).is_err() {
+            break;
+        }
+        ctx.request_repaint();
+    }
+}
+
+/// Sincroniza os arquivos um por vez, informando o andamento pelo canal de mensagens.
+///
+/// O cancelamento interrompe a espera e pula o arquivo; um download que já está em
+/// andamento termina em segundo plano, pois o `cached_path` não pode ser interrompido.
+pub async fn run(
+    jobs: Vec<Job>,
+    tokens: HashMap<String, CancellationToken>,
+    sender: UnboundedSender<Message>,
+    ctx: egui::Context,
+) {
+    let send = |name: &str, state: DownloadState| {
+        let _ = sender.send(Message::DownloadProgress(name.to_string(), state));
+        ctx.request_repaint();
+    };
+
+    for job in &jobs {
+        send(&job.name, DownloadState::Queued);
+    }
+
+    for job in jobs {
+        let token = tokens.get(&job.name).cloned().unwrap_or_default();
+        if token.is_cancelled() {
+            send(&job.name, DownloadState::Cancelled);
+            continue;
+        }
+
+        let total = {
+            let url = job.url.clone();
+            spawn_blocking(move || content_length(&url))
+                .await
+                .unwrap_or(None)
+        };
+        send(&job.name, DownloadState::Downloading { bytes: 0, total });
+
+        let monitor = {
+            let dir = cache_dir(&job.subdir);
+            let name = job.name.clone();
+            let sender = sender.clone();
+            let ctx = ctx.clone();
+            tokio::spawn(async move {
+                loop {
+                    sleep(Duration::from_millis(500)).await;
+                    if let Some(bytes) = partial_download_size(&dir) {
+                        let state = DownloadState::Downloading { bytes, total };
+                        let _ = sender.send(Message::DownloadProgress(name.clone(), state));
+                        ctx.request_repaint();
+                    }
+                }
+            })
+        };
+
+        let name = job.name.clone();
+        let task = tokio::spawn(execute(job, {
+            let name = name.clone();
+            let sender = sender.clone();
+            let ctx = ctx.clone();
+            move || {
+                let _ = sender.send(Message::DownloadProgress(name, DownloadState::Processing));
+                ctx.request_repaint();
+            }
+        }));
+
+        let state = tokio::select! {
+            result = task => match result {
+                Ok(Ok(())) => DownloadState::Done,
+                Ok(Err(err)) => DownloadState::Failed(err),
+                Err(err) => DownloadState::Failed(err.to_string()),
+            },
+            _ = token.cancelled() => DownloadState::Cancelled,
+        };
+        monitor.abort();
+        send(&name, state);
+    }
+
+    let _ = sender.send(Message::DownloadFinished);
+    ctx.request_repaint();
+}
+
+async fn execute(job: Job, processing: impl FnOnce()) -> Result<(), String> {
+    match job.kind {
+        Kind::Cadastro => {
+            // O cadastro é baixado e convertido na mesma chamada
+            let options = fund::options::load().map_err(|err| err.to_string())?;
+            options.async_path().await.map_err(|err| err.to_string())?;
+        }
+        Kind::Registro | Kind::Fii | Kind::Fidc => {
+            download(&job).await?;
+        }
+        Kind::Informe => {
+            let path = download(&job).await?;
+            processing();
+            let options = informe::options::load().map_err(|err| err.to_string())?;
+            let store = options.store_path();
+            spawn_blocking(move || informe::store::ingest_all(&[path], &store))
+                .await
+                .map_err(|err| err.to_string())?
+                .map_err(|err| err.to_string())?;
+        }
+        Kind::Carteira => {
+            let path = download(&job).await?;
+            processing();
+            let options = portfolio::options::load().map_err(|err| err.to_string())?;
+            let store = options.store_path();
+            spawn_blocking(move || portfolio::store::ingest_all(&[path], &store))
+                .await
+                .map_err(|err| err.to_string())?
+                .map_err(|err| err.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+async fn download(job: &Job) -> Result<PathBuf, String> {
+    let semaphore = Arc::new(Semaphore::new(1));
+    try_download(job.url.clone(), job.subdir.clone(), semaphore)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Diretório do `cached_path` para o subdiretório configurado.
+fn cache_dir(subdir: &str) -> PathBuf {
+    env::temp_dir().join("cache").join(subdir)
+}
+
+/// O `cached_path` baixa para um arquivo temporário (`.tmp*`) no diretório do recurso;
+/// o tamanho desse arquivo indica quantos bytes já chegaram.
+fn partial_download_size(dir: &Path) -> Option<u64> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(".tmp"))
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .max()
+}
+
+fn content_length(url: &str) -> Option<u64> {
+    if catalog::is_offline() {
+        return None;
+    }
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .ok()?;
+    client.head(url).send().ok()?.content_length()
+}
+
+fn previous_month(date: NaiveDate) -> NaiveDate {
+    let first = date.with_day(1).unwrap();
+    first.pred_opt().unwrap().with_day(1).unwrap()
+}
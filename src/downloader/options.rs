@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::get;
+
+const ROOT: &str = "sincronizacao";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Options {
+    /// Sincroniza ao abrir o aplicativo
+    pub on_startup: bool,
+    /// Intervalo entre sincronizações, em minutos (0 desativa a repetição)
+    pub interval_minutes: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            on_startup: true,
+            interval_minutes: 360,
+        }
+    }
+}
+
+pub fn load() -> Result<Options, config::ConfigError> {
+    get::<Options>(ROOT)
+}
@@ -1,10 +1,14 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod analysis;
 mod app;
+pub mod catalog;
 pub mod config;
+mod downloader;
 mod history;
 mod message;
-mod provider;
+pub mod provider;
+mod screens;
 mod statusbar;
 mod ui;
 mod util;
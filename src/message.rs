@@ -1,19 +1,84 @@
-use crate::provider::{self};
+use crate::{
+    analysis::{compare::FundSeries, look_through::LookThrough, screener::ScreenerRow},
+    downloader::DownloadState,
+    provider,
+    provider::{
+        cvm::registry::RegisteredFund,
+        indices::benchmark::{Benchmark, BenchmarkSeries},
+    },
+};
 use chrono::NaiveDate;
 use polars::frame::DataFrame;
 
 pub enum Message {
     StartDownload,
-    SearchFunds(String, Option<provider::cvm::fund::Class>),
+    DownloadProgress(String, DownloadState),
+    DownloadFinished,
+    /// Cancela um arquivo da sincronização, ou todos com `None`.
+    CancelDownload(Option<String>),
+    SearchFunds(String, provider::cvm::fund::Filters),
     ResultFunds(DataFrame),
     NewTab(String),
-    Profit(String, NaiveDate, NaiveDate),
+    /// Rentabilidade do fundo no período, com os índices de referência exibidos.
+    Profit(String, NaiveDate, NaiveDate, Vec<Benchmark>),
     Assets(String, String, String),
-    ProfitResult(String, DataFrame, DataFrame, DataFrame),
+    ProfitResult(String, DataFrame, DataFrame, Vec<BenchmarkSeries>),
+    /// Índice de referência exibido depois da consulta de rentabilidade do período.
+    Benchmark(String, NaiveDate, NaiveDate, Benchmark),
+    /// Série do índice (pelo nome), ou `None` se a consulta falhou.
+    BenchmarkResult(
+        String,
+        NaiveDate,
+        NaiveDate,
+        String,
+        Option<BenchmarkSeries>,
+    ),
+    /// Cotas e CDI para a janela móvel de `u32` meses terminando no período.
+    Rolling(String, NaiveDate, NaiveDate, u32),
+    /// Cotas e CDI da janela móvel, ou `None` se alguma das consultas falhou.
+    RollingResult(
+        String,
+        NaiveDate,
+        NaiveDate,
+        u32,
+        Option<(DataFrame, DataFrame)>,
+    ),
     AssetsResult(String, DataFrame, DataFrame, DataFrame),
+    /// Carteiras dos `u32` meses terminados no mês informado.
+    PortfolioEvolution(String, NaiveDate, u32),
+    PortfolioEvolutionResult(String, NaiveDate, u32, Vec<DataFrame>),
+    /// Carteira consolidada do fundo no ano e mês informados.
+    LookThrough(String, String, String),
+    LookThroughResult(String, String, String, LookThrough),
+    Flows(String, NaiveDate, NaiveDate),
+    FlowsResult(String, DataFrame),
+    /// Classes e subclasses do fundo no registro da Resolução CVM 175.
+    Registry(String),
+    RegistryResult(String, Option<Box<RegisteredFund>>),
     OpenSearchWindow(bool),
     ShowAssetDetail(DataFrame),
     OpenDashboardTab,
     DashboardTabResult(DataFrame, DataFrame, DataFrame),
-    OpenTab(String, DataFrame),
+    OpenTab(Box<provider::cvm::fund::Fund>, DataFrame),
+    OpenCompareTab,
+    Compare(Vec<String>, NaiveDate, NaiveDate),
+    CompareResult(Vec<FundSeries>),
+    /// Busca no cadastro os fundos sugeridos para o texto digitado na comparação.
+    CompareSearch(String),
+    CompareSearchResult(String, Vec<provider::cvm::fund::Fund>),
+    OpenScreenerTab,
+    /// Cadastro com os indicadores dos últimos 12 meses para o screener.
+    Screener,
+    ScreenerResult(Vec<ScreenerRow>),
+    /// Grava o DataFrame em CSV, com o nome de arquivo informado, na pasta de exportação.
+    ExportCsv(String, DataFrame),
+    /// Abre a aba de informes mensais do FII (CNPJ e denominação).
+    OpenFiiTab(String, String),
+    Fii(String, NaiveDate, NaiveDate),
+    FiiResult(String, DataFrame),
+    /// Abre a aba de informes mensais do FIDC (CNPJ e denominação).
+    OpenFidcTab(String, String),
+    Fidc(String, NaiveDate, NaiveDate),
+    /// Carteira e inadimplência por mês, e posição de cada classe de cotas por mês.
+    FidcResult(String, DataFrame, DataFrame),
 }
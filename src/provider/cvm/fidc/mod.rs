@@ -0,0 +1,222 @@
+pub mod options;
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use glob::glob;
+use options::{load, Options};
+use polars::{
+    datatypes::DataType,
+    error::PolarsError,
+    frame::DataFrame,
+    lazy::{
+        dsl::{col, concat, lit, Expr, StrptimeOptions},
+        frame::LazyFrame,
+    },
+    prelude::{JoinArgs, JoinType, Schema, SortOptions, UnionArgs, UniqueKeepStrategy},
+};
+
+use super::{cnpj_key, column_or_null, read_csv_lazy};
+
+/// Ativo e carteira de direitos creditórios (tabela I), com e sem aquisição substancial
+/// dos riscos.
+pub const ASSET_COLUMNS: [&str; 3] = [
+    "TAB_I_VL_ATIVO",
+    "TAB_I2A_VL_DIRCRED_RISCO",
+    "TAB_I2B_VL_DIRCRED_SEM_RISCO",
+];
+
+/// Patrimônio líquido no fim do mês (tabela IV).
+pub const NET_WORTH_COLUMN: &str = "TAB_IV_A_VL_PL";
+
+/// Direitos creditórios inadimplentes por faixa de atraso (tabela V).
+pub const OVERDUE_COLUMNS: [(&str, &str); 10] = [
+    ("TAB_V_B1_VL_INAD_30", "Até 30 dias"),
+    ("TAB_V_B2_VL_INAD_31_60", "31 a 60 dias"),
+    ("TAB_V_B3_VL_INAD_61_90", "61 a 90 dias"),
+    ("TAB_V_B4_VL_INAD_91_120", "91 a 120 dias"),
+    ("TAB_V_B5_VL_INAD_121_150", "121 a 150 dias"),
+    ("TAB_V_B6_VL_INAD_151_180", "151 a 180 dias"),
+    ("TAB_V_B7_VL_INAD_181_360", "181 a 360 dias"),
+    ("TAB_V_B8_VL_INAD_361_720", "361 a 720 dias"),
+    ("TAB_V_B9_VL_INAD_721_1080", "721 a 1080 dias"),
+    ("TAB_V_B10_VL_INAD_1080", "Acima de 1080 dias"),
+];
+
+/// Classe ou série de cotas (sênior, mezanino, subordinada) das tabelas X.
+pub const QUOTA_CLASS_COLUMN: &str = "TAB_X_CLASSE_SERIE";
+
+/// Informe mensal dos fundos de investimento em direitos creditórios
+/// (`FIDC/DOC/INF_MENSAL`).
+#[derive(Clone)]
+pub struct Fidc {
+    options: Options,
+}
+
+impl Default for Fidc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fidc {
+    pub fn new() -> Self {
+        let options = load().unwrap();
+        Self { options }
+    }
+
+    /// Informes mensais do FIDC no período: um DataFrame com a carteira, o patrimônio e
+    /// a inadimplência de cada mês, e outro com quantidade, valor, rentabilidade e
+    /// cotistas de cada classe de cotas por mês.
+    pub async fn async_monthly(
+        &self,
+        cnpj: String,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<(DataFrame, DataFrame), PolarsError> {
+        let paths = self
+            .options
+            .async_path(start_date, end_date)
+            .await
+            .map_err(|err| PolarsError::NoData(err.to_string().into()))?;
+
+        let table =
+            |name: &str, columns: Vec<(&str, DataType)>| -> Result<LazyFrame, PolarsError> {
+                Ok(scan(&paths, name, &columns)?.filter(
+                    col("CNPJ").eq(lit(cnpj.clone())).and(
+                        col("AS_DATE")
+                            .gt_eq(lit(start_date))
+                            .and(col("AS_DATE").lt_eq(lit(end_date))),
+                    ),
+                ))
+            };
+        let numbers = |names: &[&'static str]| -> Vec<(&'static str, DataType)> {
+            names
+                .iter()
+                .map(|name| (*name, DataType::Float64))
+                .collect()
+        };
+        let by_month = || [col("CNPJ"), col("AS_DATE")];
+        let by_class = || [col("CNPJ"), col("AS_DATE"), col(QUOTA_CLASS_COLUMN)];
+        let left = || JoinArgs::new(JoinType::Left);
+
+        let mut assets = numbers(&ASSET_COLUMNS);
+        assets.push(("DENOM_SOCIAL", DataType::Utf8));
+        let overdue: Vec<&'static str> = OVERDUE_COLUMNS.iter().map(|(name, _)| *name).collect();
+        let monthly = unique(table("I", assets)?, &["CNPJ", "AS_DATE"])
+            .join(
+                unique(
+                    table("IV", numbers(&[NET_WORTH_COLUMN]))?,
+                    &["CNPJ", "AS_DATE"],
+                ),
+                by_month(),
+                by_month(),
+                left(),
+            )
+            .join(
+                unique(table("V", numbers(&overdue))?, &["CNPJ", "AS_DATE"]),
+                by_month(),
+                by_month(),
+                left(),
+            )
+            .sort("AS_DATE", SortOptions::default())
+            .collect()?;
+        if monthly.height() == 0 {
+            return Err(PolarsError::NoData(
+                "Nenhum informe mensal encontrado no intervalo".into(),
+            ));
+        }
+
+        let class_keys = ["CNPJ", "AS_DATE", QUOTA_CLASS_COLUMN];
+        let class_table = |name: &str, values: &[&'static str]| {
+            let mut columns = numbers(values);
+            columns.push((QUOTA_CLASS_COLUMN, DataType::Utf8));
+            table(name, columns).map(|lf| unique(lf, &class_keys))
+        };
+        let quotas = class_table("X_2", &["TAB_X_QT_COTA", "TAB_X_VL_COTA"])?
+            .join(
+                class_table("X_3", &["TAB_X_VL_RENTAB_MES"])?,
+                by_class(),
+                by_class(),
+                left(),
+            )
+            .join(
+                class_table("X_1", &["TAB_X_NR_COTST"])?,
+                by_class(),
+                by_class(),
+                left(),
+            )
+            .sort_by_exprs(
+                [col(QUOTA_CLASS_COLUMN), col("AS_DATE")],
+                [false, false],
+                true,
+                false,
+            )
+            .collect()?;
+
+        Ok((monthly, quotas))
+    }
+}
+
+fn unique(lf: LazyFrame, keys: &[&str]) -> LazyFrame {
+    lf.unique_stable(
+        Some(keys.iter().map(|key| key.to_string()).collect()),
+        UniqueKeepStrategy::Last,
+    )
+}
+
+/// Lê uma tabela do informe (`I`, `X_2`...) de todos os meses baixados, com a chave
+/// `CNPJ`/`AS_DATE` e as colunas pedidas; colunas ausentes no arquivo ficam nulas.
+fn scan(
+    paths: &[PathBuf],
+    table: &str,
+    columns: &[(&str, DataType)],
+) -> Result<LazyFrame, PolarsError> {
+    let mut frames = Vec::new();
+    for path in paths {
+        // Só o sufixo AAAAMM, para que `X_1` não inclua `X_1_1`
+        let pattern = format!(
+            "{}/inf_mensal_fidc_tab_{}_[0-9][0-9][0-9][0-9][0-9][0-9].csv",
+            path.display(),
+            table
+        );
+        for csv in glob(&pattern).unwrap().filter_map(Result::ok) {
+            let lf = read_csv_lazy(&csv.display().to_string())?;
+            let schema = lf.schema()?;
+            let mut select = key_columns(&schema);
+            select.extend(
+                columns
+                    .iter()
+                    .map(|(name, data_type)| column_or_null(&schema, name, data_type.clone())),
+            );
+            frames.push(lf.select(select));
+        }
+    }
+    if frames.is_empty() {
+        return Err(PolarsError::NoData(
+            format!(
+                "Nenhum arquivo da tabela {} do informe mensal de FIDC",
+                table
+            )
+            .into(),
+        ));
+    }
+    concat(&frames, UnionArgs::default())
+}
+
+/// CNPJ (ver `cnpj_key`) e data de referência.
+fn key_columns(schema: &Schema) -> Vec<Expr> {
+    vec![
+        cnpj_key(schema).alias("CNPJ"),
+        col("DT_COMPTC")
+            .str()
+            .strptime(
+                DataType::Date,
+                StrptimeOptions {
+                    format: Some("%Y-%m-%d".into()),
+                    ..Default::default()
+                },
+            )
+            .alias("AS_DATE"),
+    ]
+}
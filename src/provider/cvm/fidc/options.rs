@@ -0,0 +1,102 @@
+use chrono::{Datelike, NaiveDate};
+use std::{path::PathBuf, sync::Arc};
+use tokio::sync::Semaphore;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::get, provider::cvm::try_download};
+
+const ROOT: &str = "cvm.fidc.informe";
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Options {
+    pub description: String,
+    pub url: String,
+    pub historical_url: String,
+    pub path: String,
+}
+
+impl Options {
+    /// Zip mensal e zip anual do histórico de cada mês do período.
+    pub fn urls_with_dates(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Vec<(String, String)> {
+        let mut patterns = Vec::new();
+        let mut current_date = start_date.with_day(1).unwrap();
+        while current_date <= end_date {
+            let year = current_date.year().to_string();
+            let month = format!("{:02}", current_date.month());
+            patterns.push((
+                self.url.replace("{year}", &year).replace("{month}", &month),
+                self.historical_url.replace("{year}", &year),
+            ));
+            current_date = current_date
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap();
+        }
+        patterns
+    }
+
+    /// Baixa os zips do período, recorrendo ao histórico anual quando o mensal não existe,
+    /// e retorna os diretórios com os CSVs em UTF-8. Meses ainda não publicados pela CVM
+    /// são ignorados.
+    pub async fn async_path(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<PathBuf>, cached_path::Error> {
+        let semaphore = Arc::new(Semaphore::new(4));
+        let mut historical_urls: Vec<String> = Vec::new();
+        let mut handles = Vec::new();
+        for (url, historical_url) in self.urls_with_dates(start_date, end_date) {
+            // Um único download por zip anual do histórico
+            let historical_url = if historical_urls.contains(&historical_url) {
+                None
+            } else {
+                historical_urls.push(historical_url.clone());
+                Some(historical_url)
+            };
+            let semaphore = semaphore.clone();
+            let subdir = self.path.clone();
+            handles.push(tokio::spawn(async move {
+                match try_download(url.clone(), subdir.clone(), semaphore.clone()).await {
+                    Ok(path) => (url, Ok(Some(path))),
+                    // O histórico anual já foi pedido por outro mês do mesmo ano
+                    Err(_) => match historical_url {
+                        Some(historical_url) => (
+                            historical_url.clone(),
+                            try_download(historical_url, subdir, semaphore)
+                                .await
+                                .map(Some),
+                        ),
+                        None => (url, Ok(None)),
+                    },
+                }
+            }));
+        }
+
+        let mut paths = Vec::new();
+        let mut last_error = None;
+        for handle in handles {
+            match handle.await.unwrap() {
+                (_, Ok(path)) => paths.extend(path),
+                (url, Err(err)) => {
+                    log::warn!("Informe mensal de FIDC indisponível: {} ({})", url, err);
+                    last_error = Some(err);
+                }
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        match last_error {
+            Some(err) if paths.is_empty() => Err(err),
+            _ => Ok(paths),
+        }
+    }
+}
+
+pub fn load() -> Result<Options, config::ConfigError> {
+    get::<Options>(ROOT)
+}
@@ -0,0 +1,172 @@
+pub mod options;
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use glob::glob;
+use options::{load, Options};
+use polars::{
+    datatypes::DataType,
+    error::PolarsError,
+    frame::DataFrame,
+    lazy::{
+        dsl::{col, concat, lit, Expr, StrptimeOptions},
+        frame::LazyFrame,
+    },
+    prelude::{JoinArgs, JoinType, Schema, SortOptions, UnionArgs, UniqueKeepStrategy},
+};
+
+use super::{cnpj_key, column_or_null, read_csv_lazy};
+
+/// Colunas numéricas do complemento do informe mensal, convertidas para `Float64`.
+/// Os percentuais vêm em fração (0,0075 = 0,75%).
+pub const NUMERIC_COLUMNS: [&str; 10] = [
+    "Valor_Ativo",
+    "Patrimonio_Liquido",
+    "Cotas_Emitidas",
+    "Valor_Patrimonial_Cotas",
+    "Percentual_Despesas_Taxa_Administracao",
+    "Percentual_Rentabilidade_Efetiva_Mes",
+    "Percentual_Rentabilidade_Patrimonial_Mes",
+    "Percentual_Dividend_Yield_Mes",
+    "Percentual_Amortizacao_Cotas_Mes",
+    "Total_Numero_Cotistas",
+];
+
+/// Colunas de texto do arquivo geral, com o perfil do fundo no mês.
+pub const PROFILE_COLUMNS: [&str; 4] =
+    ["Segmento_Atuacao", "Mandato", "Tipo_Gestao", "Publico_Alvo"];
+
+/// Informe mensal dos fundos imobiliários (`FII/DOC/INF_MENSAL`).
+#[derive(Clone)]
+pub struct Fii {
+    options: Options,
+}
+
+impl Default for Fii {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fii {
+    pub fn new() -> Self {
+        let options = load().unwrap();
+        Self { options }
+    }
+
+    /// Informes mensais do FII no período, um por mês (a última versão entregue), com
+    /// o perfil do arquivo geral (`Nome_Fundo`, segmento, mandato...) em cada linha.
+    pub async fn async_monthly(
+        &self,
+        cnpj: String,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<DataFrame, PolarsError> {
+        let paths = self
+            .options
+            .async_path(start_date, end_date)
+            .await
+            .map_err(|err| PolarsError::NoData(err.to_string().into()))?;
+
+        let by_fund = |lf: LazyFrame| {
+            lf.filter(
+                col("CNPJ").eq(lit(cnpj.clone())).and(
+                    col("AS_DATE")
+                        .gt_eq(lit(start_date))
+                        .and(col("AS_DATE").lt_eq(lit(end_date))),
+                ),
+            )
+        };
+        let complement = by_fund(scan(&paths, "complemento", complement_columns)?);
+        let general = by_fund(scan(&paths, "geral", general_columns)?);
+
+        let keys = || Some(vec!["CNPJ".to_string(), "AS_DATE".to_string()]);
+        let latest = |lf: LazyFrame| {
+            lf.sort_by_exprs([col("AS_DATE"), col("Versao")], [false, false], true, false)
+                .unique_stable(keys(), UniqueKeepStrategy::Last)
+        };
+        let monthly = latest(complement)
+            .join(
+                latest(general).drop_columns(["Versao"]),
+                [col("CNPJ"), col("AS_DATE")],
+                [col("CNPJ"), col("AS_DATE")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .sort("AS_DATE", SortOptions::default())
+            .collect()?;
+        if monthly.height() == 0 {
+            return Err(PolarsError::NoData(
+                "Nenhum informe mensal encontrado no intervalo".into(),
+            ));
+        }
+        Ok(monthly)
+    }
+}
+
+/// Lê os CSVs de um tipo (`complemento`, `geral`...) de todos os anos baixados.
+fn scan(
+    paths: &[PathBuf],
+    kind: &str,
+    columns: fn(&Schema) -> Vec<Expr>,
+) -> Result<LazyFrame, PolarsError> {
+    let mut frames = Vec::new();
+    for path in paths {
+        let pattern = format!("{}/inf_mensal_fii_{}_*.csv", path.display(), kind);
+        for csv in glob(&pattern).unwrap().filter_map(Result::ok) {
+            let lf = read_csv_lazy(&csv.display().to_string())?;
+            let schema = lf.schema()?;
+            frames.push(lf.select(columns(&schema)));
+        }
+    }
+    if frames.is_empty() {
+        return Err(PolarsError::NoData(
+            format!("Nenhum arquivo {} do informe mensal de FII", kind).into(),
+        ));
+    }
+    concat(&frames, UnionArgs::default())
+}
+
+fn complement_columns(schema: &Schema) -> Vec<Expr> {
+    let mut columns = key_columns(schema);
+    columns.extend(
+        NUMERIC_COLUMNS
+            .iter()
+            .map(|name| column_or_null(schema, name, DataType::Float64)),
+    );
+    columns
+}
+
+fn general_columns(schema: &Schema) -> Vec<Expr> {
+    let mut columns = key_columns(schema);
+    // Os informes posteriores à Resolução CVM 175 trazem o nome da classe
+    columns.push(if schema.get("Nome_Fundo_Classe").is_some() {
+        col("Nome_Fundo_Classe").alias("Nome_Fundo")
+    } else {
+        column_or_null(schema, "Nome_Fundo", DataType::Utf8)
+    });
+    columns.extend(
+        PROFILE_COLUMNS
+            .iter()
+            .map(|name| column_or_null(schema, name, DataType::Utf8)),
+    );
+    columns
+}
+
+/// CNPJ (ver `cnpj_key`), data de referência e versão.
+fn key_columns(schema: &Schema) -> Vec<Expr> {
+    vec![
+        cnpj_key(schema).alias("CNPJ"),
+        col("Data_Referencia")
+            .str()
+            .strptime(
+                DataType::Date,
+                StrptimeOptions {
+                    format: Some("%Y-%m-%d".into()),
+                    ..Default::default()
+                },
+            )
+            .alias("AS_DATE"),
+        column_or_null(schema, "Versao", DataType::Int64),
+    ]
+}
@@ -0,0 +1,67 @@
+use chrono::{Datelike, NaiveDate};
+use std::{path::PathBuf, sync::Arc};
+use tokio::sync::Semaphore;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::get, provider::cvm::try_download};
+
+const ROOT: &str = "cvm.fii.informe";
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Options {
+    pub description: String,
+    /// URL do zip anual, com o placeholder `{year}`.
+    pub url: String,
+    pub path: String,
+}
+
+impl Options {
+    /// Um zip por ano do período.
+    pub fn urls(&self, start_date: NaiveDate, end_date: NaiveDate) -> Vec<String> {
+        (start_date.year()..=end_date.year())
+            .map(|year| self.url.replace("{year}", &year.to_string()))
+            .collect()
+    }
+
+    /// Baixa os zips do período e retorna os diretórios com os CSVs em UTF-8. Anos
+    /// ainda não publicados pela CVM são ignorados.
+    pub async fn async_path(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<PathBuf>, cached_path::Error> {
+        let semaphore = Arc::new(Semaphore::new(2));
+        let handles: Vec<_> = self
+            .urls(start_date, end_date)
+            .into_iter()
+            .map(|url| {
+                let semaphore = semaphore.clone();
+                let subdir = self.path.clone();
+                tokio::spawn(
+                    async move { (url.clone(), try_download(url, subdir, semaphore).await) },
+                )
+            })
+            .collect();
+
+        let mut paths = Vec::new();
+        let mut last_error = None;
+        for handle in handles {
+            match handle.await.unwrap() {
+                (_, Ok(path)) => paths.push(path),
+                (url, Err(err)) => {
+                    log::warn!("Informe mensal de FII indisponível: {} ({})", url, err);
+                    last_error = Some(err);
+                }
+            }
+        }
+        match last_error {
+            Some(err) if paths.is_empty() => Err(err),
+            _ => Ok(paths),
+        }
+    }
+}
+
+pub fn load() -> Result<Options, config::ConfigError> {
+    get::<Options>(ROOT)
+}
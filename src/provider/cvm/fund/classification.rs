@@ -0,0 +1,638 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::index::fold;
+
+/// Situação do fundo no cadastro (`SIT`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum Situation {
+    Normal,
+    PreOperational,
+    Liquidation,
+    Incorporation,
+    UnderReview,
+    Special,
+    Cancelled,
+    Other(String),
+}
+
+impl Situation {
+    pub const ALL: [Situation; 7] = [
+        Situation::Normal,
+        Situation::PreOperational,
+        Situation::Liquidation,
+        Situation::Incorporation,
+        Situation::UnderReview,
+        Situation::Special,
+        Situation::Cancelled,
+    ];
+
+    /// Valor como aparece no cadastro.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Situation::Normal => "EM FUNCIONAMENTO NORMAL",
+            Situation::PreOperational => "FASE PRÉ-OPERACIONAL",
+            Situation::Liquidation => "LIQUIDAÇÃO",
+            Situation::Incorporation => "INCORPORAÇÃO",
+            Situation::UnderReview => "EM ANÁLISE",
+            Situation::Special => "EM SITUAÇÃO ESPECIAL",
+            Situation::Cancelled => "CANCELADA",
+            Situation::Other(value) => value,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Situation::Normal => "Em funcionamento",
+            Situation::PreOperational => "Pré-operacional",
+            Situation::Liquidation => "Em liquidação",
+            Situation::Incorporation => "Incorporação",
+            Situation::UnderReview => "Em análise",
+            Situation::Special => "Situação especial",
+            Situation::Cancelled => "Cancelado",
+            Situation::Other(value) => value,
+        }
+    }
+}
+
+impl FromStr for Situation {
+    type Err = Infallible;
+
+    /// Sem diferenciar acentos e maiúsculas; "EM LIQUIDAÇÃO" também é aceito.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = fold(s.trim());
+        let known = Situation::ALL
+            .into_iter()
+            .find(|sit| fold(sit.as_str()) == value)
+            .or_else(|| (value == "em liquidacao").then_some(Situation::Liquidation));
+        Ok(known.unwrap_or_else(|| Situation::Other(s.trim().to_string())))
+    }
+}
+
+impl fmt::Display for Situation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<String> for Situation {
+    fn from(value: String) -> Self {
+        value
+            .parse()
+            .unwrap_or_else(|never: Infallible| match never {})
+    }
+}
+
+impl From<Situation> for String {
+    fn from(value: Situation) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Classe CVM do fundo (`CLASSE`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum Class {
+    RendaFixa,
+    Acoes,
+    Cambial,
+    MultiMarket,
+    DividaExterna,
+    Referenciado,
+    CurtoPrazo,
+    Fidc,
+    FidcNp,
+    Fii,
+    Fip,
+    FipMulti,
+    FipIe,
+    FipEe,
+    FipCs,
+    Fmiee,
+    FmpFgts,
+    Funcine,
+    Other(String),
+}
+
+impl Class {
+    pub const ALL: [Class; 18] = [
+        Class::RendaFixa,
+        Class::Acoes,
+        Class::Cambial,
+        Class::MultiMarket,
+        Class::DividaExterna,
+        Class::Referenciado,
+        Class::CurtoPrazo,
+        Class::Fidc,
+        Class::FidcNp,
+        Class::Fii,
+        Class::Fip,
+        Class::FipMulti,
+        Class::FipIe,
+        Class::FipEe,
+        Class::FipCs,
+        Class::Fmiee,
+        Class::FmpFgts,
+        Class::Funcine,
+    ];
+
+    /// Valor como aparece no cadastro.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Class::RendaFixa => "Fundo de Renda Fixa",
+            Class::Acoes => "Fundo de Ações",
+            Class::Cambial => "Fundo Cambial",
+            Class::MultiMarket => "Fundo Multimercado",
+            Class::DividaExterna => "Fundo da Dívida Externa",
+            Class::Referenciado => "Referenciado",
+            Class::CurtoPrazo => "Curto Prazo",
+            Class::Fidc => "FIDC",
+            Class::FidcNp => "FIDC-NP",
+            Class::Fii => "FII",
+            Class::Fip => "FIP",
+            Class::FipMulti => "FIP Multi",
+            Class::FipIe => "FIP IE",
+            Class::FipEe => "FIP EE",
+            Class::FipCs => "FIP CS",
+            Class::Fmiee => "FMIEE",
+            Class::FmpFgts => "FMP-FGTS",
+            Class::Funcine => "FUNCINE",
+            Class::Other(value) => value,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Class::RendaFixa => "Renda Fixa",
+            Class::Acoes => "Ações",
+            Class::Cambial => "Cambial",
+            Class::MultiMarket => "Multimercado",
+            Class::DividaExterna => "Dívida Externa",
+            other => other.as_str(),
+        }
+    }
+}
+
+impl FromStr for Class {
+    type Err = Infallible;
+
+    /// Aceita o valor do cadastro ou o rótulo curto ("Ações", "Renda Fixa"), sem
+    /// diferenciar acentos e maiúsculas.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = fold(s.trim());
+        let known = Class::ALL
+            .into_iter()
+            .find(|class| fold(class.as_str()) == value || fold(class.label()) == value);
+        Ok(known.unwrap_or_else(|| Class::Other(s.trim().to_string())))
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<String> for Class {
+    fn from(value: String) -> Self {
+        value
+            .parse()
+            .unwrap_or_else(|never: Infallible| match never {})
+    }
+}
+
+impl From<Class> for String {
+    fn from(value: Class) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Tipo de fundo no cadastro (`TP_FUNDO`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum FundType {
+    Fi,
+    Fif,
+    Fitvm,
+    Fidc,
+    FidcNp,
+    Fii,
+    Fip,
+    Fmiee,
+    FmpFgts,
+    Funcine,
+    Fapi,
+    Other(String),
+}
+
+impl FundType {
+    pub const ALL: [FundType; 11] = [
+        FundType::Fi,
+        FundType::Fif,
+        FundType::Fitvm,
+        FundType::Fidc,
+        FundType::FidcNp,
+        FundType::Fii,
+        FundType::Fip,
+        FundType::Fmiee,
+        FundType::FmpFgts,
+        FundType::Funcine,
+        FundType::Fapi,
+    ];
+
+    /// Valor como aparece no cadastro.
+    pub fn as_str(&self) -> &str {
+        match self {
+            FundType::Fi => "FI",
+            FundType::Fif => "FIF",
+            FundType::Fitvm => "FITVM",
+            FundType::Fidc => "FIDC",
+            FundType::FidcNp => "FIDC-NP",
+            FundType::Fii => "FII",
+            FundType::Fip => "FIP",
+            FundType::Fmiee => "FMIEE",
+            FundType::FmpFgts => "FMP-FGTS",
+            FundType::Funcine => "FUNCINE",
+            FundType::Fapi => "FAPI",
+            FundType::Other(value) => value,
+        }
+    }
+}
+
+impl FromStr for FundType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = fold(s.trim());
+        let known = FundType::ALL
+            .into_iter()
+            .find(|kind| fold(kind.as_str()) == value);
+        Ok(known.unwrap_or_else(|| FundType::Other(s.trim().to_string())))
+    }
+}
+
+impl fmt::Display for FundType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<String> for FundType {
+    fn from(value: String) -> Self {
+        value
+            .parse()
+            .unwrap_or_else(|never: Infallible| match never {})
+    }
+}
+
+impl From<FundType> for String {
+    fn from(value: FundType) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Classificação ANBIMA do fundo (`CLASSE_ANBIMA`), no terceiro nível, como
+/// "Renda Fixa Duração Livre Crédito Livre".
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum AnbimaClass {
+    RendaFixaSimples,
+    RendaFixaIndexados,
+    RendaFixaBaixaSoberano,
+    RendaFixaBaixaGrauInvestimento,
+    RendaFixaBaixaCreditoLivre,
+    RendaFixaMediaSoberano,
+    RendaFixaMediaGrauInvestimento,
+    RendaFixaMediaCreditoLivre,
+    RendaFixaAltaSoberano,
+    RendaFixaAltaGrauInvestimento,
+    RendaFixaAltaCreditoLivre,
+    RendaFixaLivreSoberano,
+    RendaFixaLivreGrauInvestimento,
+    RendaFixaLivreCreditoLivre,
+    RendaFixaExterior,
+    RendaFixaDividaExterna,
+    AcoesIndexados,
+    AcoesIndiceAtivo,
+    AcoesValorCrescimento,
+    AcoesSmallCaps,
+    AcoesDividendos,
+    AcoesSustentabilidade,
+    AcoesSetoriais,
+    AcoesLivre,
+    AcoesFmpFgts,
+    AcoesFechados,
+    AcoesExterior,
+    MultimercadosBalanceados,
+    MultimercadosDinamico,
+    MultimercadosCapitalProtegido,
+    MultimercadosLongShortNeutro,
+    MultimercadosLongShortDirecional,
+    MultimercadosMacro,
+    MultimercadosTrading,
+    MultimercadosLivre,
+    MultimercadosJurosMoedas,
+    MultimercadosEstrategiaEspecifica,
+    MultimercadosExterior,
+    Cambial,
+    PrevidenciaRendaFixaBaixaSoberano,
+    PrevidenciaRendaFixaBaixaGrauInvestimento,
+    PrevidenciaRendaFixaMediaGrauInvestimento,
+    PrevidenciaRendaFixaLivreSoberano,
+    PrevidenciaRendaFixaLivreGrauInvestimento,
+    PrevidenciaRendaFixaLivreCreditoLivre,
+    PrevidenciaRendaFixaDataAlvo,
+    PrevidenciaAcoesIndexados,
+    PrevidenciaAcoesAtivos,
+    PrevidenciaMultimercadosLivre,
+    PrevidenciaBalanceadosAte15,
+    PrevidenciaBalanceados15a30,
+    PrevidenciaBalanceadosAcima30,
+    PrevidenciaBalanceadosDataAlvo,
+    Other(String),
+}
+
+impl AnbimaClass {
+    pub const ALL: [AnbimaClass; 53] = [
+        AnbimaClass::RendaFixaSimples,
+        AnbimaClass::RendaFixaIndexados,
+        AnbimaClass::RendaFixaBaixaSoberano,
+        AnbimaClass::RendaFixaBaixaGrauInvestimento,
+        AnbimaClass::RendaFixaBaixaCreditoLivre,
+        AnbimaClass::RendaFixaMediaSoberano,
+        AnbimaClass::RendaFixaMediaGrauInvestimento,
+        AnbimaClass::RendaFixaMediaCreditoLivre,
+        AnbimaClass::RendaFixaAltaSoberano,
+        AnbimaClass::RendaFixaAltaGrauInvestimento,
+        AnbimaClass::RendaFixaAltaCreditoLivre,
+        AnbimaClass::RendaFixaLivreSoberano,
+        AnbimaClass::RendaFixaLivreGrauInvestimento,
+        AnbimaClass::RendaFixaLivreCreditoLivre,
+        AnbimaClass::RendaFixaExterior,
+        AnbimaClass::RendaFixaDividaExterna,
+        AnbimaClass::AcoesIndexados,
+        AnbimaClass::AcoesIndiceAtivo,
+        AnbimaClass::AcoesValorCrescimento,
+        AnbimaClass::AcoesSmallCaps,
+        AnbimaClass::AcoesDividendos,
+        AnbimaClass::AcoesSustentabilidade,
+        AnbimaClass::AcoesSetoriais,
+        AnbimaClass::AcoesLivre,
+        AnbimaClass::AcoesFmpFgts,
+        AnbimaClass::AcoesFechados,
+        AnbimaClass::AcoesExterior,
+        AnbimaClass::MultimercadosBalanceados,
+        AnbimaClass::MultimercadosDinamico,
+        AnbimaClass::MultimercadosCapitalProtegido,
+        AnbimaClass::MultimercadosLongShortNeutro,
+        AnbimaClass::MultimercadosLongShortDirecional,
+        AnbimaClass::MultimercadosMacro,
+        AnbimaClass::MultimercadosTrading,
+        AnbimaClass::MultimercadosLivre,
+        AnbimaClass::MultimercadosJurosMoedas,
+        AnbimaClass::MultimercadosEstrategiaEspecifica,
+        AnbimaClass::MultimercadosExterior,
+        AnbimaClass::Cambial,
+        AnbimaClass::PrevidenciaRendaFixaBaixaSoberano,
+        AnbimaClass::PrevidenciaRendaFixaBaixaGrauInvestimento,
+        AnbimaClass::PrevidenciaRendaFixaMediaGrauInvestimento,
+        AnbimaClass::PrevidenciaRendaFixaLivreSoberano,
+        AnbimaClass::PrevidenciaRendaFixaLivreGrauInvestimento,
+        AnbimaClass::PrevidenciaRendaFixaLivreCreditoLivre,
+        AnbimaClass::PrevidenciaRendaFixaDataAlvo,
+        AnbimaClass::PrevidenciaAcoesIndexados,
+        AnbimaClass::PrevidenciaAcoesAtivos,
+        AnbimaClass::PrevidenciaMultimercadosLivre,
+        AnbimaClass::PrevidenciaBalanceadosAte15,
+        AnbimaClass::PrevidenciaBalanceados15a30,
+        AnbimaClass::PrevidenciaBalanceadosAcima30,
+        AnbimaClass::PrevidenciaBalanceadosDataAlvo,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            AnbimaClass::RendaFixaSimples => "Renda Fixa Simples",
+            AnbimaClass::RendaFixaIndexados => "Renda Fixa Indexados",
+            AnbimaClass::RendaFixaBaixaSoberano => "Renda Fixa Duração Baixa Soberano",
+            AnbimaClass::RendaFixaBaixaGrauInvestimento => {
+                "Renda Fixa Duração Baixa Grau de Investimento"
+            }
+            AnbimaClass::RendaFixaBaixaCreditoLivre => "Renda Fixa Duração Baixa Crédito Livre",
+            AnbimaClass::RendaFixaMediaSoberano => "Renda Fixa Duração Média Soberano",
+            AnbimaClass::RendaFixaMediaGrauInvestimento => {
+                "Renda Fixa Duração Média Grau de Investimento"
+            }
+            AnbimaClass::RendaFixaMediaCreditoLivre => "Renda Fixa Duração Média Crédito Livre",
+            AnbimaClass::RendaFixaAltaSoberano => "Renda Fixa Duração Alta Soberano",
+            AnbimaClass::RendaFixaAltaGrauInvestimento => {
+                "Renda Fixa Duração Alta Grau de Investimento"
+            }
+            AnbimaClass::RendaFixaAltaCreditoLivre => "Renda Fixa Duração Alta Crédito Livre",
+            AnbimaClass::RendaFixaLivreSoberano => "Renda Fixa Duração Livre Soberano",
+            AnbimaClass::RendaFixaLivreGrauInvestimento => {
+                "Renda Fixa Duração Livre Grau de Investimento"
+            }
+            AnbimaClass::RendaFixaLivreCreditoLivre => "Renda Fixa Duração Livre Crédito Livre",
+            AnbimaClass::RendaFixaExterior => "Renda Fixa Investimento no Exterior",
+            AnbimaClass::RendaFixaDividaExterna => "Renda Fixa Dívida Externa",
+            AnbimaClass::AcoesIndexados => "Ações Indexados",
+            AnbimaClass::AcoesIndiceAtivo => "Ações Índice Ativo",
+            AnbimaClass::AcoesValorCrescimento => "Ações Valor/Crescimento",
+            AnbimaClass::AcoesSmallCaps => "Ações Small Caps",
+            AnbimaClass::AcoesDividendos => "Ações Dividendos",
+            AnbimaClass::AcoesSustentabilidade => "Ações Sustentabilidade/Governança",
+            AnbimaClass::AcoesSetoriais => "Ações Setoriais",
+            AnbimaClass::AcoesLivre => "Ações Livre",
+            AnbimaClass::AcoesFmpFgts => "Ações FMP-FGTS",
+            AnbimaClass::AcoesFechados => "Ações Fechados",
+            AnbimaClass::AcoesExterior => "Ações Investimento no Exterior",
+            AnbimaClass::MultimercadosBalanceados => "Multimercados Balanceados",
+            AnbimaClass::MultimercadosDinamico => "Multimercados Dinâmico",
+            AnbimaClass::MultimercadosCapitalProtegido => "Multimercados Capital Protegido",
+            AnbimaClass::MultimercadosLongShortNeutro => "Multimercados Long and Short - Neutro",
+            AnbimaClass::MultimercadosLongShortDirecional => {
+                "Multimercados Long and Short - Direcional"
+            }
+            AnbimaClass::MultimercadosMacro => "Multimercados Macro",
+            AnbimaClass::MultimercadosTrading => "Multimercados Trading",
+            AnbimaClass::MultimercadosLivre => "Multimercados Livre",
+            AnbimaClass::MultimercadosJurosMoedas => "Multimercados Juros e Moedas",
+            AnbimaClass::MultimercadosEstrategiaEspecifica => "Multimercados Estratégia Específica",
+            AnbimaClass::MultimercadosExterior => "Multimercados Investimento no Exterior",
+            AnbimaClass::Cambial => "Cambial",
+            AnbimaClass::PrevidenciaRendaFixaBaixaSoberano => {
+                "Previdência Renda Fixa Duração Baixa Soberano"
+            }
+            AnbimaClass::PrevidenciaRendaFixaBaixaGrauInvestimento => {
+                "Previdência Renda Fixa Duração Baixa Grau de Investimento"
+            }
+            AnbimaClass::PrevidenciaRendaFixaMediaGrauInvestimento => {
+                "Previdência Renda Fixa Duração Média Grau de Investimento"
+            }
+            AnbimaClass::PrevidenciaRendaFixaLivreSoberano => {
+                "Previdência Renda Fixa Duração Livre Soberano"
+            }
+            AnbimaClass::PrevidenciaRendaFixaLivreGrauInvestimento => {
+                "Previdência Renda Fixa Duração Livre Grau de Investimento"
+            }
+            AnbimaClass::PrevidenciaRendaFixaLivreCreditoLivre => {
+                "Previdência Renda Fixa Duração Livre Crédito Livre"
+            }
+            AnbimaClass::PrevidenciaRendaFixaDataAlvo => "Previdência Renda Fixa Data Alvo",
+            AnbimaClass::PrevidenciaAcoesIndexados => "Previdência Ações Indexados",
+            AnbimaClass::PrevidenciaAcoesAtivos => "Previdência Ações Ativos",
+            AnbimaClass::PrevidenciaMultimercadosLivre => "Previdência Multimercados Livre",
+            AnbimaClass::PrevidenciaBalanceadosAte15 => "Previdência Balanceados - Até 15",
+            AnbimaClass::PrevidenciaBalanceados15a30 => "Previdência Balanceados - De 15-30",
+            AnbimaClass::PrevidenciaBalanceadosAcima30 => "Previdência Balanceados - Acima de 30",
+            AnbimaClass::PrevidenciaBalanceadosDataAlvo => "Previdência Balanceados - Data Alvo",
+            AnbimaClass::Other(value) => value,
+        }
+    }
+}
+
+impl FromStr for AnbimaClass {
+    type Err = Infallible;
+
+    /// Sem diferenciar acentos, maiúsculas e pontuação ("Valor / Crescimento",
+    /// "Long and Short Neutro").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = words(s);
+        let known = AnbimaClass::ALL
+            .into_iter()
+            .find(|class| words(class.as_str()) == value);
+        Ok(known.unwrap_or_else(|| AnbimaClass::Other(s.trim().to_string())))
+    }
+}
+
+/// Palavras do texto sem acentos, em minúsculas e separadas por um espaço.
+fn words(text: &str) -> String {
+    fold(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl fmt::Display for AnbimaClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<String> for AnbimaClass {
+    fn from(value: String) -> Self {
+        value
+            .parse()
+            .unwrap_or_else(|never: Infallible| match never {})
+    }
+}
+
+impl From<AnbimaClass> for String {
+    fn from(value: AnbimaClass) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Filtros da busca no cadastro; `None` não restringe.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filters {
+    pub class: Option<Class>,
+    pub situation: Option<Situation>,
+    pub fund_type: Option<FundType>,
+    pub anbima_class: Option<AnbimaClass>,
+}
+
+impl Filters {
+    /// Filtro padrão da busca: somente fundos em funcionamento normal.
+    pub fn active() -> Self {
+        Filters {
+            situation: Some(Situation::Normal),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<T: FromStr<Err = Infallible>>(value: &str) -> T {
+        value.parse().unwrap_or_else(|never| match never {})
+    }
+
+    #[test]
+    fn known_values_round_trip() {
+        for situation in Situation::ALL {
+            assert_eq!(parse::<Situation>(situation.as_str()), situation);
+        }
+        for class in Class::ALL {
+            assert_eq!(parse::<Class>(class.as_str()), class);
+        }
+        for fund_type in FundType::ALL {
+            assert_eq!(parse::<FundType>(fund_type.as_str()), fund_type);
+        }
+        for anbima_class in AnbimaClass::ALL {
+            assert_eq!(parse::<AnbimaClass>(anbima_class.as_str()), anbima_class);
+        }
+    }
+
+    #[test]
+    fn situation_accepts_liquidation_alias() {
+        assert_eq!(parse::<Situation>("EM LIQUIDAÇÃO"), Situation::Liquidation);
+        assert_eq!(parse::<Situation>("em liquidacao"), Situation::Liquidation);
+    }
+
+    #[test]
+    fn ignores_accents_and_case() {
+        assert_eq!(
+            parse::<Situation>("fase pre-operacional"),
+            Situation::PreOperational
+        );
+        assert_eq!(parse::<Class>("FUNDO DE ACOES"), Class::Acoes);
+        assert_eq!(parse::<Class>("renda fixa"), Class::RendaFixa);
+        assert_eq!(parse::<FundType>(" fidc-np "), FundType::FidcNp);
+        assert_eq!(
+            parse::<AnbimaClass>("multimercados dinamico"),
+            AnbimaClass::MultimercadosDinamico
+        );
+    }
+
+    #[test]
+    fn anbima_class_ignores_punctuation() {
+        assert_eq!(
+            parse::<AnbimaClass>("RENDA FIXA DURACAO LIVRE CREDITO LIVRE"),
+            AnbimaClass::RendaFixaLivreCreditoLivre
+        );
+        assert_eq!(
+            parse::<AnbimaClass>("Ações Valor / Crescimento"),
+            AnbimaClass::AcoesValorCrescimento
+        );
+        assert_eq!(
+            parse::<AnbimaClass>("Multimercados Long and Short Neutro"),
+            AnbimaClass::MultimercadosLongShortNeutro
+        );
+        // A categoria sozinha não é uma classificação completa
+        assert_eq!(
+            parse::<AnbimaClass>("Renda Fixa"),
+            AnbimaClass::Other("Renda Fixa".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_values_fall_back_to_other() {
+        assert_eq!(
+            parse::<Situation>(" SUSPENSA "),
+            Situation::Other("SUSPENSA".to_string())
+        );
+        assert_eq!(parse::<Class>("FIAGRO"), Class::Other("FIAGRO".to_string()));
+        assert_eq!(
+            parse::<FundType>("FIAGRO"),
+            FundType::Other("FIAGRO".to_string())
+        );
+        let other = parse::<Class>("FIAGRO");
+        assert_eq!(parse::<Class>(other.as_str()), other);
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::{BTreeMap, HashMap};
+
+use polars::{
+    frame::DataFrame,
+    prelude::{IdxCa, NamedFrom, PolarsResult},
+};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use super::classification::{AnbimaClass, Class, Filters, FundType, Situation};
+
+/// Peso de cada campo indexado na relevância.
+const NAME_WEIGHT: u32 = 4;
+const CNPJ_WEIGHT: u32 = 4;
+const PARTICIPANT_WEIGHT: u32 = 2;
+
+/// Índice de busca textual do cadastro, montado uma vez a partir do CSV.
+///
+/// Os termos são normalizados (sem acentos, minúsculos) e a busca casa prefixos, então
+/// "itau multi" encontra "ITAÚ MULTIMERCADO ...". O CNPJ é indexado só com dígitos.
+pub struct SearchIndex {
+    df: DataFrame,
+    class: Vec<Option<Class>>,
+    situation: Vec<Option<Situation>>,
+    fund_type: Vec<Option<FundType>>,
+    anbima_class: Vec<Option<AnbimaClass>>,
+    names: Vec<String>,
+    /// Primeiro termo do nome de cada linha.
+    first_terms: Vec<Option<String>>,
+    /// Termo -> (linha, peso do campo).
+    terms: BTreeMap<String, Vec<(u32, u32)>>,
+}
+
+impl SearchIndex {
+    pub fn new(df: DataFrame) -> Self {
+        let text = |name: &str| -> Vec<Option<String>> {
+            match df.column(name).ok().and_then(|c| c.utf8().ok()) {
+                Some(values) => values.into_iter().map(|v| v.map(str::to_string)).collect(),
+                None => vec![None; df.height()],
+            }
+        };
+        let names = text("DENOM_SOCIAL");
+        let cnpjs = text("CNPJ_FUNDO");
+        let gestores = text("GESTOR");
+        let admins = text("ADMIN");
+
+        let mut terms: BTreeMap<String, Vec<(u32, u32)>> = BTreeMap::new();
+        for row in 0..df.height() {
+            let fields = [
+                (&names[row], NAME_WEIGHT),
+                (&cnpjs[row], CNPJ_WEIGHT),
+                (&gestores[row], PARTICIPANT_WEIGHT),
+                (&admins[row], PARTICIPANT_WEIGHT),
+            ];
+            let mut seen: HashMap<String, u32> = HashMap::new();
+            for (value, weight) in fields {
+                for term in value.as_deref().map(tokenize).unwrap_or_default() {
+                    let best = seen.entry(term).or_default();
+                    *best = (*best).max(weight);
+                }
+            }
+            for (term, weight) in seen {
+                terms.entry(term).or_default().push((row as u32, weight));
+            }
+        }
+
+        let first_terms = names
+            .iter()
+            .map(|n| n.as_deref().and_then(|n| tokenize(n).into_iter().next()))
+            .collect();
+        SearchIndex {
+            first_terms,
+            class: parsed(text("CLASSE")),
+            situation: parsed(text("SIT")),
+            fund_type: parsed(text("TP_FUNDO")),
+            anbima_class: parsed(text("CLASSE_ANBIMA")),
+            names: names.into_iter().map(|n| n.unwrap_or_default()).collect(),
+            terms,
+            df,
+        }
+    }
+
+    /// Linhas que casam todos os termos da busca, da mais para a menos relevante.
+    ///
+    /// Cada termo soma o peso do campo em que aparece, em dobro quando a palavra é
+    /// exata; o primeiro termo no início do nome também pontua. Empates ficam em
+    /// ordem alfabética.
+    pub fn search(&self, query: &str) -> Vec<u32> {
+        let query = tokenize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: Option<HashMap<u32, u32>> = None;
+        for term in &query {
+            let mut matches: HashMap<u32, u32> = HashMap::new();
+            for (key, rows) in self
+                .terms
+                .range(term.clone()..)
+                .take_while(|(key, _)| key.starts_with(term.as_str()))
+            {
+                let exact = if key == term { 2 } else { 1 };
+                for &(row, weight) in rows {
+                    let score = matches.entry(row).or_default();
+                    *score = (*score).max(weight * exact);
+                }
+            }
+            scores = Some(match scores {
+                None => matches,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(row, score)| matches.get(&row).map(|s| (row, score + s)))
+                    .collect(),
+            });
+        }
+
+        let first = &query[0];
+        let mut ranked: Vec<(u32, u32)> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(row, score)| {
+                let starts = self.first_terms[row as usize]
+                    .as_ref()
+                    .is_some_and(|t| t.starts_with(first.as_str()));
+                (row, score + if starts { NAME_WEIGHT } else { 0 })
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| self.names[a.0 as usize].cmp(&self.names[b.0 as usize]))
+        });
+        ranked.into_iter().map(|(row, _)| row).collect()
+    }
+
+    /// Cadastro completo usado no índice.
+    pub fn dataframe(&self) -> &DataFrame {
+        &self.df
+    }
+
+    /// Busca e devolve as linhas do cadastro que passam nos filtros. Sem termos de
+    /// busca, devolve todas as linhas em ordem alfabética.
+    pub fn find(
+        &self,
+        query: &str,
+        filters: &Filters,
+        limit: Option<u32>,
+    ) -> PolarsResult<DataFrame> {
+        fn matches<T: PartialEq>(values: &[Option<T>], row: u32, expected: &Option<T>) -> bool {
+            expected
+                .as_ref()
+                .map_or(true, |e| values[row as usize].as_ref() == Some(e))
+        }
+        let candidates = if tokenize(query).is_empty() {
+            let mut rows: Vec<u32> = (0..self.df.height() as u32).collect();
+            rows.sort_by(|a, b| self.names[*a as usize].cmp(&self.names[*b as usize]));
+            rows
+        } else {
+            self.search(query)
+        };
+        let rows: Vec<u32> = candidates
+            .into_iter()
+            .filter(|&row| matches(&self.class, row, &filters.class))
+            .filter(|&row| matches(&self.situation, row, &filters.situation))
+            .filter(|&row| matches(&self.fund_type, row, &filters.fund_type))
+            .filter(|&row| matches(&self.anbima_class, row, &filters.anbima_class))
+            .take(limit.map_or(usize::MAX, |l| l as usize))
+            .collect();
+        self.df.take(&IdxCa::new("idx", rows))
+    }
+}
+
+fn parsed<T: std::str::FromStr>(values: Vec<Option<String>>) -> Vec<Option<T>> {
+    values
+        .into_iter()
+        .map(|v| v.and_then(|v| v.parse().ok()))
+        .collect()
+}
+
+/// Quebra o texto em termos sem acentos e em minúsculas. Trechos só com dígitos e
+/// pontuação de CNPJ (`.`, `/`, `-`) viram um único termo com os dígitos.
+fn tokenize(text: &str) -> Vec<String> {
+    let folded = fold(text);
+    let mut terms = Vec::new();
+    for chunk in folded.split_whitespace() {
+        let is_document = chunk.chars().any(|c| c.is_ascii_digit())
+            && chunk
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | '/' | '-'));
+        if is_document {
+            terms.push(chunk.chars().filter(char::is_ascii_digit).collect());
+        } else {
+            terms.extend(
+                chunk
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string),
+            );
+        }
+    }
+    terms
+}
+
+/// Remove acentos e passa para minúsculas.
+pub fn fold(text: &str) -> String {
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::Series;
+
+    use super::*;
+
+    fn index() -> SearchIndex {
+        let df = DataFrame::new(vec![
+            Series::new(
+                "CNPJ_FUNDO",
+                [
+                    "11.111.111/0001-11",
+                    "22.222.222/0001-22",
+                    "33.333.333/0001-33",
+                    "44.444.444/0001-44",
+                ],
+            ),
+            Series::new(
+                "DENOM_SOCIAL",
+                [
+                    "ITAÚ MULTIMERCADO FIC FIM",
+                    "ITAÚ RENDA FIXA FI",
+                    "VERDE MULTIMERCADO FIC FIM",
+                    "ITAUSA MULTISETORIAL FIA",
+                ],
+            ),
+            Series::new(
+                "GESTOR",
+                [
+                    Some("ITAÚ UNIBANCO ASSET"),
+                    Some("ITAÚ UNIBANCO ASSET"),
+                    Some("ITAÚ UNIBANCO ASSET"),
+                    None,
+                ],
+            ),
+        ])
+        .unwrap();
+        SearchIndex::new(df)
+    }
+
+    #[test]
+    fn folds_accents_and_case() {
+        let index = index();
+        assert_eq!(index.search("ITAÚ"), index.search("itau"));
+        assert_eq!(index.search("Itaú Renda"), vec![1]);
+    }
+
+    #[test]
+    fn matches_prefixes_of_every_term() {
+        let index = index();
+        let mut rows = index.search("multi");
+        rows.sort();
+        assert_eq!(rows, vec![0, 2, 3]);
+        assert_eq!(index.search("ita fix"), vec![1]);
+        assert!(index.search("itau inexistente").is_empty());
+    }
+
+    #[test]
+    fn ranks_name_matches_first() {
+        // Nome começando por "itau" > prefixo no nome > gestor
+        assert_eq!(index().search("itau multi"), vec![0, 3, 2]);
+    }
+
+    #[test]
+    fn exact_word_ranks_above_prefix() {
+        // "ITAUSA" vem antes de "ITAÚ" na ordem alfabética, mas só casa o prefixo
+        assert_eq!(index().search("itau"), vec![0, 1, 3, 2]);
+    }
+
+    #[test]
+    fn finds_cnpj_with_or_without_punctuation() {
+        let index = index();
+        assert_eq!(index.search("11.111.111/0001-11"), vec![0]);
+        assert_eq!(index.search("11111111000111"), vec![0]);
+        assert_eq!(index.search("22222222"), vec![1]);
+    }
+}
@@ -1,18 +1,26 @@
-use std::fmt;
+use std::{collections::HashSet, fs, path::PathBuf, sync::Arc, time::SystemTime};
+pub mod classification;
+pub mod index;
+pub mod model;
 pub mod options;
 
+pub use classification::{AnbimaClass, Class, Filters, FundType, Situation};
+use index::SearchIndex;
+pub use model::{Fund, FundIter, Participant};
 use options::{load, Options};
 use polars::{
     error::PolarsError,
     frame::DataFrame,
-    lazy::dsl::{col, lit, Expr, GetOutput, StrptimeOptions},
-    prelude::{DataType, LazyCsvReader, LazyFileListReader, LazyFrame, SortOptions},
-    series::IntoSeries,
+    lazy::dsl::{col, lit, StrptimeOptions},
+    prelude::{
+        BooleanChunked, DataType, LazyCsvReader, LazyFileListReader, LazyFrame, Series, SortOptions,
+    },
 };
 
-use regex::Regex;
 use thiserror::Error;
-use unicode_normalization::UnicodeNormalization;
+
+use super::registry::{self, Registry};
+use tokio::{sync::RwLock, task::spawn_blocking};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -21,41 +29,47 @@ pub enum Error {
 
     #[error("Error loading CSV: {0}")]
     PolarsError(#[from] polars::prelude::PolarsError),
+
+    #[error("Fund not found: {0}")]
+    NotFound(String),
 }
 
 #[derive(Clone)]
 pub struct Register {
     options: Options,
+    /// Registro CVM 175, cujos fundos e classes também entram na busca.
+    registry: Registry,
+    /// Índice de busca compartilhado entre os clones, com os arquivos e as datas de
+    /// modificação a partir dos quais foi montado.
+    index: Arc<RwLock<Option<IndexedRegister>>>,
 }
 
-pub enum Situation {
-    Normal,
+/// Índice montado a partir de uma versão do cadastro e do registro.
+struct IndexedRegister {
+    sources: Vec<Source>,
+    index: Arc<SearchIndex>,
 }
 
-impl Situation {
-    pub fn to_string(&self) -> &str {
-        match self {
-            Situation::Normal => "EM FUNCIONAMENTO NORMAL",
-        }
+impl IndexedRegister {
+    /// Os arquivos locais de origem não mudaram desde a montagem do índice.
+    fn is_current(&self) -> bool {
+        self.sources
+            .iter()
+            .all(|(path, modified)| source(path.clone()).1 == *modified)
     }
 }
 
-#[derive(Clone, PartialEq)]
-pub enum Class {
-    RendaFixa,
-    Acoes,
-    Cambial,
-    MultiMarket,
+/// Arquivo de origem do índice com sua data de modificação.
+type Source = (PathBuf, Option<SystemTime>);
+
+fn source(path: PathBuf) -> Source {
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+    (path, modified)
 }
 
-impl fmt::Display for Class {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Class::Acoes => write!(f, "Ações"),
-            Class::RendaFixa => write!(f, "Renda Fixa"),
-            Class::Cambial => write!(f, "Cambial"),
-            Class::MultiMarket => write!(f, "Multimercado"),
-        }
+impl Default for Register {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -63,46 +77,29 @@ impl Register {
     pub fn new() -> Self {
         let options = load().unwrap();
 
-        Self { options }
+        Self {
+            options,
+            registry: Registry::new(),
+            index: Arc::new(RwLock::new(None)),
+        }
     }
 
+    /// Busca no cadastro pelo nome, gestor, administrador ou CNPJ, em ordem de
+    /// relevância; sem palavra-chave, lista os fundos filtrados em ordem alfabética.
     pub async fn async_find(
         &self,
         keyword: Option<String>,
-        class: Option<Class>,
-        situation: Option<Situation>,
+        filters: Filters,
         limit: Option<u32>,
     ) -> Result<DataFrame, Error> {
-        let path = self.options.async_path().await?;
-        let lf = LazyCsvReader::new(&path)
-            .has_header(true)
-            .with_infer_schema_length(None)
-            .with_delimiter(b';')
-            // .with_cache(true)
-            .finish()?;
-
-        let mut filtered = lf.clone();
-
-        if let Some(keyword) = keyword {
-            filtered = filtered.filter(self.contains_normalized(keyword));
-        }
-
-        if let Some(class) = class {
-            filtered = filtered.filter(col("CLASSE").eq(lit(class.to_string())));
-        }
-
-        let sit = situation.unwrap_or(Situation::Normal);
-        filtered = filtered.filter(col("SIT").eq(lit(sit.to_string())));
-
-        if let Some(limit) = limit {
-            filtered = filtered.limit(limit);
-        }
-
-        let res = filtered
-            .sort("DENOM_SOCIAL", SortOptions::default())
-            .collect()?;
+        let index = self.search_index().await?;
+        let keyword = keyword.unwrap_or_default();
+        Ok(index.find(&keyword, &filters, limit)?)
+    }
 
-        Ok(res)
+    /// Cadastro completo, o mesmo lido para o índice de busca.
+    pub async fn async_register(&self) -> Result<DataFrame, Error> {
+        Ok(self.search_index().await?.dataframe().clone())
     }
 
     pub async fn async_find_by_cnpj(
@@ -131,38 +128,86 @@ impl Register {
         Ok(res)
     }
 
-    // Função para normalizar texto removendo acentos
-    // NOTE: Egui não suporta Unicode completo, então é necessário normalizar certas palavras.
-    // Por ex: "grão" vira "grao", "ações" - "acoes" etc...
-    fn contains_normalized(&self, keyword: String) -> Expr {
-        let q = format!("(?i){}", keyword);
-        let re = Regex::new(r"\p{M}").unwrap();
-        col("DENOM_SOCIAL")
-            .apply(
-                move |s: polars::prelude::Series| {
-                    // Assuming "DENOM_SOCIAL" is a Utf8String type column
-                    let utf8_series = s.utf8().expect("Expected Utf8String series");
-                    // Normalize each string in the series
-                    let normalized_series = utf8_series
-                        .into_iter()
-                        .map(|opt_str| {
-                            opt_str.map(|s| {
-                                let decomposed = s.nfkd().collect::<String>();
-                                let t = re.replace_all(&decomposed, "").into_owned();
-                                t
-                            })
-                        })
-                        .collect::<polars::prelude::Utf8Chunked>();
-                    // Convert the Utf8Chunked back to a Series
-                    let result_series: polars::prelude::Series = normalized_series.into_series();
-                    // Wrap the Series in Some and then Ok
-                    Ok(Some(result_series))
-                },
-                GetOutput::default(),
-            )
-            .str()
-            .contains(lit(q.clone()), false)
-            .or(col("CNPJ_FUNDO").str().contains(lit(q), false))
+    pub async fn async_funds(
+        &self,
+        keyword: Option<String>,
+        filters: Filters,
+        limit: Option<u32>,
+    ) -> Result<Vec<Fund>, Error> {
+        let df = self.async_find(keyword, filters, limit).await?;
+        Ok(Fund::iter(&df).collect())
+    }
+
+    pub async fn async_fund_by_cnpj(
+        &self,
+        cnpj: String,
+        offline: bool,
+    ) -> Result<Option<Fund>, Error> {
+        let df = self.async_find_by_cnpj(cnpj, offline).await?;
+        Ok(Fund::iter(&df).next())
+    }
+
+    /// Descarta o índice de busca, remontado na próxima busca a partir dos arquivos
+    /// atuais. Chamado quando a sincronização baixa novas versões.
+    pub async fn invalidate(&self) {
+        *self.index.write().await = None;
+    }
+
+    /// Índice de busca do cadastro atual, acrescido dos fundos e classes que só existem
+    /// no registro da Resolução CVM 175. Os arquivos são localizados uma única vez; o
+    /// índice é remontado quando um deles muda de data de modificação ou quando é
+    /// descartado por `invalidate`.
+    async fn search_index(&self) -> Result<Arc<SearchIndex>, Error> {
+        if let Some(indexed) = self.index.read().await.as_ref() {
+            if indexed.is_current() {
+                return Ok(indexed.index.clone());
+            }
+        }
+
+        let mut guard = self.index.write().await;
+        // Outra tarefa pode ter remontado o índice enquanto esperávamos a escrita
+        if let Some(indexed) = guard.as_ref().filter(|i| i.is_current()) {
+            return Ok(indexed.index.clone());
+        }
+
+        let path = self.options.async_path().await?;
+        let registry_dir = match self.registry.async_dir().await {
+            Ok(dir) => Some(dir),
+            Err(err) => {
+                log::warn!("Registro CVM 175 fora da busca: {}", err);
+                None
+            }
+        };
+        let mut sources = vec![source(path.clone())];
+        if let Some(dir) = &registry_dir {
+            sources.push(source(dir.join(registry::FUNDS_FILE)));
+            sources.push(source(dir.join(registry::CLASSES_FILE)));
+        }
+
+        let index = spawn_blocking(move || -> Result<SearchIndex, Error> {
+            let mut df = LazyCsvReader::new(&path)
+                .has_header(true)
+                .with_infer_schema_length(None)
+                .with_delimiter(b';')
+                .finish()?
+                .collect()?;
+            if let Some(dir) = registry_dir {
+                match Registry::register_rows(&dir) {
+                    Ok(rows) => df = with_registry_rows(df, &rows)?,
+                    Err(err) => log::error!("Falha ao indexar o registro CVM 175: {}", err),
+                }
+            }
+            Ok(SearchIndex::new(df))
+        })
+        .await
+        .map_err(|err| PolarsError::ComputeError(err.to_string().into()))??;
+
+        let index = Arc::new(index);
+        *guard = Some(IndexedRegister {
+            sources,
+            index: index.clone(),
+        });
+        Ok(index)
     }
 
     pub async fn async_stats(&self) -> Result<(DataFrame, DataFrame, DataFrame), Error> {
@@ -224,3 +269,35 @@ impl Register {
             .collect()
     }
 }
+
+/// Acrescenta ao cadastro as linhas do registro cujo CNPJ não está nele, com as colunas
+/// e os tipos do cadastro (as que o registro não tem ficam nulas).
+fn with_registry_rows(register: DataFrame, rows: &DataFrame) -> Result<DataFrame, PolarsError> {
+    let known: HashSet<&str> = register
+        .column("CNPJ_FUNDO")?
+        .utf8()?
+        .into_iter()
+        .flatten()
+        .collect();
+    let mask: BooleanChunked = rows
+        .column("CNPJ_FUNDO")?
+        .utf8()?
+        .into_iter()
+        .map(|cnpj| cnpj.is_some_and(|cnpj| !known.contains(cnpj)))
+        .collect();
+    let rows = rows.filter(&mask)?;
+
+    let columns = register
+        .get_columns()
+        .iter()
+        .map(|column| match rows.column(column.name()) {
+            Ok(values) => values.cast(column.dtype()),
+            Err(_) => Ok(Series::full_null(
+                column.name(),
+                rows.height(),
+                column.dtype(),
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    register.vstack(&DataFrame::new(columns)?)
+}
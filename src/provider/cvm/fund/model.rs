@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use polars::{datatypes::AnyValue, frame::DataFrame};
+
+use super::classification::{AnbimaClass, Class, FundType, Situation};
+
+/// Registro de um fundo no cadastro da CVM (`cad_fi.csv`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fund {
+    pub cnpj: String,
+    pub denomination: String,
+    pub fund_type: Option<FundType>,
+    pub cvm_code: Option<String>,
+    pub class: Option<Class>,
+    pub anbima_class: Option<AnbimaClass>,
+    pub situation: Option<Situation>,
+    pub registration_date: Option<NaiveDate>,
+    pub constitution_date: Option<NaiveDate>,
+    pub cancellation_date: Option<NaiveDate>,
+    pub situation_start_date: Option<NaiveDate>,
+    pub activity_start_date: Option<NaiveDate>,
+    pub class_start_date: Option<NaiveDate>,
+    pub condominium: Option<String>,
+    pub fund_of_funds: Option<bool>,
+    pub exclusive: Option<bool>,
+    pub target_audience: Option<String>,
+    pub admin: Option<Participant>,
+    pub gestor: Option<Participant>,
+    pub auditor: Option<Participant>,
+    pub custodian: Option<Participant>,
+    pub admin_fee: Option<f64>,
+    pub admin_fee_info: Option<String>,
+    pub performance_fee: Option<f64>,
+    pub performance_fee_info: Option<String>,
+    pub net_worth: Option<f64>,
+    pub net_worth_date: Option<NaiveDate>,
+}
+
+/// Prestador de serviço do fundo (administrador, gestor, auditor ou custodiante).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Participant {
+    pub name: String,
+    pub document: Option<String>,
+}
+
+impl Fund {
+    /// Monta o fundo a partir de uma linha do DataFrame do cadastro.
+    /// Retorna `None` se a linha não existir ou não tiver CNPJ.
+    pub fn from_row(df: &DataFrame, row: usize) -> Option<Self> {
+        if row >= df.height() {
+            return None;
+        }
+
+        let text = |name: &str| value(df, name, row);
+        let date = |name: &str| text(name).and_then(|v| parse_date(&v));
+        let number = |name: &str| text(name).and_then(|v| parse_number(&v));
+        let flag = |name: &str| text(name).map(|v| v.eq_ignore_ascii_case("S"));
+        let participant = |name: &str, document: &str| {
+            text(name).map(|name| Participant {
+                name,
+                document: text(document),
+            })
+        };
+
+        Some(Fund {
+            cnpj: text("CNPJ_FUNDO")?,
+            denomination: text("DENOM_SOCIAL").unwrap_or_default(),
+            fund_type: text("TP_FUNDO").and_then(parse),
+            cvm_code: text("CD_CVM"),
+            class: text("CLASSE").and_then(parse),
+            anbima_class: text("CLASSE_ANBIMA").and_then(parse),
+            situation: text("SIT").and_then(parse),
+            registration_date: date("DT_REG"),
+            constitution_date: date("DT_CONST"),
+            cancellation_date: date("DT_CANCEL"),
+            situation_start_date: date("DT_INI_SIT"),
+            activity_start_date: date("DT_INI_ATIV"),
+            class_start_date: date("DT_INI_CLASSE"),
+            condominium: text("CONDOM"),
+            fund_of_funds: flag("FUNDO_COTAS"),
+            exclusive: flag("FUNDO_EXCLUSIVO"),
+            target_audience: text("PUBLICO_ALVO"),
+            admin: participant("ADMIN", "CNPJ_ADMIN"),
+            gestor: participant("GESTOR", "CPF_CNPJ_GESTOR"),
+            auditor: participant("AUDITOR", "CNPJ_AUDITOR"),
+            custodian: participant("CUSTODIANTE", "CNPJ_CUSTODIANTE"),
+            admin_fee: number("TAXA_ADM"),
+            admin_fee_info: text("INF_TAXA_ADM"),
+            performance_fee: number("TAXA_PERFM"),
+            performance_fee_info: text("INF_TAXA_PERFM"),
+            net_worth: number("VL_PATRIM_LIQ"),
+            net_worth_date: date("DT_PATRIM_LIQ"),
+        })
+    }
+
+    /// Percorre todas as linhas do DataFrame do cadastro como `Fund`.
+    pub fn iter(df: &DataFrame) -> FundIter<'_> {
+        FundIter { df, row: 0 }
+    }
+}
+
+pub struct FundIter<'a> {
+    df: &'a DataFrame,
+    row: usize,
+}
+
+impl<'a> Iterator for FundIter<'a> {
+    type Item = Fund;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.row < self.df.height() {
+            let row = self.row;
+            self.row += 1;
+            if let Some(fund) = Fund::from_row(self.df, row) {
+                return Some(fund);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.df.height() - self.row))
+    }
+}
+
+fn value(df: &DataFrame, name: &str, row: usize) -> Option<String> {
+    let value = df.column(name).ok()?.get(row).ok()?;
+    let text = match value {
+        AnyValue::Null => return None,
+        AnyValue::Utf8(s) => s.to_string(),
+        other => other.to_string(),
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+fn parse<T: FromStr>(value: String) -> Option<T> {
+    value.parse().ok()
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.replace(',', ".").parse::<f64>().ok()
+}
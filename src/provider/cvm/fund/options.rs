@@ -4,12 +4,12 @@ use std::{
     path::PathBuf,
 };
 
-use cached_path::{cached_path_with_options, Cache};
+use cached_path::Cache;
 use encoding_rs::WINDOWS_1252;
 use serde::{Deserialize, Serialize};
 use tokio::task::spawn_blocking;
 
-use crate::config::get;
+use crate::{catalog, config::get};
 
 const ROOT: &str = "cvm.fundo.cadastro";
 
@@ -24,29 +24,20 @@ impl Options {
     pub async fn async_path(&self) -> Result<PathBuf, cached_path::Error> {
         let url = self.url.clone();
         let subdir = self.path.clone();
+        let dataset = catalog::dataset_key(&subdir, &url);
         // Baixa o arquivo usando `cached_path`
-        let path = spawn_blocking(move || {
-            let res = cached_path_with_options(
-                url.as_str(),
-                &cached_path::Options::default().subdir(&subdir),
-            );
-
-            match res {
-                Ok(path) => Ok(path),
-                Err(_err) => {
-                    let cache = Cache::builder()
-                        .progress_bar(Some(cached_path::ProgressBar::Full))
-                        .offline(true)
-                        .build()?;
-                    cache.cached_path_with_options(
-                        url.as_str(),
-                        &cached_path::Options::default().subdir(&subdir),
-                    )
-                }
-            }
-        })
-        .await
-        .unwrap()?;
+        let path = {
+            let dataset = dataset.clone();
+            spawn_blocking(move || {
+                catalog::fetch(
+                    &dataset,
+                    url.as_str(),
+                    &cached_path::Options::default().subdir(&subdir),
+                )
+            })
+            .await
+            .unwrap()?
+        };
         // Cria uma cópia do caminho para usar na verificação e na conversão
         let utf8_path = PathBuf::from(format!("{}.utf8", path.display()));
         // Verifica se o arquivo já foi convertido para UTF-8
@@ -70,6 +61,7 @@ impl Options {
             // Salva o arquivo convertido
             let mut output_file = File::create(&utf8_path_clone)?;
             output_file.write_all(cow.as_bytes())?;
+            catalog::set_rows(&dataset, cow.lines().count().saturating_sub(1));
             // Retorna o caminho do arquivo convertido
             Ok::<_, std::io::Error>(utf8_path_clone)
         })
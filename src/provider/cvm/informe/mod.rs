@@ -1,112 +1,67 @@
 use chrono::NaiveDate;
-use glob::glob;
 pub mod options;
+pub mod store;
 use options::{load, Options};
 use polars::{
-    datatypes::DataType,
     error::PolarsError,
     frame::DataFrame,
     lazy::{
-        dsl::{col, concat, lit, StrptimeOptions},
+        dsl::{col, concat, lit},
         frame::LazyFrame,
     },
-    prelude::{when, IntoLazy, SortOptions, UnionArgs},
+    prelude::{when, IntoLazy, ScanArgsParquet, SortOptions, UnionArgs},
 };
 
-use super::{align_columns, get_all_columns, read_csv_lazy};
-
 #[derive(Clone)]
 pub struct Informe {
     options: Options,
 }
 
+impl Default for Informe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Informe {
     pub fn new() -> Self {
         let options = load().unwrap();
         Self { options }
     }
 
+    /// Informes diários do período, lidos dos arquivos Parquet do armazenamento local.
+    /// Os filtros aplicados sobre o `LazyFrame` são empurrados para a leitura dos arquivos.
     pub async fn async_informes(
         &self,
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<LazyFrame, PolarsError> {
-        let result = self
+        let paths = self
             .options
             .async_path(Some(start_date), Some(end_date))
-            .await;
-        match result {
-            Ok(paths) => {
-                let mut frames: Vec<LazyFrame> = Vec::new();
-                for path in paths {
-                    let pattern = format!("{}/*", path.display());
-                    for path in glob(&pattern).unwrap().filter_map(Result::ok) {
-                        let file = path.display().to_string();
-                        let res = read_csv_lazy(&file);
-                        match res {
-                            Ok(mut lf) => {
-                                lf = lf.select(&[
-                                    col("CNPJ_FUNDO"),
-                                    col("DT_COMPTC"),
-                                    col("VL_QUOTA"),
-                                ]);
-
-                                let lf = lf
-                                    .with_column(
-                                        col("DT_COMPTC")
-                                            .str()
-                                            .strptime(
-                                                DataType::Date,
-                                                StrptimeOptions {
-                                                    format: Some("%Y-%m-%d".into()),
-                                                    ..Default::default()
-                                                },
-                                            )
-                                            .cast(DataType::Date)
-                                            .alias("AS_DATE"),
-                                    )
-                                    .filter(col("AS_DATE").gt_eq(lit(start_date)).and(
-                                        col("AS_DATE").cast(DataType::Date).lt_eq(lit(end_date)),
-                                    ))
-                                    .collect()
-                                    .unwrap()
-                                    .lazy();
+            .await
+            .map_err(|err| PolarsError::NoData(err.to_string().into()))?;
 
-                                frames.push(lf)
-                            }
-                            Err(err) => {
-                                log::error!("err {}", err);
-                            }
-                        }
-                    }
-                }
-                let all_columns = get_all_columns(&frames);
-
-                let aligned_lfs: Vec<LazyFrame> = frames
-                    .into_iter()
-                    .map(|lf| align_columns(lf, &all_columns))
-                    .collect();
+        let store = self.options.store_path();
+        let files = tokio::task::spawn_blocking(move || store::ingest_all(&paths, &store))
+            .await
+            .map_err(|err| PolarsError::ComputeError(err.to_string().into()))??;
+        if files.is_empty() {
+            return Err(PolarsError::NoData(
+                "Nenhum informe disponível no período".into(),
+            ));
+        }
 
-                // Concatenar em blocos se o número de LazyFrames for grande
-                let result = if aligned_lfs.len() > 10 {
-                    let mut concatenated = aligned_lfs[0].clone();
-                    for chunk in aligned_lfs.chunks(10) {
-                        let partial_concat = concat(chunk, UnionArgs::default())?;
-                        concatenated =
-                            concat(&[concatenated, partial_concat], UnionArgs::default())?;
-                    }
-                    Ok(concatenated)
-                } else {
-                    concat(&aligned_lfs, UnionArgs::default())
-                };
+        let frames = files
+            .iter()
+            .map(|file| LazyFrame::scan_parquet(file, ScanArgsParquet::default()))
+            .collect::<Result<Vec<_>, _>>()?;
 
-                match result {
-                    Ok(lf) => Ok(lf),
-                    Err(err) => Err(err),
-                }
-            }
-            Err(err) => Err(PolarsError::NoData(err.to_string().into())),
-        }
+        Ok(concat(&frames, UnionArgs::default())?.filter(
+            col("DT_COMPTC")
+                .gt_eq(lit(start_date))
+                .and(col("DT_COMPTC").lt_eq(lit(end_date))),
+        ))
     }
 
     pub async fn async_profit(
@@ -115,57 +70,126 @@ impl Informe {
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<DataFrame, PolarsError> {
-        let res = self.async_informes(start_date, end_date).await.unwrap();
-        // Ajustar a rentabilidade acumulada
-        let cotas = res
-            .filter(col("CNPJ_FUNDO").str().contains(lit(cnpj), false))
-            .with_column(col("VL_QUOTA").cast(DataType::Float64).alias("valor_float"))
+        let res = self.async_informes(start_date, end_date).await?;
+        profit(res, cnpj, start_date, end_date)
+    }
+
+    /// Patrimônio líquido, captações, resgates e número de cotistas do fundo no período,
+    /// com a captação líquida do dia em `CAPTC_LIQ`.
+    pub async fn async_flows(
+        &self,
+        cnpj: String,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<DataFrame, PolarsError> {
+        let res = self.async_informes(start_date, end_date).await?;
+        let flows = res
+            .filter(col("CNPJ_FUNDO").eq(lit(cnpj)))
+            .select([
+                col("DT_COMPTC").alias("AS_DATE"),
+                col("VL_PATRIM_LIQ"),
+                col("CAPTC_DIA"),
+                col("RESG_DIA"),
+                col("NR_COTST"),
+            ])
             .with_column(
-                col("DT_COMPTC")
-                    .str()
-                    .strptime(
-                        DataType::Date,
-                        StrptimeOptions {
-                            format: Some("%Y-%m-%d".into()),
-                            ..Default::default()
-                        },
-                    )
-                    .alias("AS_DATE"),
-            )
-            .filter(
-                col("AS_DATE")
-                    .gt_eq(lit(start_date))
-                    .and(col("AS_DATE").lt_eq(lit(end_date))),
+                (col("CAPTC_DIA").fill_null(lit(0.0)) - col("RESG_DIA").fill_null(lit(0.0)))
+                    .alias("CAPTC_LIQ"),
             )
             .sort("AS_DATE", SortOptions::default())
             .collect()?;
-        // Verificar se há dados suficientes para cálculo
-        if cotas.height() == 0 {
+        if flows.height() == 0 {
             return Err(PolarsError::NoData(
                 "Nenhum dado encontrado no intervalo".into(),
             ));
         }
-        // Calcular rentabilidade diária e acumulada
-        let df_with_rent_acc = cotas
-            .lazy()
-            .with_column(
-                // Verificar se o valor anterior é diferente de zero antes de calcular a rentabilidade
-                when(col("valor_float").shift(1).gt(lit(0.0)))
-                    .then(col("valor_float") / col("valor_float").shift(1) - lit(1.0))
-                    .otherwise(lit(0.0))
-                    .fill_null(lit(0.0)) // Preencher valores nulos com 0
-                    .alias("DAILY_RETURN"),
-            )
-            .with_column(
-                (col("DAILY_RETURN") + lit(1.0))
-                    .cumprod(false) // Produto acumulado
-                    .alias("CUMULATIVE_PRODUCT"),
-            )
-            .with_column(((col("CUMULATIVE_PRODUCT") - lit(1.0)) * lit(100.0)).alias("RENT_ACUM"))
-            //.drop("DAILY_RETURN") // Remover coluna intermediária, se desejado
-            .sort("AS_DATE", SortOptions::default()) // Ordena por data
-            .collect()?;
+        Ok(flows)
+    }
+
+    /// Resumo de todos os fundos no período: rentabilidade entre a primeira e a última
+    /// cota em `RETURN_12M` (%), além do último patrimônio líquido e número de cotistas.
+    pub async fn async_summary(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<DataFrame, PolarsError> {
+        let res = self.async_informes(start_date, end_date).await?;
+        let last = |name: &str| col(name).sort_by([col("DT_COMPTC")], [false]).last();
+        res.filter(col("VL_QUOTA").gt(lit(0.0)))
+            .groupby([col("CNPJ_FUNDO")])
+            .agg([
+                ((last("VL_QUOTA") / col("VL_QUOTA").sort_by([col("DT_COMPTC")], [false]).first()
+                    - lit(1.0))
+                    * lit(100.0))
+                .alias("RETURN_12M"),
+                last("VL_PATRIM_LIQ"),
+                last("NR_COTST"),
+            ])
+            .collect()
+    }
 
-        Ok(df_with_rent_acc)
+    /// Rentabilidade de vários fundos no mesmo período, lendo os informes uma única vez.
+    pub async fn async_profits(
+        &self,
+        cnpjs: &[String],
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<(String, Result<DataFrame, PolarsError>)>, PolarsError> {
+        let res = self.async_informes(start_date, end_date).await?;
+        Ok(cnpjs
+            .iter()
+            .map(|cnpj| {
+                let profit = profit(res.clone(), cnpj.clone(), start_date, end_date);
+                (cnpj.clone(), profit)
+            })
+            .collect())
     }
 }
+
+fn profit(
+    res: LazyFrame,
+    cnpj: String,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<DataFrame, PolarsError> {
+    // Ajustar a rentabilidade acumulada
+    let cotas = res
+        .filter(col("CNPJ_FUNDO").eq(lit(cnpj)))
+        .with_column(col("VL_QUOTA").alias("valor_float"))
+        .with_column(col("DT_COMPTC").alias("AS_DATE"))
+        .filter(
+            col("AS_DATE")
+                .gt_eq(lit(start_date))
+                .and(col("AS_DATE").lt_eq(lit(end_date))),
+        )
+        .sort("AS_DATE", SortOptions::default())
+        .collect()?;
+    // Verificar se há dados suficientes para cálculo
+    if cotas.height() == 0 {
+        return Err(PolarsError::NoData(
+            "Nenhum dado encontrado no intervalo".into(),
+        ));
+    }
+    // Calcular rentabilidade diária e acumulada
+    let df_with_rent_acc = cotas
+        .lazy()
+        .with_column(
+            // Verificar se o valor anterior é diferente de zero antes de calcular a rentabilidade
+            when(col("valor_float").shift(1).gt(lit(0.0)))
+                .then(col("valor_float") / col("valor_float").shift(1) - lit(1.0))
+                .otherwise(lit(0.0))
+                .fill_null(lit(0.0)) // Preencher valores nulos com 0
+                .alias("DAILY_RETURN"),
+        )
+        .with_column(
+            (col("DAILY_RETURN") + lit(1.0))
+                .cumprod(false) // Produto acumulado
+                .alias("CUMULATIVE_PRODUCT"),
+        )
+        .with_column(((col("CUMULATIVE_PRODUCT") - lit(1.0)) * lit(100.0)).alias("RENT_ACUM"))
+        //.drop("DAILY_RETURN") // Remover coluna intermediária, se desejado
+        .sort("AS_DATE", SortOptions::default()) // Ordena por data
+        .collect()?;
+
+    Ok(df_with_rent_acc)
+}
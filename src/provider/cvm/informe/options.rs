@@ -14,6 +14,8 @@ pub struct Options {
     pub url: String,
     pub historical_url: String,
     pub path: String,
+    /// Subdiretório do cache com os informes convertidos para Parquet
+    pub store: String,
 }
 
 impl Options {
@@ -66,6 +68,10 @@ impl Options {
         Ok(paths)
     }
 
+    pub fn store_path(&self) -> PathBuf {
+        std::env::temp_dir().join("cache").join(&self.store)
+    }
+
     fn generate_patterns(
         &self,
         start_date: NaiveDate,
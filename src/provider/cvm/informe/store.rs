@@ -0,0 +1,110 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use glob::glob;
+use polars::{
+    datatypes::DataType,
+    error::PolarsError,
+    lazy::dsl::{col, Expr, StrptimeOptions},
+    prelude::{ParquetWriter, SortOptions},
+};
+
+use crate::provider::cvm::{cnpj_key, column_or_null, read_csv_lazy};
+
+/// Linhas por row group; com os dados ordenados por CNPJ, a estatística de cada
+/// grupo permite descartar quase todo o arquivo ao filtrar um fundo.
+const ROW_GROUP_SIZE: usize = 16_384;
+
+/// Colunas numéricas mantidas no Parquet, todas convertidas para `Float64`.
+const NUMERIC_COLUMNS: [&str; 6] = [
+    "VL_QUOTA",
+    "VL_PATRIM_LIQ",
+    "VL_TOTAL",
+    "CAPTC_DIA",
+    "RESG_DIA",
+    "NR_COTST",
+];
+
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Converte os CSVs de cada diretório baixado em arquivos Parquet tipados, um por mês,
+/// e devolve os caminhos dos arquivos gerados (ou reaproveitados).
+pub fn ingest_all(paths: &[PathBuf], store: &Path) -> Result<Vec<PathBuf>, PolarsError> {
+    let mut files = Vec::new();
+    for path in paths {
+        let pattern = format!("{}/*.csv", path.display());
+        for csv in glob(&pattern).unwrap().filter_map(Result::ok) {
+            match ingest(&csv, store) {
+                Ok(file) => files.push(file),
+                Err(err) => log::error!("Falha ao converter {}: {}", csv.display(), err),
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Gera `<store>/<nome do csv>.parquet`. O arquivo é reaproveitado enquanto for mais
+/// recente que o CSV de origem; o mês corrente é regerado quando a CVM o atualiza.
+pub fn ingest(csv: &Path, store: &Path) -> Result<PathBuf, PolarsError> {
+    let stem = csv
+        .file_stem()
+        .ok_or_else(|| PolarsError::NoData(format!("{}", csv.display()).into()))?
+        .to_string_lossy();
+    let target = store.join(format!("{}.parquet", stem));
+    if is_fresh(&target, csv) {
+        return Ok(target);
+    }
+
+    fs::create_dir_all(store)?;
+    let lf = read_csv_lazy(&csv.display().to_string())?;
+    let schema = lf.schema()?;
+
+    let mut columns = vec![cnpj_key(&schema).alias("CNPJ_FUNDO"), date_column()];
+    columns.extend(
+        NUMERIC_COLUMNS
+            .iter()
+            .map(|name| column_or_null(&schema, name, DataType::Float64)),
+    );
+    let mut df = lf
+        .select(&columns)
+        .filter(col("CNPJ_FUNDO").is_not_null())
+        .sort("CNPJ_FUNDO", SortOptions::default())
+        .collect()?;
+
+    // Escreve em um arquivo temporário para não expor um Parquet incompleto
+    let tmp = store.join(format!(
+        "{}.{}-{}.tmp",
+        stem,
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    ParquetWriter::new(File::create(&tmp)?)
+        .with_statistics(true)
+        .with_row_group_size(Some(ROW_GROUP_SIZE))
+        .finish(&mut df)?;
+    fs::rename(&tmp, &target)?;
+
+    log::info!("Informe convertido: {}", target.display());
+    Ok(target)
+}
+
+fn is_fresh(target: &Path, source: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(target), modified(source)) {
+        (Some(target), Some(source)) => target >= source,
+        _ => false,
+    }
+}
+
+fn date_column() -> Expr {
+    col("DT_COMPTC").str().strptime(
+        DataType::Date,
+        StrptimeOptions {
+            format: Some("%Y-%m-%d".into()),
+            ..Default::default()
+        },
+    )
+}
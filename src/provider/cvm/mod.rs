@@ -5,20 +5,24 @@ use std::{
     sync::Arc,
 };
 
-use cached_path::Cache;
 use encoding_rs::WINDOWS_1252;
 
 use polars::{
     error::PolarsError,
     lazy::dsl::{col, lit, Expr},
-    prelude::{DataType, LazyCsvReader, LazyFileListReader, LazyFrame, NULL},
+    prelude::{DataType, LazyCsvReader, LazyFileListReader, LazyFrame, Schema, NULL},
 };
 
 use tokio::sync::Semaphore;
 
+use crate::catalog;
+
+pub mod fidc;
+pub mod fii;
 pub mod fund;
 pub mod informe;
 pub mod portfolio;
+pub mod registry;
 
 fn read_csv_lazy(file_path: &str) -> Result<LazyFrame, PolarsError> {
     LazyCsvReader::new(file_path)
@@ -31,52 +35,29 @@ fn read_csv_lazy(file_path: &str) -> Result<LazyFrame, PolarsError> {
         .finish()
 }
 
-fn get_all_columns(lfs: &[LazyFrame]) -> Vec<String> {
-    let mut columns = std::collections::HashSet::new();
-    for lf in lfs {
-        if let Ok(schema) = lf.schema() {
-            for field in schema.iter_fields() {
-                columns.insert(field.name().clone());
-            }
-        }
+/// Coluna convertida para `data_type`, ou nula desse tipo quando o arquivo não a traz.
+pub(crate) fn column_or_null(schema: &Schema, name: &str, data_type: DataType) -> Expr {
+    if schema.get(name).is_some() {
+        col(name).cast(data_type)
+    } else {
+        lit(NULL).cast(data_type).alias(name)
     }
-    columns.into_iter().map(|s| s.to_string()).collect()
 }
 
-fn align_columns(lf: LazyFrame, all_columns: &[String]) -> LazyFrame {
-    let mut new_lf = lf;
-    for column in all_columns {
-        if !new_lf
-            .schema()
-            .unwrap()
-            .iter_fields()
-            .any(|f| f.name() == column)
-        {
-            new_lf = new_lf.with_column(lit(NULL).alias(column));
-        }
-    }
-    new_lf
-}
-
-fn align_and_convert_columns_to_string(lf: LazyFrame, all_columns: &[String]) -> LazyFrame {
-    let mut aligned_columns: Vec<Expr> = Vec::new();
-    for co in all_columns {
-        if lf.schema().unwrap().get_field(co).is_some() {
-            aligned_columns.push(col(co));
-        } else {
-            aligned_columns.push(lit(NULL).alias(co));
-        }
-    }
-
-    let aligned_lf = lf.select(&aligned_columns);
-    let aligned_lf = aligned_lf.with_columns(
-        all_columns
-            .iter()
-            .map(|co| col(co).cast(DataType::Utf8))
-            .collect::<Vec<_>>(),
-    );
-
-    aligned_lf
+/// CNPJ que identifica as linhas do arquivo. Os arquivos posteriores à Resolução CVM 175
+/// trazem o CNPJ da classe (`CNPJ_FUNDO_CLASSE`, ou `CNPJ_Fundo_Classe` nos informes de
+/// FII) no lugar do CNPJ do fundo.
+pub(crate) fn cnpj_key(schema: &Schema) -> Expr {
+    [
+        "CNPJ_FUNDO_CLASSE",
+        "CNPJ_Fundo_Classe",
+        "CNPJ_FUNDO",
+        "CNPJ_Fundo",
+    ]
+    .into_iter()
+    .find(|name| schema.get(name).is_some())
+    .map(col)
+    .unwrap_or_else(|| lit(NULL).cast(DataType::Utf8))
 }
 
 pub async fn try_download(
@@ -87,28 +68,12 @@ pub async fn try_download(
     let permit = semaphore.clone().acquire_owned().await.unwrap();
 
     tokio::task::spawn_blocking(move || {
-        let cache = Cache::builder()
-            .progress_bar(Some(cached_path::ProgressBar::Full))
-            .build()?;
-
-        let res = cache.cached_path_with_options(
+        let dataset = catalog::dataset_key(&subdir, &url);
+        let result = catalog::fetch(
+            &dataset,
             url.as_str(),
             &cached_path::Options::default().extract().subdir(&subdir),
-        );
-
-        let result = match res {
-            Ok(path) => Ok(path),
-            Err(_err) => {
-                let cache = Cache::builder()
-                    .progress_bar(Some(cached_path::ProgressBar::Full))
-                    .offline(true)
-                    .build()?;
-                cache.cached_path_with_options(
-                    url.as_str(),
-                    &cached_path::Options::default().extract().subdir(&subdir),
-                )
-            }
-        }?;
+        )?;
 
         // Verifica se `result` é um arquivo ou diretório
         if result.is_file() {
@@ -120,7 +85,8 @@ pub async fn try_download(
             }
 
             // Converte o arquivo para UTF-8
-            convert_file_to_utf8(&result, &utf8_path)?;
+            let rows = convert_file_to_utf8(&result, &utf8_path)?;
+            catalog::set_rows(&dataset, rows);
             drop(permit); // Libera a permissão ao terminar a tarefa
             return Ok(utf8_path);
         } else if result.is_dir() {
@@ -131,6 +97,7 @@ pub async fn try_download(
                 return Ok(utf8_path);
             }
             // Processa todos os arquivos no diretório
+            let mut rows = 0;
             for entry in fs::read_dir(&result)? {
                 let entry = entry?;
                 let file_path = entry.path();
@@ -141,10 +108,11 @@ pub async fn try_download(
                             format!("{}/{}", utf8_path.display(), name.to_string_lossy());
                         let path = PathBuf::from(utf8_file_path.to_string());
                         // Converte o arquivo para UTF-8
-                        convert_file_to_utf8(&file_path, &path)?;
+                        rows += convert_file_to_utf8(&file_path, &path)?;
                     }
                 }
             }
+            catalog::set_rows(&dataset, rows);
             drop(permit); // Libera a permissão ao terminar a tarefa
             return Ok(utf8_path);
         }
@@ -156,10 +124,11 @@ pub async fn try_download(
     .unwrap()
 }
 
-// Função que converte um único arquivo para UTF-8 e trunca o arquivo original
-fn convert_file_to_utf8(file_path: &Path, utf8_file_path: &Path) -> Result<(), std::io::Error> {
+// Função que converte um único arquivo para UTF-8 e trunca o arquivo original.
+// Retorna a quantidade de linhas de dados (sem o cabeçalho).
+fn convert_file_to_utf8(file_path: &Path, utf8_file_path: &Path) -> Result<usize, std::io::Error> {
     if utf8_file_path.exists() {
-        return Ok(());
+        return Ok(0);
     }
 
     if let Some(p) = utf8_file_path.parent() {
@@ -177,10 +146,11 @@ fn convert_file_to_utf8(file_path: &Path, utf8_file_path: &Path) -> Result<(), s
     }
     let mut outfile = File::create(utf8_file_path)?;
     outfile.write_all(decoded_str.as_bytes())?;
+    let rows = decoded_str.lines().count().saturating_sub(1);
 
     remove_file(file_path)?;
     // Limpa o arquivo original (trunca seu conteúdo)
     //  File::create(file_path)?; // Trunca o arquivo original
 
-    Ok(())
+    Ok(rows)
 }
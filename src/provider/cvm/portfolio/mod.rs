@@ -1,6 +1,8 @@
-use chrono::NaiveDate;
-use glob::glob;
+use std::{collections::HashMap, path::PathBuf};
 
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::analysis::{credit::Limits, look_through::LookThrough, portfolio::Position};
 use options::Options;
 use polars::{
     datatypes::DataType,
@@ -10,101 +12,153 @@ use polars::{
         dsl::{col, concat, lit},
         frame::LazyFrame,
     },
-    prelude::{IntoLazy, StrptimeOptions, UnionArgs},
+    prelude::{IntoLazy, TakeRandom, UnionArgs},
 };
 pub mod options;
-
-use super::{align_and_convert_columns_to_string, get_all_columns, read_csv_lazy};
+pub mod store;
 
 #[derive(Clone)]
 pub struct Portfolio {
     options: Options,
 }
 
+impl Default for Portfolio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Portfolio {
     pub fn new() -> Self {
         let options = options::load().unwrap();
         Self { options }
     }
 
+    /// Limites de concentração por emissor configurados.
+    pub fn limits(&self) -> Limits {
+        Limits {
+            warning: self.options.issuer_warning,
+            limit: self.options.issuer_limit,
+        }
+    }
+
+    /// Aplicações e patrimônio líquido do fundo nos meses do período, lidos do
+    /// armazenamento local da CDA (convertido na primeira consulta de cada mês).
     pub async fn async_read_assets(
         &self,
+        cnpj: String,
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<(LazyFrame, LazyFrame), PolarsError> {
-        let result = self
+        let paths = self
             .options
             .async_path(Some(start_date), Some(end_date))
-            .await;
-        match result {
-            Ok(paths) => {
-                let mut frames = Vec::new();
-                let mut pls = Vec::new();
-                for path in paths {
-                    let pattern = format!("{}/*", path.display());
-                    for path in glob(&pattern).unwrap().filter_map(Result::ok) {
-                        let file = path.display().to_string();
-                        let res = read_csv_lazy(&file);
-                        match res {
-                            Ok(lf) => {
-                                if file.contains("PL") {
-                                    pls.push(lf)
-                                } else {
-                                    let lf = lf
-                                        .with_column(
-                                            col("DT_COMPTC")
-                                                .str()
-                                                .strptime(
-                                                    DataType::Date,
-                                                    StrptimeOptions {
-                                                        format: Some("%Y-%m-%d".into()),
-                                                        ..Default::default()
-                                                    },
-                                                )
-                                                .cast(DataType::Date)
-                                                .alias("AS_DATE"),
-                                        )
-                                        .filter(
-                                            col("AS_DATE").gt_eq(lit(start_date)).and(
-                                                col("AS_DATE")
-                                                    .cast(DataType::Date)
-                                                    .lt_eq(lit(end_date)),
-                                            ),
-                                        )
-                                        .collect()
-                                        .unwrap()
-                                        .lazy();
-                                    frames.push(lf)
-                                }
-                            }
-                            Err(err) => {
-                                log::error!("err {}", err);
-                            }
-                        }
-                    }
-                }
+            .await
+            .map_err(|err| PolarsError::NoData(err.to_string().into()))?;
 
-                let all_columns = get_all_columns(&frames);
-                let aligned_lfs: Vec<LazyFrame> = frames
-                    .into_iter()
-                    .map(|lf| align_and_convert_columns_to_string(lf, &all_columns))
-                    .collect();
-
-                let result = concat(&aligned_lfs, UnionArgs::default());
-                match result {
-                    Ok(lf) => {
-                        if pls.is_empty() {
-                            Ok((lf, LazyFrame::default()))
-                        } else {
-                            let pl = pls.first().unwrap().clone();
-                            Ok((lf, pl))
-                        }
-                    }
-                    Err(err) => Err(err),
-                }
+        let store = self.options.store_path();
+        let months = tokio::task::spawn_blocking(move || store::ingest_all(&paths, &store))
+            .await
+            .map_err(|err| PolarsError::ComputeError(err.to_string().into()))??;
+
+        let mut frames = Vec::new();
+        let mut pls = Vec::new();
+        for month in months {
+            if let Some((assets, pl)) = store::lookup(&month, &cnpj)? {
+                frames.push(assets);
+                pls.push(pl);
+            }
+        }
+        if frames.is_empty() {
+            return Err(PolarsError::NoData(
+                format!("Carteira não encontrada para {}", cnpj).into(),
+            ));
+        }
+
+        let date_filter = col("DT_COMPTC")
+            .gt_eq(lit(start_date))
+            .and(col("DT_COMPTC").lt_eq(lit(end_date)));
+        let assets = concat(&frames, UnionArgs::default())?.filter(date_filter.clone());
+        let pl = concat(&pls, UnionArgs::default())?.filter(date_filter);
+        Ok((assets, pl))
+    }
+
+    /// Aplicações do fundo em cada um dos `months` meses terminados no mês de `end_date`,
+    /// do mais antigo ao mais recente. Meses sem carteira publicada são ignorados.
+    pub async fn async_evolution(
+        &self,
+        cnpj: String,
+        end_date: NaiveDate,
+        months: u32,
+    ) -> Result<Vec<DataFrame>, PolarsError> {
+        let last = end_date.with_day(1).unwrap_or(end_date);
+        let handles: Vec<_> = (0..months)
+            .rev()
+            .filter_map(|i| last.checked_sub_months(Months::new(i)))
+            .map(|start_date| {
+                let end_date = start_date + Months::new(1) - chrono::Duration::days(1);
+                let portfolio = self.clone();
+                let cnpj = cnpj.clone();
+                tokio::spawn(async move {
+                    let (assets, _) = portfolio
+                        .async_read_assets(cnpj, start_date, end_date)
+                        .await?;
+                    assets.collect()
+                })
+            })
+            .collect();
+
+        let mut frames = Vec::new();
+        for handle in handles {
+            match handle.await {
+                Ok(Ok(df)) if df.height() > 0 => frames.push(df),
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => log::warn!("Carteira indisponível: {}", err),
+                Err(err) => log::error!("Falha ao obter carteira: {}", err),
             }
-            Err(err) => Err(PolarsError::NoData(err.to_string().into())),
         }
+        if frames.is_empty() {
+            return Err(PolarsError::NoData(
+                format!("Carteira não encontrada para {}", cnpj).into(),
+            ));
+        }
+        Ok(frames)
+    }
+
+    /// Carteira do fundo no mês com as cotas de outros fundos abertas nas carteiras
+    /// desses fundos no mesmo mês, até `look_through_depth` níveis.
+    pub async fn async_look_through(
+        &self,
+        cnpj: String,
+        year: String,
+        month: String,
+    ) -> Result<LookThrough, PolarsError> {
+        let (start_date, end_date) = self
+            .get_month_start_and_end(month, year)
+            .map_err(|err| PolarsError::ComputeError(err.into()))?;
+        let paths = self
+            .options
+            .async_path(Some(start_date), Some(end_date))
+            .await
+            .map_err(|err| PolarsError::NoData(err.to_string().into()))?;
+
+        let store = self.options.store_path();
+        let max_depth = self.options.look_through_depth;
+        tokio::task::spawn_blocking(move || {
+            let months = store::ingest_all(&paths, &store)?;
+            let mut cache: HashMap<String, Option<(Vec<Position>, f64)>> = HashMap::new();
+            let fetch = |cnpj: &str| {
+                cache
+                    .entry(cnpj.to_string())
+                    .or_insert_with(|| read_positions(&months, cnpj))
+                    .clone()
+            };
+            LookThrough::resolve(&cnpj, max_depth, fetch).ok_or_else(|| {
+                PolarsError::NoData(format!("Carteira não encontrada para {}", cnpj).into())
+            })
+        })
+        .await
+        .map_err(|err| PolarsError::ComputeError(err.to_string().into()))?
     }
 
     fn get_month_start_and_end(
@@ -122,19 +176,13 @@ impl Portfolio {
         }
 
         // Criar a data de início (primeiro dia do mês)
-        let start_date = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+        let start_date = NaiveDate::from_ymd_opt(year, month, 1).ok_or("Invalid year")?;
 
         // Calcular o último dia do mês
-        let end_date = match month {
-            12 => NaiveDate::from_ymd_opt(year + 1, 1, 1)
-                .unwrap()
-                .pred_opt()
-                .unwrap(), // Janeiro do ano seguinte
-            _ => NaiveDate::from_ymd_opt(year, month + 1, 1)
-                .unwrap()
-                .pred_opt()
-                .unwrap(), // Último dia do mês atual
-        };
+        let end_date = start_date
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .ok_or("Invalid year")?;
 
         Ok((start_date, end_date))
     }
@@ -146,35 +194,32 @@ impl Portfolio {
         month: String,
         top: bool,
     ) -> Result<(DataFrame, DataFrame, DataFrame), PolarsError> {
-        let (start_date, end_date) = self.get_month_start_and_end(month, year).unwrap();
+        let (start_date, end_date) = self
+            .get_month_start_and_end(month, year)
+            .map_err(|err| PolarsError::ComputeError(err.into()))?;
         let res = self
-            .async_read_assets(start_date.to_owned(), end_date.to_owned())
+            .async_read_assets(cnpj.clone(), start_date, end_date)
             .await;
 
         match res {
             Ok((lf, pl)) => {
                 let mut valor_pl = 0.0;
-                let pl = pl
-                    .filter(col("CNPJ_FUNDO").eq(lit(cnpj.clone())))
-                    .collect()?;
+                let pl = pl.collect()?;
 
                 if let Some(parsed_value) = pl
                     .column("VL_PATRIM_LIQ")
                     .ok()
-                    .and_then(|col| col.get(0).ok())
-                    .and_then(|val| val.get_str().map(|s| s.to_string()))
-                    .and_then(|value_str| value_str.parse::<f64>().ok())
+                    .and_then(|col| col.f64().ok())
+                    .and_then(|values| values.into_iter().next().flatten())
                 {
                     valor_pl = parsed_value;
                 }
 
                 let assets = lf
-                    .filter(col("CNPJ_FUNDO").eq(lit(cnpj.clone())))
                     .with_column(
-                        (col("VL_MERC_POS_FINAL").cast(DataType::Float64) / lit(valor_pl)
-                            * lit(100.0))
-                        .round(3)
-                        .alias("VL_PORCENTAGEM_PL"),
+                        (col("VL_MERC_POS_FINAL") / lit(valor_pl) * lit(100.0))
+                            .round(3)
+                            .alias("VL_PORCENTAGEM_PL"),
                     )
                     .collect()
                     .unwrap();
@@ -221,3 +266,20 @@ impl Portfolio {
         Ok(top_assets)
     }
 }
+
+/// Posições e patrimônio líquido do fundo no primeiro mês convertido em que aparece.
+fn read_positions(months: &[PathBuf], cnpj: &str) -> Option<(Vec<Position>, f64)> {
+    for month in months {
+        match store::lookup(month, cnpj) {
+            Ok(Some((assets, pl))) => {
+                let assets = assets.collect().ok()?;
+                let pl = pl.collect().ok()?;
+                let net_worth = pl.column("VL_PATRIM_LIQ").ok()?.f64().ok()?.get(0)?;
+                return Some((Position::from_dataframe(&assets), net_worth));
+            }
+            Ok(None) => {}
+            Err(err) => log::error!("Falha ao ler a carteira de {}: {}", cnpj, err),
+        }
+    }
+    None
+}
@@ -14,6 +14,29 @@ pub struct Options {
     pub url: String,
     pub historical_url: String,
     pub path: String,
+    /// Subdiretório do cache com as carteiras convertidas para Parquet
+    pub store: String,
+    /// Níveis de fundos investidos abertos no look-through
+    #[serde(default = "look_through_depth")]
+    pub look_through_depth: u32,
+    /// Concentração por emissor, em % do patrimônio líquido, que gera alerta
+    #[serde(default = "issuer_warning")]
+    pub issuer_warning: f64,
+    /// Concentração máxima por emissor, em % do patrimônio líquido
+    #[serde(default = "issuer_limit")]
+    pub issuer_limit: f64,
+}
+
+fn look_through_depth() -> u32 {
+    3
+}
+
+pub fn issuer_warning() -> f64 {
+    5.0
+}
+
+pub fn issuer_limit() -> f64 {
+    10.0
 }
 
 impl Options {
@@ -67,6 +90,10 @@ impl Options {
         Ok(paths)
     }
 
+    pub fn store_path(&self) -> PathBuf {
+        std::env::temp_dir().join("cache").join(&self.store)
+    }
+
     fn generate_patterns(
         &self,
         start_date: NaiveDate,
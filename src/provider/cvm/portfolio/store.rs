@@ -0,0 +1,357 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use glob::glob;
+use polars::{
+    datatypes::DataType,
+    error::PolarsError,
+    frame::DataFrame,
+    lazy::{
+        dsl::{col, concat, lit, Expr, StrptimeOptions},
+        frame::LazyFrame,
+    },
+    prelude::{
+        IntoLazy, NamedFrom, ParquetWriter, ScanArgsParquet, Series, SortOptions, TakeRandom,
+        TakeRandomUtf8, UnionArgs,
+    },
+};
+
+use crate::provider::cvm::{cnpj_key, column_or_null, read_csv_lazy};
+
+/// Arquivo com uma linha por CNPJ: partição, deslocamento e quantidade de linhas.
+const INDEX_FILE: &str = "index.parquet";
+const PL_FILE: &str = "pl.parquet";
+const ASSETS_DIR: &str = "carteira";
+/// Versão do formato gravado; meses convertidos com outra versão são convertidos de novo.
+const LAYOUT_FILE: &str = "layout";
+const LAYOUT_VERSION: &str = "2";
+
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Converte os blocos da CDA (`cda_fi_BLC_*`, `cda_fi_CONFID_*` e `cda_fi_PL_*`) de cada
+/// diretório baixado em um diretório por mês dentro de `store` e devolve esses diretórios.
+///
+/// As aplicações ficam tipadas (`VL_*`/`QT_*` como `Float64`, `DT_*` como `Date`),
+/// ordenadas por CNPJ e particionadas pelos dois primeiros dígitos do CNPJ.
+pub fn ingest_all(paths: &[PathBuf], store: &Path) -> Result<Vec<PathBuf>, PolarsError> {
+    let mut months: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for path in paths {
+        let pattern = format!("{}/*.csv", path.display());
+        for csv in glob(&pattern).unwrap().filter_map(Result::ok) {
+            if let Some(month) = month_of(&csv) {
+                months.entry(month).or_default().push(csv);
+            }
+        }
+    }
+
+    let mut dirs = Vec::new();
+    for (month, files) in months {
+        match ingest_month(&month, &files, store) {
+            Ok(dir) => dirs.push(dir),
+            Err(err) => log::error!("Falha ao converter a carteira de {}: {}", month, err),
+        }
+    }
+    Ok(dirs)
+}
+
+/// Aplicações e patrimônio líquido de um fundo em um mês já convertido, ou `None` se
+/// o fundo não entregou carteira no mês. Usa o índice para ler apenas as linhas do CNPJ
+/// na partição correspondente.
+pub fn lookup(dir: &Path, cnpj: &str) -> Result<Option<(LazyFrame, LazyFrame)>, PolarsError> {
+    let index = LazyFrame::scan_parquet(dir.join(INDEX_FILE), ScanArgsParquet::default())?
+        .filter(col("CNPJ_FUNDO").eq(lit(cnpj)))
+        .collect()?;
+    if index.height() == 0 {
+        return Ok(None);
+    }
+    let entry = (
+        index.column("PARTICAO")?.utf8()?.get(0),
+        index.column("OFFSET")?.u32()?.get(0),
+        index.column("LINHAS")?.u32()?.get(0),
+    );
+    let (Some(partition), Some(offset), Some(rows)) = entry else {
+        return Ok(None);
+    };
+
+    let assets = LazyFrame::scan_parquet(
+        dir.join(ASSETS_DIR).join(format!("{}.parquet", partition)),
+        ScanArgsParquet::default(),
+    )?
+    .slice(offset as i64, rows);
+    let pl = LazyFrame::scan_parquet(dir.join(PL_FILE), ScanArgsParquet::default())?
+        .filter(col("CNPJ_FUNDO").eq(lit(cnpj)));
+    Ok(Some((assets, pl)))
+}
+
+fn ingest_month(month: &str, files: &[PathBuf], store: &Path) -> Result<PathBuf, PolarsError> {
+    let target = store.join(month);
+    let layout = fs::read_to_string(target.join(LAYOUT_FILE)).unwrap_or_default();
+    if layout == LAYOUT_VERSION && is_fresh(&target.join(INDEX_FILE), files) {
+        return Ok(target);
+    }
+
+    let (pls, blocks): (Vec<&PathBuf>, Vec<&PathBuf>) = files
+        .iter()
+        .partition(|file| file.to_string_lossy().contains("_PL_"));
+
+    let mut frames = Vec::new();
+    for file in &blocks {
+        let lf = normalize_columns(read_csv_lazy(&file.display().to_string())?)?;
+        frames.push((block_of(file), lf));
+    }
+    let columns = ordered_columns(frames.iter().map(|(_, lf)| lf));
+    let typed = frames
+        .into_iter()
+        .map(|(block, lf)| {
+            typed_select(lf, &columns).map(|lf| lf.with_column(lit(block).alias("BLOCO")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let assets = concat(&typed, UnionArgs::default())?
+        .filter(col("CNPJ_FUNDO").is_not_null())
+        .sort("CNPJ_FUNDO", SortOptions::default())
+        .collect()?;
+
+    let mut pl = match pls.first() {
+        Some(file) => {
+            let lf = normalize_columns(read_csv_lazy(&file.display().to_string())?)?;
+            let columns = ordered_columns([&lf]);
+            typed_select(lf, &columns)?.collect()?
+        }
+        None => DataFrame::new(vec![
+            Series::new_empty("CNPJ_FUNDO", &DataType::Utf8),
+            Series::new_empty("VL_PATRIM_LIQ", &DataType::Float64),
+        ])?,
+    };
+
+    // Monta o mês em um diretório temporário e troca de uma vez ao final
+    let tmp = store.join(format!(
+        "{}.{}-{}.tmp",
+        month,
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(tmp.join(ASSETS_DIR))?;
+    write(&tmp.join(PL_FILE), &mut pl)?;
+    let mut index = write_partitions(&tmp.join(ASSETS_DIR), &assets)?;
+    write(&tmp.join(INDEX_FILE), &mut index)?;
+    fs::write(tmp.join(LAYOUT_FILE), LAYOUT_VERSION)?;
+
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+    fs::rename(&tmp, &target)?;
+
+    log::info!("Carteira convertida: {}", target.display());
+    Ok(target)
+}
+
+/// Grava uma partição por prefixo de CNPJ. Como `assets` está ordenado por CNPJ,
+/// cada partição é um intervalo contíguo de linhas.
+fn write_partitions(dir: &Path, assets: &DataFrame) -> Result<DataFrame, PolarsError> {
+    let counts = assets
+        .clone()
+        .lazy()
+        .groupby_stable([col("CNPJ_FUNDO")])
+        .agg([col("CNPJ_FUNDO").count().alias("LINHAS")])
+        .collect()?;
+
+    let cnpjs = counts.column("CNPJ_FUNDO")?.utf8()?;
+    let rows = counts.column("LINHAS")?.u32()?;
+    let mut partitions: Vec<String> = Vec::with_capacity(counts.height());
+    let mut offsets: Vec<u32> = Vec::with_capacity(counts.height());
+
+    let mut start = 0usize;
+    let mut current: Option<(String, usize)> = None;
+    for (cnpj, len) in cnpjs.into_iter().zip(rows.into_iter()) {
+        let partition = partition_of(cnpj.unwrap_or_default());
+        let len = len.unwrap_or(0) as usize;
+        match &current {
+            Some((name, first)) if *name == partition => {
+                offsets.push((start - first) as u32);
+            }
+            _ => {
+                if let Some((name, first)) = current.take() {
+                    write_partition(dir, &name, assets, first, start - first)?;
+                }
+                current = Some((partition.clone(), start));
+                offsets.push(0);
+            }
+        }
+        partitions.push(partition);
+        start += len;
+    }
+    if let Some((name, first)) = current {
+        write_partition(dir, &name, assets, first, start - first)?;
+    }
+
+    let mut index = counts;
+    index.with_column(Series::new("PARTICAO", partitions))?;
+    index.with_column(Series::new("OFFSET", offsets))?;
+    Ok(index)
+}
+
+fn write_partition(
+    dir: &Path,
+    name: &str,
+    assets: &DataFrame,
+    offset: usize,
+    len: usize,
+) -> Result<(), PolarsError> {
+    let mut df = assets.slice(offset as i64, len);
+    write(&dir.join(format!("{}.parquet", name)), &mut df)
+}
+
+fn write(path: &Path, df: &mut DataFrame) -> Result<(), PolarsError> {
+    ParquetWriter::new(File::create(path)?)
+        .with_statistics(true)
+        .finish(df)?;
+    Ok(())
+}
+
+fn is_fresh(index: &Path, sources: &[PathBuf]) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let Some(index) = modified(index) else {
+        return false;
+    };
+    sources
+        .iter()
+        .all(|source| modified(source).map_or(false, |source| index >= source))
+}
+
+/// `cda_fi_BLC_1_202401.csv` → `202401`.
+fn month_of(csv: &Path) -> Option<String> {
+    let stem = csv.file_stem()?.to_string_lossy();
+    let month = stem.rsplit('_').next()?;
+    (month.len() == 6 && month.chars().all(|c| c.is_ascii_digit())).then(|| month.to_string())
+}
+
+/// `cda_fi_BLC_1_202401.csv` → `BLC_1`; `cda_fi_CONFID_202401.csv` → `CONFID`.
+fn block_of(csv: &Path) -> String {
+    let stem = csv
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let parts: Vec<&str> = stem.split('_').collect();
+    match parts.as_slice() {
+        [_, _, rest @ .., _] => rest.join("_"),
+        _ => stem,
+    }
+}
+
+/// Partição pelos dois primeiros dígitos do CNPJ.
+fn partition_of(cnpj: &str) -> String {
+    let digits: String = cnpj.chars().filter(char::is_ascii_digit).take(2).collect();
+    format!("{:0>2}", digits)
+}
+
+/// Colunas dos fundos investidos renomeadas nos arquivos posteriores à Resolução CVM 175.
+const RENAMED_COLUMNS: [(&str, &str); 2] = [
+    ("CNPJ_FUNDO_CLASSE_COTA", "CNPJ_FUNDO_COTA"),
+    ("NM_FUNDO_CLASSE_SUBCLASSE_COTA", "NM_FUNDO_COTA"),
+];
+
+/// Identifica as linhas por `CNPJ_FUNDO` (ver `cnpj_key`) e traz as colunas dos fundos
+/// investidos com os nomes anteriores à Resolução CVM 175.
+fn normalize_columns(lf: LazyFrame) -> Result<LazyFrame, PolarsError> {
+    let schema = lf.schema()?;
+    let mut lf = lf.with_column(cnpj_key(&schema).alias("CNPJ_FUNDO"));
+    if schema.get("CNPJ_FUNDO_CLASSE").is_some() {
+        lf = lf.drop_columns(["CNPJ_FUNDO_CLASSE"]);
+    }
+    let (old, new): (Vec<&str>, Vec<&str>) = RENAMED_COLUMNS
+        .into_iter()
+        .filter(|(old, new)| schema.get(old).is_some() && schema.get(new).is_none())
+        .unzip();
+    Ok(lf.rename(old, new))
+}
+
+/// União das colunas dos blocos, na ordem em que aparecem.
+fn ordered_columns<'a>(frames: impl IntoIterator<Item = &'a LazyFrame>) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for lf in frames {
+        if let Ok(schema) = lf.schema() {
+            for name in schema.iter_names() {
+                if !columns.iter().any(|c| c == name.as_str()) {
+                    columns.push(name.to_string());
+                }
+            }
+        }
+    }
+    columns
+}
+
+fn typed_select(lf: LazyFrame, columns: &[String]) -> Result<LazyFrame, PolarsError> {
+    let schema = lf.schema()?;
+    let exprs: Vec<Expr> = columns
+        .iter()
+        .map(|name| {
+            let data_type = column_type(name);
+            if schema.get(name).is_none() {
+                return column_or_null(&schema, name, data_type);
+            }
+            match data_type {
+                DataType::Date => col(name).str().strptime(
+                    DataType::Date,
+                    StrptimeOptions {
+                        format: Some("%Y-%m-%d".into()),
+                        strict: false,
+                        ..Default::default()
+                    },
+                ),
+                data_type => col(name).cast(data_type),
+            }
+        })
+        .collect();
+    Ok(lf.select(&exprs))
+}
+
+fn column_type(name: &str) -> DataType {
+    if name.starts_with("VL_") || name.starts_with("QT_") {
+        DataType::Float64
+    } else if name.starts_with("DT_") {
+        DataType::Date
+    } else {
+        DataType::Utf8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn post_175_columns_get_the_previous_names() {
+        let lf = DataFrame::new(vec![
+            Series::new("CNPJ_FUNDO_CLASSE", ["11.111.111/0001-11"]),
+            Series::new("CNPJ_FUNDO_CLASSE_COTA", ["22.222.222/0001-22"]),
+            Series::new("NM_FUNDO_CLASSE_SUBCLASSE_COTA", ["FUNDO INVESTIDO"]),
+        ])
+        .unwrap()
+        .lazy();
+
+        let df = normalize_columns(lf).unwrap().collect().unwrap();
+        let mut names = df.get_column_names();
+        names.sort();
+        assert_eq!(names, ["CNPJ_FUNDO", "CNPJ_FUNDO_COTA", "NM_FUNDO_COTA"]);
+        let value = |name: &str| df.column(name).unwrap().utf8().unwrap().get(0);
+        assert_eq!(value("CNPJ_FUNDO"), Some("11.111.111/0001-11"));
+        assert_eq!(value("CNPJ_FUNDO_COTA"), Some("22.222.222/0001-22"));
+    }
+
+    #[test]
+    fn previous_columns_are_kept() {
+        let lf = DataFrame::new(vec![
+            Series::new("CNPJ_FUNDO", ["11.111.111/0001-11"]),
+            Series::new("CNPJ_FUNDO_COTA", ["22.222.222/0001-22"]),
+        ])
+        .unwrap()
+        .lazy();
+
+        let df = normalize_columns(lf).unwrap().collect().unwrap();
+        assert_eq!(df.get_column_names(), ["CNPJ_FUNDO", "CNPJ_FUNDO_COTA"]);
+    }
+}
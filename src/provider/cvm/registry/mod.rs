@@ -0,0 +1,371 @@
+pub mod options;
+
+use chrono::NaiveDate;
+use options::{load, Options};
+use polars::{
+    frame::DataFrame,
+    lazy::dsl::{col, concat, lit, Expr},
+    prelude::{DataType, JoinArgs, JoinType, TakeRandom, UnionArgs, UniqueKeepStrategy, NULL},
+};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::{
+    fund::{Fund, Participant, Situation},
+    read_csv_lazy,
+};
+
+pub const FUNDS_FILE: &str = "registro_fundo.csv";
+pub const CLASSES_FILE: &str = "registro_classe.csv";
+const SUBCLASSES_FILE: &str = "registro_subclasse.csv";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error getting async path: {0}")]
+    CachedPathError(#[from] cached_path::Error),
+
+    #[error("Error loading CSV: {0}")]
+    PolarsError(#[from] polars::prelude::PolarsError),
+}
+
+/// Subclasse de cotas de uma classe (Resolução CVM 175).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Subclass {
+    pub id: String,
+    pub name: String,
+    pub cvm_code: Option<String>,
+    pub situation: Option<Situation>,
+    pub exclusive: Option<bool>,
+    pub target_audience: Option<String>,
+}
+
+/// Classe de cotas de um fundo, com CNPJ próprio.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FundClass {
+    pub id: String,
+    pub cnpj: Option<String>,
+    pub name: String,
+    /// Tipo da classe (`Tipo_Classe`), como "Classes de Cotas de Fundos FIF".
+    pub kind: Option<String>,
+    pub situation: Option<Situation>,
+    /// Classificação CVM da classe ("Renda Fixa", "Ações", ...).
+    pub classification: Option<String>,
+    pub anbima_class: Option<String>,
+    pub net_worth: Option<f64>,
+    pub subclasses: Vec<Subclass>,
+}
+
+/// Fundo do registro de fundos, classes e subclasses da CVM.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RegisteredFund {
+    pub id: String,
+    pub cnpj: String,
+    pub name: String,
+    pub fund_type: Option<String>,
+    pub situation: Option<Situation>,
+    pub situation_start_date: Option<NaiveDate>,
+    pub constitution_date: Option<NaiveDate>,
+    /// Data de adaptação à Resolução CVM 175.
+    pub adaptation_date: Option<NaiveDate>,
+    pub admin: Option<Participant>,
+    pub gestor: Option<Participant>,
+    pub classes: Vec<FundClass>,
+    /// Linha do fundo no registro, exibida nos detalhes.
+    pub details: DataFrame,
+}
+
+impl RegisteredFund {
+    /// Classe cujo CNPJ é o informado, quando o CNPJ não é o do próprio fundo.
+    pub fn class_by_cnpj(&self, cnpj: &str) -> Option<&FundClass> {
+        self.classes
+            .iter()
+            .find(|class| class.cnpj.as_deref() == Some(cnpj))
+    }
+
+    /// Fundo no formato do cadastro legado, para fundos que só existem no registro novo.
+    /// Quando `cnpj` é o de uma das classes, o fundo é a própria classe, com o CNPJ
+    /// buscado, e herda tipo, datas, administrador e gestor do fundo.
+    pub fn to_fund(&self, cnpj: &str) -> Fund {
+        let fund = Fund {
+            cnpj: self.cnpj.clone(),
+            denomination: self.name.clone(),
+            fund_type: self.fund_type.as_deref().and_then(|t| t.parse().ok()),
+            situation: self.situation.clone(),
+            situation_start_date: self.situation_start_date,
+            constitution_date: self.constitution_date,
+            admin: self.admin.clone(),
+            gestor: self.gestor.clone(),
+            ..Default::default()
+        };
+        match self.class_by_cnpj(cnpj) {
+            Some(class) => Fund {
+                cnpj: cnpj.to_string(),
+                denomination: class.name.clone(),
+                situation: class.situation.clone(),
+                class: class.classification.as_deref().and_then(|c| c.parse().ok()),
+                anbima_class: class.anbima_class.as_deref().and_then(|c| c.parse().ok()),
+                net_worth: class.net_worth,
+                ..fund
+            },
+            None => fund,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Registry {
+    options: Options,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let options = load().unwrap();
+        Self { options }
+    }
+
+    /// Diretório com os CSVs do registro, baixado se necessário.
+    pub async fn async_dir(&self) -> Result<PathBuf, Error> {
+        Ok(self.options.async_path().await?)
+    }
+
+    /// Fundos e classes do registro no layout do cadastro legado (`CNPJ_FUNDO`,
+    /// `DENOM_SOCIAL`, `TP_FUNDO`, `SIT`, ...), para entrarem no índice de busca. As
+    /// classes aparecem com o próprio CNPJ e herdam tipo, administrador e gestor do fundo.
+    pub fn register_rows(dir: &Path) -> Result<DataFrame, Error> {
+        let file = |name: &str| dir.join(name).display().to_string();
+        let funds = read_csv_lazy(&file(FUNDS_FILE))?;
+        let classes = read_csv_lazy(&file(CLASSES_FILE))?;
+
+        let fund_rows = funds.clone().select([
+            col("CNPJ_Fundo").alias("CNPJ_FUNDO"),
+            col("Denominacao_Social").alias("DENOM_SOCIAL"),
+            col("Tipo_Fundo").alias("TP_FUNDO"),
+            col("Situacao").alias("SIT"),
+            col("Data_Constituicao").alias("DT_CONST"),
+            lit(NULL).cast(DataType::Utf8).alias("CLASSE"),
+            lit(NULL).cast(DataType::Utf8).alias("CLASSE_ANBIMA"),
+            col("Administrador").alias("ADMIN"),
+            col("CNPJ_Administrador").alias("CNPJ_ADMIN"),
+            col("Gestor").alias("GESTOR"),
+            col("CPF_CNPJ_Gestor").alias("CPF_CNPJ_GESTOR"),
+        ]);
+        let class_rows = classes
+            .join(
+                funds.select([
+                    col("ID_Registro_Fundo"),
+                    col("Tipo_Fundo"),
+                    col("Data_Constituicao"),
+                    col("Administrador"),
+                    col("CNPJ_Administrador"),
+                    col("Gestor"),
+                    col("CPF_CNPJ_Gestor"),
+                ]),
+                [col("ID_Registro_Fundo")],
+                [col("ID_Registro_Fundo")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .select([
+                col("CNPJ_Classe").alias("CNPJ_FUNDO"),
+                col("Denominacao_Social").alias("DENOM_SOCIAL"),
+                col("Tipo_Fundo").alias("TP_FUNDO"),
+                col("Situacao").alias("SIT"),
+                col("Data_Constituicao").alias("DT_CONST"),
+                col("Classificacao").alias("CLASSE"),
+                col("Classificacao_Anbima").alias("CLASSE_ANBIMA"),
+                col("Administrador").alias("ADMIN"),
+                col("CNPJ_Administrador").alias("CNPJ_ADMIN"),
+                col("Gestor").alias("GESTOR"),
+                col("CPF_CNPJ_Gestor").alias("CPF_CNPJ_GESTOR"),
+            ]);
+
+        Ok(concat([fund_rows, class_rows], UnionArgs::default())?
+            .filter(col("CNPJ_FUNDO").is_not_null())
+            .unique_stable(
+                Some(vec!["CNPJ_FUNDO".to_string()]),
+                UniqueKeepStrategy::First,
+            )
+            .collect()?)
+    }
+
+    /// Fundo com suas classes e subclasses. O CNPJ pode ser o do fundo ou o de uma das
+    /// classes, que nos fundos adaptados costuma ser o CNPJ do cadastro legado.
+    pub async fn async_find_by_cnpj(&self, cnpj: String) -> Result<Option<RegisteredFund>, Error> {
+        let dir = self.options.async_path().await?;
+        let file = |name: &str| dir.join(name).display().to_string();
+        let funds = read_csv_lazy(&file(FUNDS_FILE))?;
+        let classes = read_csv_lazy(&file(CLASSES_FILE))?;
+
+        let mut fund = funds
+            .clone()
+            .filter(col("CNPJ_Fundo").eq(lit(cnpj.clone())))
+            .collect()?;
+        if fund.height() == 0 {
+            let class = classes
+                .clone()
+                .filter(col("CNPJ_Classe").eq(lit(cnpj)))
+                .collect()?;
+            let Some(id) = text(&class, "ID_Registro_Fundo", 0) else {
+                return Ok(None);
+            };
+            fund = funds
+                .filter(col("ID_Registro_Fundo").eq(lit(id)))
+                .collect()?;
+        }
+        let Some(id) = text(&fund, "ID_Registro_Fundo", 0) else {
+            return Ok(None);
+        };
+
+        let classes_df = classes
+            .filter(col("ID_Registro_Fundo").eq(lit(id.clone())))
+            .collect()?;
+        let mut classes: Vec<FundClass> = (0..classes_df.height())
+            .filter_map(|row| {
+                let text = |name: &str| text(&classes_df, name, row);
+                Some(FundClass {
+                    id: text("ID_Registro_Classe")?,
+                    cnpj: text("CNPJ_Classe"),
+                    name: text("Denominacao_Social").unwrap_or_default(),
+                    kind: text("Tipo_Classe"),
+                    situation: text("Situacao").and_then(|s| s.parse().ok()),
+                    classification: text("Classificacao"),
+                    anbima_class: text("Classificacao_Anbima"),
+                    net_worth: text("Patrimonio_Liquido").and_then(|v| parse_number(&v)),
+                    subclasses: Vec::new(),
+                })
+            })
+            .collect();
+
+        // Subclasses de todas as classes do fundo numa única leitura
+        let by_class: Option<Expr> = classes
+            .iter()
+            .map(|class| col("ID_Registro_Classe").eq(lit(class.id.clone())))
+            .reduce(|acc, expr| acc.or(expr));
+        if let Some(by_class) = by_class {
+            let subclasses = read_csv_lazy(&file(SUBCLASSES_FILE))?
+                .filter(by_class)
+                .collect()?;
+            for row in 0..subclasses.height() {
+                let text = |name: &str| text(&subclasses, name, row);
+                let (Some(class_id), Some(id)) = (text("ID_Registro_Classe"), text("ID_Subclasse"))
+                else {
+                    continue;
+                };
+                if let Some(class) = classes.iter_mut().find(|c| c.id == class_id) {
+                    class.subclasses.push(Subclass {
+                        id,
+                        name: text("Denominacao_Social").unwrap_or_default(),
+                        cvm_code: text("Codigo_CVM"),
+                        situation: text("Situacao").and_then(|s| s.parse().ok()),
+                        exclusive: text("Exclusivo").map(|v| v.eq_ignore_ascii_case("S")),
+                        target_audience: text("Publico_Alvo"),
+                    });
+                }
+            }
+        }
+
+        let text = |name: &str| text(&fund, name, 0);
+        let date = |name: &str| text(name).and_then(|v| parse_date(&v));
+        let participant = |name: &str, document: &str| {
+            text(name).map(|name| Participant {
+                name,
+                document: text(document),
+            })
+        };
+        Ok(Some(RegisteredFund {
+            cnpj: text("CNPJ_Fundo").unwrap_or_default(),
+            name: text("Denominacao_Social").unwrap_or_default(),
+            fund_type: text("Tipo_Fundo"),
+            situation: text("Situacao").and_then(|s| s.parse().ok()),
+            situation_start_date: date("Data_Inicio_Situacao"),
+            constitution_date: date("Data_Constituicao"),
+            adaptation_date: date("Data_Adaptacao_RCVM175"),
+            admin: participant("Administrador", "CNPJ_Administrador"),
+            gestor: participant("Gestor", "CPF_CNPJ_Gestor"),
+            classes,
+            details: fund.clone(),
+            id,
+        }))
+    }
+}
+
+fn text(df: &DataFrame, name: &str, row: usize) -> Option<String> {
+    let values = df.column(name).ok()?.utf8().ok()?;
+    let value = values.get(row)?.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.replace(',', ".").parse::<f64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::cvm::fund::Class;
+
+    fn registered() -> RegisteredFund {
+        RegisteredFund {
+            id: "1".to_string(),
+            cnpj: "11.111.111/0001-11".to_string(),
+            name: "FUNDO MASTER".to_string(),
+            fund_type: Some("FIF".to_string()),
+            situation: Some(Situation::Normal),
+            admin: Some(Participant {
+                name: "ADMINISTRADORA".to_string(),
+                document: Some("33.333.333/0001-33".to_string()),
+            }),
+            gestor: Some(Participant {
+                name: "GESTORA".to_string(),
+                document: None,
+            }),
+            classes: vec![FundClass {
+                id: "10".to_string(),
+                cnpj: Some("22.222.222/0001-22".to_string()),
+                name: "CLASSE RENDA FIXA".to_string(),
+                situation: Some(Situation::Liquidation),
+                classification: Some("Renda Fixa".to_string()),
+                net_worth: Some(1_000.0),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn class_cnpj_opens_the_class_with_parent_participants() {
+        let fund = registered().to_fund("22.222.222/0001-22");
+        assert_eq!(fund.cnpj, "22.222.222/0001-22");
+        assert_eq!(fund.denomination, "CLASSE RENDA FIXA");
+        assert_eq!(fund.situation, Some(Situation::Liquidation));
+        assert_eq!(fund.class, Some(Class::RendaFixa));
+        assert_eq!(fund.net_worth, Some(1_000.0));
+        assert_eq!(
+            fund.admin.map(|a| a.name),
+            Some("ADMINISTRADORA".to_string())
+        );
+        assert_eq!(fund.gestor.map(|g| g.name), Some("GESTORA".to_string()));
+    }
+
+    #[test]
+    fn fund_cnpj_opens_the_fund() {
+        let fund = registered().to_fund("11.111.111/0001-11");
+        assert_eq!(fund.cnpj, "11.111.111/0001-11");
+        assert_eq!(fund.denomination, "FUNDO MASTER");
+        assert_eq!(fund.situation, Some(Situation::Normal));
+        assert_eq!(fund.class, None);
+    }
+}
@@ -0,0 +1,27 @@
+use std::{path::PathBuf, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use crate::{config::get, provider::cvm::try_download};
+
+const ROOT: &str = "cvm.fundo.registro";
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Options {
+    pub description: String,
+    pub url: String,
+    pub path: String,
+}
+
+impl Options {
+    /// Baixa e extrai o zip do registro; retorna o diretório com os CSVs em UTF-8.
+    pub async fn async_path(&self) -> Result<PathBuf, cached_path::Error> {
+        let semaphore = Arc::new(Semaphore::new(1));
+        try_download(self.url.clone(), self.path.clone(), semaphore).await
+    }
+}
+
+pub fn load() -> Result<Options, config::ConfigError> {
+    get::<Options>(ROOT)
+}
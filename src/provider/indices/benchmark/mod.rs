@@ -0,0 +1,187 @@
+use std::{future::Future, pin::Pin};
+
+use chrono::NaiveDate;
+use polars::{
+    error::PolarsError,
+    frame::DataFrame,
+    lazy::dsl::{col, lit},
+    prelude::{IntoLazy, SortOptions},
+};
+use thiserror::Error;
+
+use super::{ibovespa, quotes, sgs};
+
+pub mod options;
+
+pub use options::{Benchmark, Source};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Erro ao obter as cotações: {0}")]
+    Quotes(#[from] quotes::Error),
+
+    #[error("Erro ao obter a série do SGS: {0}")]
+    Sgs(#[from] sgs::Error),
+
+    #[error("Série do SGS sem código configurado: {0}")]
+    MissingCode(String),
+
+    #[error("Índice sem ticker configurado: {0}")]
+    MissingTicker(String),
+
+    #[error("Erro ao processar a série: {0}")]
+    Polars(#[from] PolarsError),
+}
+
+pub type SeriesFuture = Pin<Box<dyn Future<Output = Result<DataFrame, Error>> + Send>>;
+
+/// Índice de referência para comparar com a rentabilidade de um fundo.
+pub trait BenchmarkProvider: Send + Sync {
+    /// Nome exibido na legenda do gráfico.
+    fn name(&self) -> &str;
+
+    /// Série do índice no período, com ao menos `as_date` (`Date`) e `retorno`
+    /// (taxa diária em decimal), ordenada por data.
+    fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> SeriesFuture;
+
+    /// Retorno acumulado em % (`as_date`, `value`) a partir da série de `fetch`.
+    fn cumulative(&self, series: &DataFrame) -> Result<DataFrame, PolarsError> {
+        let value = ((col("retorno") + lit(1.0)).cumprod(false) - lit(1.0)) * lit(100.0);
+        series
+            .clone()
+            .lazy()
+            .select([col("as_date"), value.alias("value")])
+            .sort("as_date", SortOptions::default())
+            .collect()
+    }
+}
+
+/// Série de um índice de referência já carregada para um período.
+#[derive(Debug, Clone)]
+pub struct BenchmarkSeries {
+    pub benchmark: Benchmark,
+    /// Série devolvida por `BenchmarkProvider::fetch`.
+    pub series: DataFrame,
+    /// Retorno acumulado devolvido por `BenchmarkProvider::cumulative`.
+    pub cumulative: DataFrame,
+}
+
+pub struct Ibovespa {
+    name: String,
+}
+
+impl BenchmarkProvider for Ibovespa {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> SeriesFuture {
+        Box::pin(async move {
+            let df = ibovespa::async_dataframe(start_date, end_date).await?;
+            Ok(df
+                .lazy()
+                .select([
+                    col("AS_DATE").alias("as_date"),
+                    (col("rentabilidade") / lit(100.0)).alias("retorno"),
+                ])
+                .collect()?)
+        })
+    }
+}
+
+pub struct Sgs {
+    name: String,
+    code: Option<u32>,
+    periodicity: sgs::Periodicity,
+    spread: Option<f64>,
+}
+
+impl BenchmarkProvider for Sgs {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> SeriesFuture {
+        let (name, code, periodicity, spread) =
+            (self.name.clone(), self.code, self.periodicity, self.spread);
+        Box::pin(async move {
+            let code = code.ok_or(Error::MissingCode(name))?;
+            let df = sgs::async_dataframe(code, periodicity, start_date, end_date).await?;
+            Ok(sgs::daily_rates(
+                &df,
+                periodicity,
+                spread,
+                start_date,
+                end_date,
+            )?)
+        })
+    }
+}
+
+pub struct Ticker {
+    name: String,
+    ticker: Option<String>,
+    fx: Option<String>,
+}
+
+impl BenchmarkProvider for Ticker {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> SeriesFuture {
+        let (name, ticker, fx) = (self.name.clone(), self.ticker.clone(), self.fx.clone());
+        Box::pin(async move {
+            let ticker = ticker.ok_or(Error::MissingTicker(name))?;
+            let mut prices = quotes::async_history(&ticker, start_date, end_date).await?;
+            if let Some(fx) = fx {
+                // Alguns dias antes, para ter câmbio já no primeiro pregão do período
+                let fx_start = start_date - chrono::Duration::days(7);
+                let rates = quotes::async_history(&fx, fx_start, end_date).await?;
+                prices = quotes::convert(&prices, &rates)?;
+            }
+            Ok(quotes::daily_returns(&prices)?)
+        })
+    }
+}
+
+/// Implementação correspondente à fonte configurada.
+pub fn provider(benchmark: &Benchmark) -> Box<dyn BenchmarkProvider> {
+    let name = benchmark.name.clone();
+    match benchmark.source {
+        Source::Cdi => Box::new(Sgs {
+            name,
+            code: Some(sgs::CDI),
+            periodicity: sgs::Periodicity::Daily,
+            spread: benchmark.spread,
+        }),
+        Source::Ibovespa => Box::new(Ibovespa { name }),
+        Source::Sgs => Box::new(Sgs {
+            name,
+            code: benchmark.code,
+            periodicity: benchmark.periodicity,
+            spread: benchmark.spread,
+        }),
+        Source::Ticker => Box::new(Ticker {
+            name,
+            ticker: benchmark.ticker.clone(),
+            fx: benchmark.fx.clone(),
+        }),
+    }
+}
+
+/// Busca a série de um índice e calcula o retorno acumulado.
+pub async fn async_series(
+    benchmark: Benchmark,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<BenchmarkSeries, Error> {
+    let provider = provider(&benchmark);
+    let series = provider.fetch(start_date, end_date).await?;
+    let cumulative = provider.cumulative(&series)?;
+    Ok(BenchmarkSeries {
+        benchmark,
+        series,
+        cumulative,
+    })
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{config::get, provider::indices::sgs::Periodicity};
+
+const ROOT: &str = "indices.benchmarks";
+
+/// Fonte dos dados de um índice de referência.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// Atalho para a série 12 do SGS (CDI), diária.
+    Cdi,
+    Ibovespa,
+    /// Qualquer série do SGS do Banco Central, pelo código (`code`).
+    Sgs,
+    /// Qualquer ticker do Yahoo Finanças (`ticker`), opcionalmente convertido (`fx`).
+    Ticker,
+}
+
+/// Um índice de referência exibido junto da rentabilidade do fundo.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Benchmark {
+    /// Nome exibido na legenda do gráfico
+    pub name: String,
+    pub source: Source,
+    /// Cor da linha, em hexadecimal (ex.: "#ff0000")
+    pub color: String,
+    /// Código da série no SGS (ex.: 11 SELIC, 433 IPCA, 189 IGP-M, 195 poupança)
+    #[serde(default)]
+    pub code: Option<u32>,
+    #[serde(default)]
+    pub periodicity: Periodicity,
+    /// Taxa somada à série, em % ao ano (ex.: 6.0 para "IPCA + 6%")
+    #[serde(default)]
+    pub spread: Option<f64>,
+    /// Ticker no Yahoo Finanças (ex.: "^GSPC", "USDBRL=X", "IVVB11.SA")
+    #[serde(default)]
+    pub ticker: Option<String>,
+    /// Ticker do câmbio usado para converter os preços (ex.: "USDBRL=X" para ver
+    /// um índice em dólar em reais)
+    #[serde(default)]
+    pub fx: Option<String>,
+    /// Exibido por padrão ao abrir o gráfico
+    #[serde(default = "visible")]
+    pub visible: bool,
+}
+
+fn visible() -> bool {
+    true
+}
+
+pub fn load() -> Result<Vec<Benchmark>, config::ConfigError> {
+    get::<Vec<Benchmark>>(ROOT)
+}
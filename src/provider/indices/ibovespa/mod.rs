@@ -3,51 +3,22 @@ pub mod options;
 
 use options::load;
 use polars::{
-    error::PolarsError,
     frame::DataFrame,
-    io::SerReader,
-    lazy::dsl::{col, lit, StrptimeOptions},
-    prelude::{DataType, IntoLazy, JsonReader, NamedFrom, SortOptions, TakeRandom},
+    prelude::{NamedFrom, TakeRandom},
     series::Series,
 };
-use std::fs::File;
 
-use serde::{Deserialize, Serialize};
+use super::quotes;
 
 pub async fn async_dataframe(
     start_date: NaiveDate,
     end_date: NaiveDate,
-) -> Result<DataFrame, PolarsError> {
-    let opts = load().unwrap();
-
-    let path = opts.async_path(start_date, end_date).await.unwrap();
-
-    let mut file = File::open(path)?;
-    let df = JsonReader::new(&mut file).finish()?;
-    // Adicionar coluna de datas formatadas e filtrar por data
-    let mut df = df
-        .lazy()
-        .with_column(col("adjclose").cast(DataType::Float64).alias("adjclose"))
-        .with_column(
-            col("date")
-                .str()
-                .strptime(
-                    DataType::Date,
-                    StrptimeOptions {
-                        format: Some("%d/%m/%Y".into()),
-                        ..Default::default()
-                    },
-                )
-                .cast(DataType::Date)
-                .alias("AS_DATE"),
-        )
-        .filter(
-            col("AS_DATE")
-                .gt_eq(lit(start_date))
-                .and(col("AS_DATE").cast(DataType::Date).lt_eq(lit(end_date))),
-        )
-        .sort("AS_DATE", SortOptions::default())
-        .collect()?;
+) -> Result<DataFrame, quotes::Error> {
+    let opts = load()?;
+    let mut df = quotes::async_history(&opts.ticker, start_date, end_date).await?;
+    if df.height() == 0 {
+        return Err(quotes::Error::NoData(opts.ticker));
+    }
 
     // Adicionando coluna de rentabilidade percentual
     let close_series = df.column("adjclose")?.f64()?;
@@ -75,15 +46,3 @@ pub async fn async_dataframe(
     df.with_column(acumulada_series)?;
     Ok(df)
 }
-
-#[derive(Debug, Clone, PartialEq, PartialOrd, Deserialize, Serialize)]
-pub struct Ibov {
-    pub date: String,
-    pub timestamp: u64,
-    pub open: f64,
-    pub high: f64,
-    pub low: f64,
-    pub volume: u64,
-    pub close: f64,
-    pub adjclose: f64,
-}
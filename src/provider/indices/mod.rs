@@ -1,2 +1,4 @@
-pub mod cdi;
+pub mod benchmark;
 pub mod ibovespa;
+pub mod quotes;
+pub mod sgs;
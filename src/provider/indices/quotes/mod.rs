@@ -0,0 +1,467 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Duration, NaiveDate};
+use once_cell::sync::Lazy;
+use polars::{
+    datatypes::DataType,
+    error::PolarsError,
+    frame::DataFrame,
+    lazy::{
+        dsl::{col, concat, lit},
+        frame::LazyFrame,
+    },
+    prelude::{
+        IntoLazy, NamedFrom, ParquetWriter, ScanArgsParquet, Series, SortOptions, UnionArgs,
+        UniqueKeepStrategy,
+    },
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use yahoo_finance_api::{time::OffsetDateTime, Quote, YahooConnector, YahooError};
+
+use crate::catalog;
+
+pub mod options;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error fetching quotes: {0}")]
+    Yahoo(#[from] YahooError),
+
+    #[error("Error reading quotes: {0}")]
+    Polars(#[from] PolarsError),
+
+    #[error("Error loading quotes file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Error loading config: {0}")]
+    Config(#[from] config::ConfigError),
+
+    #[error("Sem cotações em cache para {0}")]
+    NoData(String),
+}
+
+/// Um ticker por vez: requisições concorrentes do mesmo ticker esperam a anterior
+/// terminar de gravar, e então só buscam o que ainda faltar.
+static LOCKS: Lazy<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Intervalo de datas já consultado na fonte, gravado ao lado da série
+/// (dias sem pregão não têm cotação, então a série sozinha não basta).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Coverage {
+    from: String,
+    to: String,
+}
+
+/// Cotações diárias de um ticker no período (`AS_DATE`, `open`, `high`, `low`, `close`,
+/// `adjclose`, `volume`), ordenadas por data.
+///
+/// A série de cada ticker fica em `<store>/<ticker>.parquet`; somente os trechos do
+/// período ainda não consultados são buscados no Yahoo Finanças, e o restante vem do
+/// disco. No modo offline, ou se a fonte falhar, usa apenas o que já está salvo.
+pub async fn async_history(
+    ticker: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<DataFrame, Error> {
+    let options = options::load()?;
+    let dir = options.store_path();
+    let file = dir.join(format!("{}.parquet", file_stem(ticker)));
+    let dataset = format!("{}/{}", options.store, ticker);
+    let url = format!("yahoo://{}", ticker);
+
+    let lock = LOCKS
+        .lock()
+        .unwrap()
+        .entry(ticker.to_string())
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+
+    let mut online_err = None;
+    if !catalog::is_offline() {
+        let mut coverage = read_coverage(&file);
+        // O dia de hoje nunca conta como consultado: o fechamento ainda pode mudar
+        let today = chrono::offset::Local::now().date_naive();
+        let end_date = end_date.min(today);
+        let mut fetched = false;
+        for (from, to) in missing_ranges(coverage, start_date, end_date) {
+            match fetch(ticker, from, to).await {
+                Ok(quotes) => {
+                    let path = file.clone();
+                    tokio::task::spawn_blocking(move || merge(&path, &quotes))
+                        .await
+                        .unwrap()?;
+                    coverage = Some(match coverage {
+                        Some((covered_from, covered_to)) => {
+                            (covered_from.min(from), covered_to.max(to))
+                        }
+                        None => (from, to),
+                    });
+                    let (covered_from, covered_to) = coverage.unwrap();
+                    let covered_to = covered_to.min(today - Duration::days(1));
+                    if covered_to >= covered_from {
+                        write_coverage(&file, covered_from, covered_to)?;
+                    }
+                    fetched = true;
+                }
+                Err(err) => {
+                    catalog::record_failure(&dataset, &url, &err);
+                    online_err = Some(err);
+                    break;
+                }
+            }
+        }
+        if fetched {
+            catalog::record_download(&dataset, &url, rows(&file));
+        }
+    }
+
+    if !file.exists() {
+        return Err(match online_err {
+            Some(err) => err.into(),
+            None => Error::NoData(ticker.to_string()),
+        });
+    }
+
+    let df = LazyFrame::scan_parquet(&file, ScanArgsParquet::default())?
+        .filter(
+            col("AS_DATE")
+                .gt_eq(lit(start_date))
+                .and(col("AS_DATE").lt_eq(lit(end_date))),
+        )
+        .sort("AS_DATE", SortOptions::default())
+        .collect()?;
+    Ok(df)
+}
+
+/// Trechos do período fora do intervalo já consultado.
+fn missing_ranges(
+    coverage: Option<(NaiveDate, NaiveDate)>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Vec<(NaiveDate, NaiveDate)> {
+    if start_date > end_date {
+        return Vec::new();
+    }
+    let Some((from, to)) = coverage else {
+        return vec![(start_date, end_date)];
+    };
+    // Os trechos vão até a cobertura, para que ela continue contínua
+    let mut ranges = Vec::new();
+    if start_date < from {
+        ranges.push((start_date, from - Duration::days(1)));
+    }
+    if end_date > to {
+        ranges.push((to + Duration::days(1), end_date));
+    }
+    ranges
+}
+
+async fn fetch(ticker: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<Quote>, YahooError> {
+    let provider = YahooConnector::new()?;
+    let timestamp = |date: NaiveDate, h, m, s| {
+        OffsetDateTime::from_unix_timestamp(
+            date.and_hms_opt(h, m, s).unwrap().and_utc().timestamp(),
+        )
+        .unwrap()
+    };
+    let resp = provider
+        .get_quote_history(ticker, timestamp(from, 0, 0, 0), timestamp(to, 23, 59, 59))
+        .await?;
+    match resp.quotes() {
+        // Período sem pregão
+        Err(YahooError::EmptyDataSet) => Ok(Vec::new()),
+        res => res,
+    }
+}
+
+/// Junta as cotações novas à série salva, sem repetir datas (a mais recente prevalece).
+fn merge(file: &Path, quotes: &[Quote]) -> Result<(), Error> {
+    if quotes.is_empty() {
+        return Ok(());
+    }
+    let dates: Vec<NaiveDate> = quotes
+        .iter()
+        .map(|q| {
+            DateTime::from_timestamp(q.timestamp as i64, 0)
+                .unwrap()
+                .naive_utc()
+                .date()
+        })
+        .collect();
+    let new = DataFrame::new(vec![
+        Series::new("AS_DATE", dates).cast(&DataType::Date)?,
+        Series::new("open", quotes.iter().map(|q| q.open).collect::<Vec<_>>()),
+        Series::new("high", quotes.iter().map(|q| q.high).collect::<Vec<_>>()),
+        Series::new("low", quotes.iter().map(|q| q.low).collect::<Vec<_>>()),
+        Series::new("close", quotes.iter().map(|q| q.close).collect::<Vec<_>>()),
+        Series::new(
+            "adjclose",
+            quotes.iter().map(|q| q.adjclose).collect::<Vec<_>>(),
+        ),
+        Series::new(
+            "volume",
+            quotes.iter().map(|q| q.volume).collect::<Vec<_>>(),
+        ),
+    ])?;
+
+    let mut frames = Vec::new();
+    if file.exists() {
+        frames.push(
+            LazyFrame::scan_parquet(file, ScanArgsParquet::default())?
+                .collect()?
+                .lazy(),
+        );
+    }
+    frames.push(new.lazy());
+    let mut df = concat(&frames, UnionArgs::default())?
+        .unique_stable(Some(vec!["AS_DATE".into()]), UniqueKeepStrategy::Last)
+        .sort("AS_DATE", SortOptions::default())
+        .collect()?;
+
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = file.with_extension(format!("{}.tmp", std::process::id()));
+    ParquetWriter::new(File::create(&tmp)?)
+        .with_statistics(true)
+        .finish(&mut df)?;
+    fs::rename(&tmp, file)?;
+    Ok(())
+}
+
+fn rows(file: &Path) -> Option<usize> {
+    LazyFrame::scan_parquet(file, ScanArgsParquet::default())
+        .and_then(|lf| lf.collect())
+        .ok()
+        .map(|df| df.height())
+}
+
+fn coverage_path(file: &Path) -> PathBuf {
+    file.with_extension("json")
+}
+
+fn read_coverage(file: &Path) -> Option<(NaiveDate, NaiveDate)> {
+    let reader = BufReader::new(File::open(coverage_path(file)).ok()?);
+    let coverage: Coverage = serde_json::from_reader(reader).ok()?;
+    Some((coverage.from.parse().ok()?, coverage.to.parse().ok()?))
+}
+
+fn write_coverage(file: &Path, from: NaiveDate, to: NaiveDate) -> Result<(), Error> {
+    let coverage = Coverage {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    let writer = BufWriter::new(File::create(coverage_path(file))?);
+    serde_json::to_writer_pretty(writer, &coverage).map_err(std::io::Error::from)?;
+    Ok(())
+}
+
+/// `^BVSP` → `BVSP`; `USDBRL=X` → `USDBRL_X`.
+fn file_stem(ticker: &str) -> String {
+    ticker
+        .trim_start_matches('^')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Converte os preços (`open`, `high`, `low`, `close`, `adjclose`) para outra moeda,
+/// multiplicando pela última cotação do câmbio (`adjclose` de `fx`) até cada data.
+/// Datas anteriores à primeira cotação do câmbio ficam de fora.
+pub fn convert(prices: &DataFrame, fx: &DataFrame) -> Result<DataFrame, PolarsError> {
+    let fx_dates = fx.column("AS_DATE")?.date()?;
+    let fx_rates = fx.column("adjclose")?.f64()?;
+    let rates: Vec<(NaiveDate, f64)> = fx_dates
+        .as_date_iter()
+        .zip(fx_rates)
+        .filter_map(|(date, rate)| Some((date?, rate?)))
+        .collect();
+
+    let dates = prices.column("AS_DATE")?.date()?;
+    let mut next = 0;
+    let mut current = None;
+    let factors: Vec<Option<f64>> = dates
+        .as_date_iter()
+        .map(|date| {
+            let date = date?;
+            while next < rates.len() && rates[next].0 <= date {
+                current = Some(rates[next].1);
+                next += 1;
+            }
+            current
+        })
+        .collect();
+
+    let factor = Series::new("CAMBIO", factors);
+    let columns = ["open", "high", "low", "close", "adjclose"];
+    let mut exprs = vec![col("AS_DATE")];
+    exprs.extend(
+        columns
+            .iter()
+            .map(|name| (col(name) * col("CAMBIO")).alias(name)),
+    );
+    exprs.push(col("volume"));
+    exprs.push(col("CAMBIO"));
+
+    let mut df = prices.clone();
+    df.with_column(factor)?;
+    df.lazy()
+        .filter(col("CAMBIO").is_not_null())
+        .select(exprs)
+        .collect()
+}
+
+/// Variação diária do preço ajustado (`as_date`, `retorno` em decimal); o primeiro dia
+/// do período tem retorno zero.
+pub fn daily_returns(prices: &DataFrame) -> Result<DataFrame, PolarsError> {
+    prices
+        .clone()
+        .lazy()
+        .sort("AS_DATE", SortOptions::default())
+        .select([
+            col("AS_DATE").alias("as_date"),
+            (col("adjclose") / col("adjclose").shift(1) - lit(1.0))
+                .fill_null(lit(0.0))
+                .alias("retorno"),
+        ])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn covered() -> Option<(NaiveDate, NaiveDate)> {
+        Some((date(2024, 3, 1), date(2024, 6, 30)))
+    }
+
+    #[test]
+    fn without_coverage_fetches_the_whole_period() {
+        assert_eq!(
+            missing_ranges(None, date(2024, 1, 1), date(2024, 12, 31)),
+            vec![(date(2024, 1, 1), date(2024, 12, 31))]
+        );
+    }
+
+    #[test]
+    fn covered_period_has_no_gap() {
+        assert!(missing_ranges(covered(), date(2024, 3, 1), date(2024, 6, 30)).is_empty());
+        assert!(missing_ranges(covered(), date(2024, 4, 10), date(2024, 5, 20)).is_empty());
+    }
+
+    #[test]
+    fn leading_gap_ends_the_day_before_coverage() {
+        assert_eq!(
+            missing_ranges(covered(), date(2024, 1, 15), date(2024, 5, 31)),
+            vec![(date(2024, 1, 15), date(2024, 2, 29))]
+        );
+    }
+
+    #[test]
+    fn trailing_gap_starts_the_day_after_coverage() {
+        assert_eq!(
+            missing_ranges(covered(), date(2024, 4, 1), date(2024, 8, 10)),
+            vec![(date(2024, 7, 1), date(2024, 8, 10))]
+        );
+    }
+
+    #[test]
+    fn period_around_coverage_has_both_gaps() {
+        assert_eq!(
+            missing_ranges(covered(), date(2024, 1, 1), date(2024, 12, 31)),
+            vec![
+                (date(2024, 1, 1), date(2024, 2, 29)),
+                (date(2024, 7, 1), date(2024, 12, 31)),
+            ]
+        );
+    }
+
+    #[test]
+    fn inverted_period_is_empty() {
+        assert!(missing_ranges(covered(), date(2024, 8, 1), date(2024, 1, 1)).is_empty());
+    }
+
+    fn dates(days: &[NaiveDate]) -> Series {
+        let epoch = date(1970, 1, 1);
+        let days: Vec<i32> = days
+            .iter()
+            .map(|d| (*d - epoch).num_days() as i32)
+            .collect();
+        Series::new("AS_DATE", days).cast(&DataType::Date).unwrap()
+    }
+
+    #[test]
+    fn convert_carries_the_last_rate_over_fx_gaps() {
+        // Sem câmbio no fim de semana; em 05/03 a cotação veio sem valor
+        let fx = DataFrame::new(vec![
+            dates(&[
+                date(2024, 3, 1),
+                date(2024, 3, 4),
+                date(2024, 3, 5),
+                date(2024, 3, 6),
+            ]),
+            Series::new("adjclose", [Some(5.0), Some(5.1), None, Some(5.2)]),
+        ])
+        .unwrap();
+        let days = [
+            date(2024, 2, 29),
+            date(2024, 3, 1),
+            date(2024, 3, 2),
+            date(2024, 3, 4),
+            date(2024, 3, 5),
+            date(2024, 3, 7),
+        ];
+        let prices = DataFrame::new(vec![
+            dates(&days),
+            Series::new("open", [10.0; 6]),
+            Series::new("high", [10.0; 6]),
+            Series::new("low", [10.0; 6]),
+            Series::new("close", [10.0; 6]),
+            Series::new("adjclose", [10.0; 6]),
+            Series::new("volume", [100u64; 6]),
+        ])
+        .unwrap();
+
+        let converted = convert(&prices, &fx).unwrap();
+
+        // 29/02 é anterior à primeira cotação do câmbio e fica de fora
+        let converted_days: Vec<_> = converted
+            .column("AS_DATE")
+            .unwrap()
+            .date()
+            .unwrap()
+            .as_date_iter()
+            .flatten()
+            .collect();
+        assert_eq!(converted_days, days[1..]);
+        let rates: Vec<_> = converted
+            .column("CAMBIO")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(rates, [5.0, 5.0, 5.1, 5.1, 5.2]);
+        let close: Vec<_> = converted
+            .column("close")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(close, [50.0, 50.0, 51.0, 51.0, 52.0]);
+    }
+}
@@ -0,0 +1,22 @@
+use crate::config::get;
+use serde::{Deserialize, Serialize};
+use std::{env, path::PathBuf};
+
+const ROOT: &str = "indices.cotacoes";
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Options {
+    pub description: String,
+    /// Subdiretório do cache com uma série persistente por ticker
+    pub store: String,
+}
+
+impl Options {
+    pub fn store_path(&self) -> PathBuf {
+        env::temp_dir().join("cache").join(&self.store)
+    }
+}
+
+pub fn load() -> Result<Options, config::ConfigError> {
+    get::<Options>(ROOT)
+}
@@ -0,0 +1,278 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+pub mod options;
+
+use options::load;
+
+use crate::catalog;
+use polars::{
+    error::PolarsError,
+    frame::DataFrame,
+    io::SerReader,
+    lazy::dsl::{col, lit, StrptimeOptions},
+    prelude::{DataType, IntoLazy, JsonReader, NamedFrom, SortOptions},
+    series::Series,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error getting async path: {0}")]
+    CachedPath(#[from] cached_path::Error),
+
+    #[error("Error loading JsonReader: {0}")]
+    Polars(#[from] PolarsError),
+
+    #[error("Error loading JSON file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Error loading config: {0}")]
+    Config(#[from] config::ConfigError),
+}
+
+/// Periodicidade de uma série do SGS.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Periodicity {
+    /// Taxa em % ao dia útil (ex.: CDI 12, SELIC 11).
+    #[default]
+    Daily,
+    /// Variação em % no mês (ex.: IPCA 433, IGP-M 189). Na poupança (195) vale a
+    /// primeira data de aniversário de cada mês.
+    Monthly,
+}
+
+/// Código da taxa DI (CDI) no SGS, em % ao dia útil.
+pub const CDI: u32 = 12;
+
+/// Taxas diárias do CDI (`as_date`, `retorno` em decimal) no período, usadas nos
+/// indicadores do fundo.
+pub async fn async_cdi(start_date: NaiveDate, end_date: NaiveDate) -> Result<DataFrame, Error> {
+    let df = async_dataframe(CDI, Periodicity::Daily, start_date, end_date).await?;
+    Ok(daily_rates(
+        &df,
+        Periodicity::Daily,
+        None,
+        start_date,
+        end_date,
+    )?)
+}
+
+/// Valores de uma série do SGS no período (`as_date`, `valor` em %), ordenados por data.
+/// Séries mensais são consultadas desde o primeiro dia do mês inicial.
+pub async fn async_dataframe(
+    code: u32,
+    periodicity: Periodicity,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<DataFrame, Error> {
+    let options = load()?;
+    let start_date = match periodicity {
+        Periodicity::Daily => start_date,
+        Periodicity::Monthly => start_date.with_day(1).unwrap(),
+    };
+    let path = options.async_path(code, start_date, end_date).await?;
+    let mut file = std::fs::File::open(path)?;
+    let res = JsonReader::new(&mut file).finish()?;
+    catalog::set_rows(&format!("{}/{}", options.path, code), res.height());
+
+    let df = res
+        .lazy()
+        .select([
+            col("data")
+                .str()
+                .strptime(
+                    DataType::Date,
+                    StrptimeOptions {
+                        format: Some("%d/%m/%Y".into()),
+                        ..Default::default()
+                    },
+                )
+                .alias("as_date"),
+            col("valor").cast(DataType::Float64).alias("valor"),
+        ])
+        .filter(
+            col("as_date")
+                .gt_eq(lit(start_date))
+                .and(col("as_date").lt_eq(lit(end_date))),
+        )
+        .sort("as_date", SortOptions::default())
+        .collect()?;
+    Ok(df)
+}
+
+/// Taxas diárias (`as_date`, `retorno` em decimal) entre `start_date` e `end_date`.
+///
+/// Séries diárias são usadas como estão. A variação de cada mês de uma série mensal
+/// é distribuída geometricamente pelos dias úteis (segunda a sexta) do mês, de modo
+/// que o acumulado no mês completo seja a própria variação divulgada; meses ainda não
+/// divulgados ficam de fora.
+///
+/// `spread` soma uma taxa real em % ao ano (ex.: 6.0 para "IPCA + 6%"), composta com
+/// a série: 252 dias úteis no ano para séries diárias, 12 meses para as mensais.
+pub fn daily_rates(
+    df: &DataFrame,
+    periodicity: Periodicity,
+    spread: Option<f64>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<DataFrame, PolarsError> {
+    let dates = df.column("as_date")?.date()?;
+    let values = df.column("valor")?.f64()?;
+    let series: Vec<(NaiveDate, f64)> = dates
+        .as_date_iter()
+        .zip(values)
+        .filter_map(|(date, value)| Some((date?, value? / 100.0)))
+        .collect();
+    let spread = spread.unwrap_or(0.0) / 100.0;
+
+    let (dates, rates): (Vec<NaiveDate>, Vec<f64>) = match periodicity {
+        Periodicity::Daily => {
+            let factor = (1.0 + spread).powf(1.0 / 252.0);
+            series
+                .into_iter()
+                .filter(|(date, _)| *date >= start_date && *date <= end_date)
+                .map(|(date, rate)| (date, (1.0 + rate) * factor - 1.0))
+                .unzip()
+        }
+        Periodicity::Monthly => {
+            let factor = (1.0 + spread).powf(1.0 / 12.0);
+            let mut months: Vec<(NaiveDate, f64)> = Vec::new();
+            for (date, rate) in series {
+                let month = date.with_day(1).unwrap();
+                if months.last().map_or(true, |(last, _)| *last != month) {
+                    months.push((month, rate));
+                }
+            }
+            months
+                .into_iter()
+                .flat_map(|(month, rate)| {
+                    let days = business_days(month);
+                    let daily = ((1.0 + rate) * factor).powf(1.0 / days.len() as f64) - 1.0;
+                    days.into_iter().map(move |day| (day, daily))
+                })
+                .filter(|(date, _)| *date >= start_date && *date <= end_date)
+                .unzip()
+        }
+    };
+
+    let dates = Series::new("as_date", dates).cast(&DataType::Date)?;
+    DataFrame::new(vec![dates, Series::new("retorno", rates)])
+}
+
+/// Dias de segunda a sexta do mês de `month`.
+fn business_days(month: NaiveDate) -> Vec<NaiveDate> {
+    month
+        .iter_days()
+        .take_while(|day| day.month() == month.month())
+        .filter(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// Série como a do SGS: `valor` em %, uma linha por data.
+    fn series(points: &[(NaiveDate, f64)]) -> DataFrame {
+        let (dates, values): (Vec<NaiveDate>, Vec<f64>) = points.iter().copied().unzip();
+        DataFrame::new(vec![
+            Series::new("as_date", dates).cast(&DataType::Date).unwrap(),
+            Series::new("valor", values),
+        ])
+        .unwrap()
+    }
+
+    /// Fator acumulado das taxas diárias em `[from, to]`.
+    fn compound(df: &DataFrame, from: NaiveDate, to: NaiveDate) -> f64 {
+        let dates = df.column("as_date").unwrap().date().unwrap();
+        let rates = df.column("retorno").unwrap().f64().unwrap();
+        dates
+            .as_date_iter()
+            .zip(rates)
+            .filter(|(d, _)| d.is_some_and(|d| d >= from && d <= to))
+            .map(|(_, rate)| 1.0 + rate.unwrap())
+            .product()
+    }
+
+    #[test]
+    fn monthly_rate_spreads_over_business_days() {
+        let df = series(&[(date(2024, 1, 1), 0.42), (date(2024, 2, 1), 0.83)]);
+        let rates = daily_rates(
+            &df,
+            Periodicity::Monthly,
+            None,
+            date(2024, 1, 1),
+            date(2024, 2, 29),
+        )
+        .unwrap();
+
+        // Janeiro de 2024 tem 23 dias úteis e fevereiro, 21
+        assert_eq!(rates.height(), 44);
+        let january = compound(&rates, date(2024, 1, 1), date(2024, 1, 31));
+        let february = compound(&rates, date(2024, 2, 1), date(2024, 2, 29));
+        assert!((january - 1.0042).abs() < 1e-12);
+        assert!((february - 1.0083).abs() < 1e-12);
+    }
+
+    #[test]
+    fn monthly_spread_compounds_with_the_series() {
+        let df = series(&[(date(2024, 3, 1), 0.16)]);
+        let rates = daily_rates(
+            &df,
+            Periodicity::Monthly,
+            Some(6.0),
+            date(2024, 3, 1),
+            date(2024, 3, 31),
+        )
+        .unwrap();
+
+        let expected = 1.0016 * 1.06_f64.powf(1.0 / 12.0);
+        let march = compound(&rates, date(2024, 3, 1), date(2024, 3, 31));
+        assert!((march - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn period_cuts_the_spread_month() {
+        let df = series(&[(date(2024, 1, 1), 0.42)]);
+        let rates = daily_rates(
+            &df,
+            Periodicity::Monthly,
+            None,
+            date(2024, 1, 15),
+            date(2024, 1, 31),
+        )
+        .unwrap();
+
+        // Do dia 15 ao 31 há 13 dias úteis, cada um com a taxa diária do mês inteiro
+        assert_eq!(rates.height(), 13);
+        let expected = 1.0042_f64.powf(13.0 / 23.0);
+        let partial = compound(&rates, date(2024, 1, 1), date(2024, 1, 31));
+        assert!((partial - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn daily_series_is_kept_as_is() {
+        let df = series(&[
+            (date(2024, 1, 2), 0.04),
+            (date(2024, 1, 3), 0.05),
+            (date(2024, 1, 4), 0.04),
+        ]);
+        let rates = daily_rates(
+            &df,
+            Periodicity::Daily,
+            None,
+            date(2024, 1, 3),
+            date(2024, 1, 31),
+        )
+        .unwrap();
+
+        assert_eq!(rates.height(), 2);
+        let total = compound(&rates, date(2024, 1, 1), date(2024, 1, 31));
+        assert!((total - 1.0005 * 1.0004).abs() < 1e-12);
+    }
+}
@@ -1,11 +1,10 @@
-use crate::config::get;
-use cached_path::{cached_path_with_options, Cache};
+use crate::{catalog, config::get};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tokio::task::spawn_blocking;
 
-const ROOT: &str = "indices.cdi";
+const ROOT: &str = "indices.sgs";
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct Options {
@@ -15,8 +14,9 @@ pub struct Options {
 }
 
 impl Options {
-    pub fn url_with_date(&self, start_date: NaiveDate, end_date: NaiveDate) -> String {
+    pub fn url_with_code(&self, code: u32, start_date: NaiveDate, end_date: NaiveDate) -> String {
         let mut pattern = self.url.to_string();
+        pattern = pattern.replace("{code}", &code.to_string());
         pattern = pattern.replace("{start_date}", &start_date.format("%d/%m/%Y").to_string());
         pattern = pattern.replace("{end_date}", &end_date.format("%d/%m/%Y").to_string());
         pattern
@@ -24,32 +24,21 @@ impl Options {
 
     pub async fn async_path(
         &self,
+        code: u32,
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<PathBuf, cached_path::Error> {
-        let url = self.url_with_date(start_date, end_date).clone();
+        let url = self.url_with_code(code, start_date, end_date);
         let subdir = self.path.clone();
-        // Baixa o arquivo usando `cached_path`
+        // Uma entrada no catálogo por série, ex.: `sgs/433`
+        let dataset = format!("{}/{}", subdir, code);
 
         spawn_blocking(move || {
-            let res = cached_path_with_options(
+            catalog::fetch(
+                &dataset,
                 url.as_str(),
                 &cached_path::Options::default().subdir(&subdir),
-            );
-
-            match res {
-                Ok(path) => Ok(path),
-                Err(_err) => {
-                    let cache = Cache::builder()
-                        .progress_bar(Some(cached_path::ProgressBar::Full))
-                        .offline(true)
-                        .build()?;
-                    cache.cached_path_with_options(
-                        url.as_str(),
-                        &cached_path::Options::default().subdir(&subdir),
-                    )
-                }
-            }
+            )
         })
         .await
         .unwrap()
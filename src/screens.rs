@@ -0,0 +1,36 @@
+use crate::analysis::screener::Screen;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::PathBuf;
+
+// Telas salvas do screener, gravadas junto ao histórico
+#[derive(Debug, Clone)]
+pub struct Screens {
+    filename: PathBuf,
+}
+
+impl Screens {
+    pub fn new() -> Self {
+        let filename = env::temp_dir().join("cache/screens.json");
+
+        Screens { filename }
+    }
+
+    pub fn load(&self) -> io::Result<Vec<Screen>> {
+        let file = File::open(&self.filename)?;
+        let reader = BufReader::new(file);
+        let screens = serde_json::from_reader(reader)?;
+        Ok(screens)
+    }
+
+    pub fn save(&self, screens: &[Screen]) -> io::Result<()> {
+        if let Some(dir) = self.filename.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = File::create(&self.filename)?;
+        let writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, screens)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use super::app::TemplateApp;
+use crate::{catalog, downloader::DownloadState, message::Message, util};
 use eframe::egui::{Context, TopBottomPanel};
 use eframe::Frame;
-use egui::{global_dark_light_mode_switch, Layout};
+use egui::{global_dark_light_mode_switch, Color32, Grid, Layout, Ui};
 impl TemplateApp {
     pub fn show_statusbar(&mut self, ctx: &Context, _frame: &mut Frame) {
         TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
@@ -9,6 +10,11 @@ impl TemplateApp {
                 global_dark_light_mode_switch(ui);
                 ui.add_space(5.0);
                 ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    self.show_offline_toggle(ui);
+                    ui.separator();
+                    self.show_sync(ui);
+                    show_freshness(ui);
+                    ui.separator();
                     let btn = ui
                         .small_button(egui_phosphor::regular::WARNING)
                         .on_hover_ui(|ui| {
@@ -24,6 +30,54 @@ impl TemplateApp {
         self.show_logs(ctx);
     }
 
+    /// Andamento da sincronização em segundo plano, com botão para cancelar.
+    fn show_sync(&mut self, ui: &mut Ui) {
+        if self.downloading {
+            if ui
+                .small_button(egui_phosphor::regular::X)
+                .on_hover_text("Cancelar sincronização")
+                .clicked()
+            {
+                let _ = self.channel.0.send(Message::CancelDownload(None));
+            }
+            let done = self
+                .download_states
+                .iter()
+                .filter(|(_, state)| state.is_finished())
+                .count();
+            ui.label(format!(
+                "Sincronizando {}/{} {}",
+                done,
+                self.download_states.len(),
+                self.status
+            ))
+            .on_hover_ui(|ui| show_downloads(ui, &self.download_states));
+            ui.spinner();
+            ui.separator();
+        } else if !self.offline
+            && ui
+                .small_button(egui_phosphor::regular::ARROWS_CLOCKWISE)
+                .on_hover_text("Sincronizar dados agora")
+                .clicked()
+        {
+            let _ = self.channel.0.send(Message::StartDownload);
+        }
+    }
+
+    fn show_offline_toggle(&mut self, ui: &mut Ui) {
+        let icon = if self.offline {
+            egui_phosphor::regular::WIFI_SLASH
+        } else {
+            egui_phosphor::regular::WIFI_HIGH
+        };
+        let response = ui
+            .toggle_value(&mut self.offline, format!("{} Offline", icon))
+            .on_hover_text("Usar somente os dados em cache, sem consultar as fontes");
+        if response.changed() {
+            catalog::set_offline(self.offline);
+        }
+    }
+
     fn show_logs(&mut self, ctx: &Context) {
         egui::Window::new("Log do Sistema")
             .open(&mut self.open_logs)
@@ -33,3 +87,115 @@ impl TemplateApp {
             });
     }
 }
+
+/// Idade dos dados em cache e aviso quando a última atualização de algum conjunto falhou.
+fn show_freshness(ui: &mut Ui) {
+    let entries = catalog::entries();
+    if entries.is_empty() {
+        return;
+    }
+    let stale = entries.iter().filter(|(_, entry)| entry.is_stale()).count();
+    let checked = entries
+        .iter()
+        .filter_map(|(_, entry)| entry.checked_at)
+        .max();
+
+    let text = match checked {
+        Some(checked) => format!(
+            "{} Dados verificados {}",
+            egui_phosphor::regular::DATABASE,
+            catalog::age(checked)
+        ),
+        None => format!("{} Dados em cache", egui_phosphor::regular::DATABASE),
+    };
+    let response = if stale > 0 {
+        ui.colored_label(
+            Color32::from_rgb(230, 160, 0),
+            format!(
+                "{} {} ({} com falha)",
+                egui_phosphor::regular::WARNING_CIRCLE,
+                text,
+                stale
+            ),
+        )
+    } else {
+        ui.weak(text)
+    };
+    response.on_hover_ui(|ui| show_catalog(ui, &entries));
+}
+
+fn show_catalog(ui: &mut Ui, entries: &[(String, catalog::Entry)]) {
+    Grid::new("catalog_grid").striped(true).show(ui, |ui| {
+        ui.strong("Conjunto");
+        ui.strong("Baixado");
+        ui.strong("Linhas");
+        ui.strong("Last-Modified");
+        ui.strong("Situação");
+        ui.end_row();
+        for (dataset, entry) in entries {
+            ui.label(dataset).on_hover_text(&entry.url);
+            ui.label(entry.downloaded_at.map(catalog::age).unwrap_or("-".into()));
+            ui.label(
+                entry
+                    .rows
+                    .map(|rows| rows.to_string())
+                    .unwrap_or("-".into()),
+            );
+            ui.label(entry.last_modified.as_deref().unwrap_or("-"))
+                .on_hover_text(format!("ETag: {}", entry.etag.as_deref().unwrap_or("-")));
+            if entry.is_stale() {
+                let failed = entry.failed_at.map(catalog::age).unwrap_or_default();
+                ui.colored_label(Color32::RED, format!("Falhou {}", failed))
+                    .on_hover_text(entry.last_error.as_deref().unwrap_or_default());
+            } else if entry.downloaded_at.is_none() {
+                ui.weak("Indisponível");
+            } else {
+                ui.label("Atualizado");
+            }
+            ui.end_row();
+        }
+    });
+}
+
+fn show_downloads(ui: &mut Ui, states: &[(String, DownloadState)]) {
+    Grid::new("downloads_grid").striped(true).show(ui, |ui| {
+        for (name, state) in states {
+            ui.label(name);
+            match state {
+                DownloadState::Queued => {
+                    ui.weak("Na fila");
+                }
+                DownloadState::Downloading {
+                    bytes,
+                    total: Some(total),
+                } if *total > 0 => {
+                    ui.add(
+                        egui::ProgressBar::new(*bytes as f32 / *total as f32)
+                            .desired_width(150.0)
+                            .text(format!(
+                                "{} de {}",
+                                util::format_bytes(*bytes),
+                                util::format_bytes(*total)
+                            )),
+                    );
+                }
+                DownloadState::Downloading { bytes, .. } => {
+                    ui.label(format!("Baixando {}", util::format_bytes(*bytes)));
+                }
+                DownloadState::Processing => {
+                    ui.label("Processando");
+                }
+                DownloadState::Done => {
+                    ui.label("Concluído");
+                }
+                DownloadState::Failed(err) => {
+                    ui.colored_label(Color32::RED, "Falhou").on_hover_text(err);
+                }
+                DownloadState::Cancelled => {
+                    ui.weak("Cancelado");
+                }
+            }
+            ui.end_row();
+        }
+    });
+}
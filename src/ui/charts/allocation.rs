@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use egui::{Color32, Stroke, Ui};
+use egui_plot::{Line, Polygon};
+
+use super::compare::{self, timestamp};
+
+/// Participação (%) de cada grupo empilhada mês a mês.
+///
+/// Cada faixa é desenhada como um trapézio por intervalo entre meses, pois o
+/// `Polygon` do egui_plot só preenche polígonos convexos.
+pub fn stacked_area(
+    id: &str,
+    months: &[NaiveDate],
+    groups: &[(String, Vec<f64>)],
+    colors: &[Color32],
+    ui: &mut Ui,
+) {
+    let xs: Vec<f64> = months.iter().map(|month| timestamp(*month)).collect();
+    let mut lower = vec![0.0; months.len()];
+    let mut polygons = Vec::new();
+    let mut lines = Vec::new();
+
+    for ((name, values), color) in groups.iter().zip(colors) {
+        let upper: Vec<f64> = lower.iter().zip(values).map(|(l, v)| l + v).collect();
+        for i in 1..xs.len() {
+            let points = vec![
+                [xs[i - 1], lower[i - 1]],
+                [xs[i], lower[i]],
+                [xs[i], upper[i]],
+                [xs[i - 1], upper[i - 1]],
+            ];
+            polygons.push(
+                Polygon::new(points)
+                    .name(name)
+                    .stroke(Stroke::new(0.5, *color))
+                    .fill_color(color.gamma_multiply(0.8)),
+            );
+        }
+        let top: Vec<[f64; 2]> = xs.iter().zip(&upper).map(|(x, y)| [*x, *y]).collect();
+        lines.push(Line::new(top).color(*color).name(name));
+        lower = upper;
+    }
+
+    compare::date_plot(id, "%m/%Y", "%")
+        .set_margin_fraction(egui::Vec2::new(0.0, 0.05))
+        .include_y(100.0)
+        .label_formatter(|name, value| {
+            match compare::date_at(value.x, "%m/%Y").filter(|_| !name.is_empty()) {
+                Some(date) => format!("{}: ({})", name, date),
+                None => "".to_owned(),
+            }
+        })
+        .show(ui, |plot_ui| {
+            for polygon in polygons {
+                plot_ui.polygon(polygon);
+            }
+            for line in lines {
+                plot_ui.line(line);
+            }
+        });
+}
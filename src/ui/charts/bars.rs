@@ -0,0 +1,33 @@
+use chrono::NaiveDate;
+use egui::{Color32, Ui};
+use egui_plot::{Bar, BarChart};
+
+use super::compare::{date_plot, timestamp};
+
+const DAY: f64 = 86_400.0;
+
+/// Uma barra por data, com `days` de largura e `unit` após os valores do eixo y. A cor
+/// de cada barra vem do seu valor (ex.: verde para entradas e vermelho para saídas).
+/// Barras de um mês ou mais mostram as datas como mês/ano.
+pub fn by_date(
+    id: &str,
+    name: &str,
+    points: &[(NaiveDate, f64)],
+    days: f64,
+    color: impl Fn(f64) -> Color32,
+    unit: &'static str,
+    ui: &mut Ui,
+) {
+    let bars: Vec<Bar> = points
+        .iter()
+        .map(|(date, value)| {
+            Bar::new(timestamp(*date), *value)
+                .width(days * DAY * 0.8)
+                .fill(color(*value))
+        })
+        .collect();
+    let chart = BarChart::new(bars).name(name).color(color(0.0));
+    let date_format = if days >= 28.0 { "%m/%Y" } else { "%d/%m/%Y" };
+
+    date_plot(id, date_format, unit).show(ui, |plot_ui| plot_ui.bar_chart(chart));
+}
@@ -0,0 +1,76 @@
+use std::ops::RangeInclusive;
+
+use chrono::{DateTime, NaiveDate};
+use egui::{Color32, Ui};
+use egui_plot::{AxisHints, GridMark, HPlacement, Legend, Line, Plot};
+
+pub struct SeriesLine {
+    pub name: String,
+    pub color: Color32,
+    pub points: Vec<(NaiveDate, f64)>,
+}
+
+/// Retorno acumulado (%) de várias séries na mesma escala de datas.
+pub fn chart(id: &str, series: &[SeriesLine], ui: &mut Ui) {
+    chart_with_unit(id, series, "%", ui);
+}
+
+/// Várias séries na mesma escala de datas, com `unit` após os valores do eixo y.
+pub fn chart_with_unit(id: &str, series: &[SeriesLine], unit: &'static str, ui: &mut Ui) {
+    let lines: Vec<Line> = series
+        .iter()
+        .map(|s| {
+            let points: Vec<[f64; 2]> = s
+                .points
+                .iter()
+                .map(|(date, value)| [timestamp(*date), *value])
+                .collect();
+            Line::new(points).color(s.color).name(s.name.to_string())
+        })
+        .collect();
+
+    date_plot(id, "%d/%m/%Y", unit).show(ui, |plot_ui| {
+        for line in lines {
+            plot_ui.line(line);
+        }
+    });
+}
+
+pub fn timestamp(date: NaiveDate) -> f64 {
+    date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as f64
+}
+
+/// Data de um valor do eixo x (timestamp em segundos) no formato `date_format`.
+pub fn date_at(x: f64, date_format: &str) -> Option<String> {
+    let timestamp = x as i64;
+    if timestamp <= 0 {
+        return None;
+    }
+    DateTime::from_timestamp(timestamp, 0).map(|datetime| datetime.format(date_format).to_string())
+}
+
+/// Gráfico com datas no eixo x (em `date_format`) e `unit` após os valores do eixo y.
+/// O cursor sobre uma série mostra o nome, a data e o valor.
+pub fn date_plot(id: &str, date_format: &'static str, unit: &'static str) -> Plot {
+    let x_formatter = move |mark: GridMark, _digits, _range: &RangeInclusive<f64>| {
+        date_at(mark.value, date_format).unwrap_or_default()
+    };
+    let y_formatter = move |mark: GridMark, _digits, _range: &RangeInclusive<f64>| {
+        format!("{}{}", mark.value, unit)
+    };
+
+    Plot::new(id)
+        .legend(Legend::default())
+        .set_margin_fraction(egui::Vec2::new(0.0, 0.15))
+        .y_axis_position(HPlacement::Left)
+        .y_axis_width(0)
+        .custom_x_axes(vec![AxisHints::new_x().label("").formatter(x_formatter)])
+        .custom_y_axes(vec![AxisHints::new_y().label("").formatter(y_formatter)])
+        .include_y(0.0)
+        .label_formatter(move |name, value| {
+            match date_at(value.x, date_format).filter(|_| !name.is_empty()) {
+                Some(date) => format!("{}: ({}, {:.2}{})", name, date, value.y, unit),
+                None => "".to_owned(),
+            }
+        })
+}
@@ -1,2 +1,5 @@
+pub mod allocation;
+pub mod bars;
+pub mod compare;
 pub mod profit;
 pub mod stats;
@@ -1,10 +1,53 @@
 use std::ops::RangeInclusive;
 
 use chrono::DateTime;
-use egui::{Color32, Ui};
-use egui_plot::{AxisHints, GridMark, Legend, Line, Plot};
+use egui::{Align2, Color32, RichText, Ui};
+use egui_plot::{AxisHints, GridMark, Legend, Line, Plot, PlotPoint, Points, Text};
 use polars::frame::DataFrame;
 
+use crate::analysis::{metrics::Drawdown, rolling::Rolling, TimeSeries};
+
+use super::compare::{self, SeriesLine};
+
+/// O que o gráfico de rentabilidade exibe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Retorno acumulado no período, com os índices de referência.
+    Cumulative,
+    RollingReturn,
+    RollingVolatility,
+    RollingSharpe,
+    /// Queda em relação ao pico, do fundo e dos índices de referência.
+    Drawdown,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 5] = [
+        Mode::Cumulative,
+        Mode::RollingReturn,
+        Mode::RollingVolatility,
+        Mode::RollingSharpe,
+        Mode::Drawdown,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mode::Cumulative => "Acumulado",
+            Mode::RollingReturn => "Retorno móvel",
+            Mode::RollingVolatility => "Volatilidade móvel",
+            Mode::RollingSharpe => "Sharpe móvel",
+            Mode::Drawdown => "Drawdown",
+        }
+    }
+
+    pub fn is_rolling(&self) -> bool {
+        matches!(
+            self,
+            Mode::RollingReturn | Mode::RollingVolatility | Mode::RollingSharpe
+        )
+    }
+}
+
 pub struct Indice {
     pub name: String,
     pub color: Color32,
@@ -16,21 +59,15 @@ pub fn chart(dataframe: &DataFrame, indices: Vec<Indice>, ui: &mut Ui) {
     let color = ui.visuals().selection.bg_fill;
 
     let green = Color32::from_rgb(0, 255, 0); // Verde
-    let chart = match (dataframe.column("DT_COMPTC"), dataframe.column("RENT_ACUM")) {
+    let chart = match (dataframe.column("AS_DATE"), dataframe.column("RENT_ACUM")) {
         (Ok(dates), Ok(rentabilidade)) => {
             let mut line_data = Vec::new();
-            let dates = dates.utf8().unwrap();
+            let dates = dates.date().unwrap();
             let rentabilidade = rentabilidade.f64().unwrap();
-            for (date, rent) in dates.into_iter().zip(rentabilidade.into_iter()) {
+            for (date, rent) in dates.as_date_iter().zip(rentabilidade.into_iter()) {
                 if let (Some(date), Some(rent)) = (date, rent) {
-                    if let Ok(parsed_date) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") {
-                        let timestamp = parsed_date
-                            .and_hms_opt(0, 0, 0)
-                            .unwrap()
-                            .and_utc()
-                            .timestamp() as f64;
-                        line_data.push([timestamp, rent]);
-                    }
+                    let timestamp = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as f64;
+                    line_data.push([timestamp, rent]);
                 }
             }
 
@@ -42,24 +79,18 @@ pub fn chart(dataframe: &DataFrame, indices: Vec<Indice>, ui: &mut Ui) {
     let mut charts = Vec::new();
     for indice in indices.iter() {
         let chart = match (
-            indice.dataframe.column("date"),
+            indice.dataframe.column("as_date"),
             indice.dataframe.column("value"),
         ) {
             (Ok(dates), Ok(rentabilidade)) => {
                 let mut line_data = Vec::new();
-                let dates = dates.utf8().unwrap();
+                let dates = dates.date().unwrap();
                 let rentabilidade = rentabilidade.f64().unwrap();
-                for (date, rent) in dates.into_iter().zip(rentabilidade.into_iter()) {
+                for (date, rent) in dates.as_date_iter().zip(rentabilidade.into_iter()) {
                     if let (Some(date), Some(rent)) = (date, rent) {
-                        if let Ok(parsed_date) = chrono::NaiveDate::parse_from_str(date, "%d/%m/%Y")
-                        {
-                            let timestamp = parsed_date
-                                .and_hms_opt(0, 0, 0)
-                                .unwrap()
-                                .and_utc()
-                                .timestamp() as f64;
-                            line_data.push([timestamp, rent]);
-                        }
+                        let timestamp =
+                            date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as f64;
+                        line_data.push([timestamp, rent]);
                     }
                 }
                 Line::new(line_data)
@@ -118,3 +149,74 @@ pub fn chart(dataframe: &DataFrame, indices: Vec<Indice>, ui: &mut Ui) {
             }
         });
 }
+
+/// Indicadores em janela móvel do fundo; o retorno é comparado ao CDI na mesma janela.
+pub fn rolling(rolling: &Rolling, mode: Mode, ui: &mut Ui) {
+    let percent = |series: &TimeSeries| -> Vec<(chrono::NaiveDate, f64)> {
+        series.iter().map(|(date, v)| (*date, v * 100.0)).collect()
+    };
+    let fund = |points| SeriesLine {
+        name: "Fundo".to_string(),
+        color: Color32::from_rgb(0, 255, 0),
+        points,
+    };
+
+    let (lines, unit) = match mode {
+        Mode::RollingReturn => (
+            vec![
+                fund(percent(&rolling.returns)),
+                SeriesLine {
+                    name: "CDI".to_string(),
+                    color: Color32::from_rgb(255, 0, 0),
+                    points: percent(&rolling.cdi_returns),
+                },
+            ],
+            "%",
+        ),
+        Mode::RollingVolatility => (vec![fund(percent(&rolling.volatility))], "%"),
+        Mode::RollingSharpe => (vec![fund(rolling.sharpe.clone())], ""),
+        Mode::Cumulative | Mode::Drawdown => return,
+    };
+    compare::chart_with_unit("plot::funds::rolling", &lines, unit, ui);
+}
+
+/// Drawdown (%) do fundo e dos índices; a primeira série é a do fundo, cujo pior
+/// drawdown é destacado no ponto de mínimo.
+pub fn drawdown(series: &[SeriesLine], worst: Option<Drawdown>, ui: &mut Ui) {
+    let lines: Vec<Line> = series
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let points: Vec<[f64; 2]> = s
+                .points
+                .iter()
+                .map(|(date, value)| [compare::timestamp(*date), value * 100.0])
+                .collect();
+            let line = Line::new(points).color(s.color).name(s.name.to_string());
+            if i == 0 {
+                line.fill(0.0)
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    let red = Color32::from_rgb(255, 0, 0);
+
+    compare::date_plot("plot::funds::drawdown", "%d/%m/%Y", "%").show(ui, |plot_ui| {
+        for line in lines {
+            plot_ui.line(line);
+        }
+        if let Some(worst) = worst {
+            let point = PlotPoint::new(compare::timestamp(worst.trough), worst.depth * 100.0);
+            plot_ui.points(Points::new(vec![[point.x, point.y]]).radius(4.0).color(red));
+            let label = format!(
+                "Pior drawdown: {:.2}% em {}",
+                worst.depth * 100.0,
+                worst.trough.format("%d/%m/%Y")
+            );
+            plot_ui
+                .text(Text::new(point, RichText::new(label).color(red)).anchor(Align2::LEFT_TOP));
+        }
+    });
+}
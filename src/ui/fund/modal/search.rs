@@ -1,5 +1,9 @@
-use crate::{message::Message, provider::cvm::fund::Class, ui::loading};
-use egui::{Align2, TopBottomPanel, Vec2};
+use crate::{
+    message::Message,
+    provider::cvm::fund::{AnbimaClass, Class, Filters, FundType, Situation},
+    ui::loading,
+};
+use egui::{Align2, ComboBox, TopBottomPanel, Vec2};
 use egui_extras::{Column, TableBuilder};
 use polars::frame::DataFrame;
 use tokio::sync::mpsc::UnboundedSender;
@@ -8,14 +12,14 @@ pub struct Search {
     sender: UnboundedSender<Message>,
     pub open_window: bool,
     pub query: String,
-    pub class: Option<Class>,
+    pub filters: Filters,
     pub result: DataFrame,
     pub loading: bool,
 }
 
 enum Msg {
     Search,
-    SelectClass(Option<Class>),
+    SelectFilters,
 }
 
 impl Search {
@@ -24,7 +28,7 @@ impl Search {
             sender,
             open_window,
             query: "".to_string(),
-            class: None,
+            filters: Filters::active(),
             result: DataFrame::empty(),
             loading: false,
         }
@@ -36,8 +40,7 @@ impl Search {
                 self.set_loading(true);
                 self.search_send();
             }
-            Msg::SelectClass(selected_class) => {
-                self.class = selected_class;
+            Msg::SelectFilters => {
                 self.search_send();
                 self.set_loading(true);
             }
@@ -80,13 +83,7 @@ impl Search {
                 }
 
                 ui.add_space(5.0);
-                ui.horizontal(|ui| {
-                    self.handle_selectable_value(ui, None, "Todos");
-                    self.handle_selectable_value(ui, Some(Class::Acoes), "Ações");
-                    self.handle_selectable_value(ui, Some(Class::RendaFixa), "Renda Fixa");
-                    self.handle_selectable_value(ui, Some(Class::Cambial), "Cambial");
-                    self.handle_selectable_value(ui, Some(Class::MultiMarket), "MultiMercado");
-                });
+                self.show_filters(ui);
                 ui.add_space(5.0);
                 let nr_rows = self.result.height();
                 let cols: Vec<&str> = vec!["CNPJ_FUNDO", "DENOM_SOCIAL"];
@@ -149,26 +146,69 @@ impl Search {
 
         if !open {
             self.query = "".to_string();
-            self.class = None;
+            self.filters = Filters::active();
         }
 
         self.open_window = open;
     }
-    fn handle_selectable_value(&mut self, ui: &mut egui::Ui, class: Option<Class>, label: &str) {
-        if ui
-            .selectable_value(&mut self.class, class.clone(), label)
-            .clicked()
-        {
-            self.update(Msg::SelectClass(class));
+    fn show_filters(&mut self, ui: &mut egui::Ui) {
+        let filters = &mut self.filters;
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            changed |= filter_combobox(
+                ui,
+                "Situação",
+                &mut filters.situation,
+                &Situation::ALL,
+                |s| s.label(),
+            );
+            changed |=
+                filter_combobox(ui, "Classe", &mut filters.class, &Class::ALL, |c| c.label());
+            changed |= filter_combobox(ui, "Tipo", &mut filters.fund_type, &FundType::ALL, |t| {
+                t.as_str()
+            });
+            changed |= filter_combobox(
+                ui,
+                "ANBIMA",
+                &mut filters.anbima_class,
+                &AnbimaClass::ALL,
+                |a| a.as_str(),
+            );
+        });
+        if changed {
+            self.update(Msg::SelectFilters);
         }
     }
 
     fn search_send(&self) {
         let sender = self.sender.clone();
         let text = self.query.to_string();
-        let class = self.class.clone();
+        let filters = self.filters.clone();
         tokio::spawn(async move {
-            let _ = sender.send(Message::SearchFunds(text, class));
+            let _ = sender.send(Message::SearchFunds(text, filters));
         });
     }
 }
+
+/// Seleção de um filtro da busca, com "Todos" para não restringir.
+fn filter_combobox<T: Clone + PartialEq>(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<T>,
+    options: &[T],
+    text: impl Fn(&T) -> &str,
+) -> bool {
+    let mut changed = false;
+    ui.weak(label);
+    ComboBox::from_id_source(label)
+        .selected_text(value.as_ref().map_or("Todos", &text))
+        .show_ui(ui, |ui| {
+            changed |= ui.selectable_value(value, None, "Todos").changed();
+            for option in options {
+                changed |= ui
+                    .selectable_value(value, Some(option.clone()), text(option))
+                    .changed();
+            }
+        });
+    changed
+}
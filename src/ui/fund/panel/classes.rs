@@ -0,0 +1,168 @@
+use crate::{
+    message,
+    provider::cvm::registry::{FundClass, RegisteredFund},
+    ui::loading,
+    util,
+};
+use egui::{CollapsingHeader, Grid, RichText};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Hierarquia fundo → classes → subclasses do registro da Resolução CVM 175.
+#[derive(Default)]
+pub struct ClassesUI {
+    pub cnpj: String,
+    pub sender: Option<UnboundedSender<message::Message>>,
+    pub loading: bool,
+    pub requested: bool,
+    pub fund: Option<RegisteredFund>,
+}
+
+impl ClassesUI {
+    /// Pede o registro uma única vez por aba.
+    pub fn send_registry_message(&mut self) {
+        if self.requested {
+            return;
+        }
+        self.requested = true;
+        self.loading = true;
+        let _ = self
+            .sender
+            .clone()
+            .unwrap()
+            .send(message::Message::Registry(self.cnpj.clone()));
+    }
+
+    pub fn set_fund(&mut self, fund: Option<RegisteredFund>) {
+        self.fund = fund;
+        self.loading = false;
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.heading(RichText::new("Classes e Subclasses").size(16.0));
+            ui.separator();
+
+            if self.loading {
+                loading::show(ui);
+                return;
+            }
+            let Some(fund) = &self.fund else {
+                ui.label("Fundo não encontrado no registro de classes (Resolução CVM 175).");
+                return;
+            };
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                Grid::new(ui.id().with("registry_fund"))
+                    .num_columns(2)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        row(ui, "Fundo", Some(&fund.name));
+                        row(ui, "CNPJ do fundo", Some(&fund.cnpj));
+                        row(ui, "Tipo", fund.fund_type.as_ref());
+                        row(
+                            ui,
+                            "Situação",
+                            fund.situation
+                                .as_ref()
+                                .map(|s| s.label().to_string())
+                                .as_ref(),
+                        );
+                        row(
+                            ui,
+                            "Adaptação à CVM 175",
+                            fund.adaptation_date
+                                .map(|d| d.format("%d/%m/%Y").to_string())
+                                .as_ref(),
+                        );
+                    });
+                ui.add_space(10.0);
+
+                if fund.classes.is_empty() {
+                    ui.label("Nenhuma classe registrada.");
+                }
+                for class in &fund.classes {
+                    show_class(ui, class, class.cnpj.as_deref() == Some(self.cnpj.as_str()));
+                }
+            });
+        });
+    }
+}
+
+fn show_class(ui: &mut egui::Ui, class: &FundClass, consulted: bool) {
+    let mut title = RichText::new(format!("{} {}", egui_phosphor::regular::FOLDER, class.name));
+    if consulted {
+        title = title.strong();
+    }
+    CollapsingHeader::new(title)
+        .id_source(&class.id)
+        .default_open(consulted)
+        .show(ui, |ui| {
+            Grid::new(ui.id().with("registry_class"))
+                .num_columns(2)
+                .spacing([20.0, 4.0])
+                .show(ui, |ui| {
+                    row(ui, "CNPJ da classe", class.cnpj.as_ref());
+                    row(ui, "Tipo", class.kind.as_ref());
+                    row(
+                        ui,
+                        "Situação",
+                        class
+                            .situation
+                            .as_ref()
+                            .map(|s| s.label().to_string())
+                            .as_ref(),
+                    );
+                    row(ui, "Classificação", class.classification.as_ref());
+                    row(ui, "Classificação ANBIMA", class.anbima_class.as_ref());
+                    row(
+                        ui,
+                        "Patrimônio líquido",
+                        class
+                            .net_worth
+                            .and_then(|v| util::to_real(v).ok())
+                            .map(|v| v.format())
+                            .as_ref(),
+                    );
+                });
+
+            if class.subclasses.is_empty() {
+                return;
+            }
+            ui.add_space(5.0);
+            ui.strong("Subclasses");
+            Grid::new(ui.id().with("registry_subclasses"))
+                .num_columns(5)
+                .striped(true)
+                .spacing([20.0, 4.0])
+                .show(ui, |ui| {
+                    for title in [
+                        "Nome",
+                        "Código CVM",
+                        "Situação",
+                        "Exclusiva",
+                        "Público-alvo",
+                    ] {
+                        ui.weak(title);
+                    }
+                    ui.end_row();
+                    for subclass in &class.subclasses {
+                        ui.label(&subclass.name);
+                        ui.label(subclass.cvm_code.as_deref().unwrap_or("-"));
+                        ui.label(subclass.situation.as_ref().map_or("-", |s| s.label()));
+                        ui.label(match subclass.exclusive {
+                            Some(true) => "Sim",
+                            Some(false) => "Não",
+                            None => "-",
+                        });
+                        ui.label(subclass.target_audience.as_deref().unwrap_or("-"));
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn row(ui: &mut egui::Ui, label: &str, value: Option<&String>) {
+    ui.weak(label);
+    ui.label(value.map_or("-", |v| v.as_str()));
+    ui.end_row();
+}
@@ -0,0 +1,160 @@
+use crate::{
+    analysis::{
+        credit::{Concentration, Credit, Limits},
+        portfolio::Position,
+    },
+    provider::cvm::portfolio::options,
+    util,
+};
+use egui::{Color32, DragValue, Grid};
+use egui_extras::{Column, TableBuilder};
+
+/// Exposição de crédito da carteira do mês: concentração por emissor, vencimentos e
+/// emissores ligados.
+pub struct CreditUI {
+    pub credit: Credit,
+    pub limits: Limits,
+}
+
+impl Default for CreditUI {
+    fn default() -> Self {
+        CreditUI {
+            credit: Credit::default(),
+            limits: Limits {
+                warning: options::issuer_warning(),
+                limit: options::issuer_limit(),
+            },
+        }
+    }
+}
+
+impl CreditUI {
+    pub fn set_positions(&mut self, positions: &[Position], net_worth: f64) {
+        self.credit = Credit::compute(positions, net_worth);
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        if self.credit.issuers.is_empty() && self.credit.related_party.is_empty() {
+            ui.label("Carteira sem posições de crédito privado.");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.weak("Alerta (% PL)");
+            ui.add(
+                DragValue::new(&mut self.limits.warning)
+                    .speed(0.5)
+                    .clamp_range(0.0..=100.0),
+            );
+            ui.weak("Limite (% PL)");
+            ui.add(
+                DragValue::new(&mut self.limits.limit)
+                    .speed(0.5)
+                    .clamp_range(0.0..=100.0),
+            );
+            ui.separator();
+            ui.weak("Emissores");
+            ui.label(self.credit.issuers.len().to_string());
+            ui.separator();
+            ui.weak("Acima do alerta");
+            ui.label(self.credit.concentrated(self.limits).to_string());
+        });
+        ui.add_space(5.0);
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.columns(2, |columns| {
+                columns[0].strong("Por emissor");
+                self.issuer_table(&mut columns[0]);
+                columns[1].strong("Por vencimento");
+                self.maturity_grid(&mut columns[1]);
+            });
+
+            if !self.credit.related_party.is_empty() {
+                ui.separator();
+                ui.strong("Emissores ligados");
+                Grid::new(ui.id().with("credit_related_party"))
+                    .num_columns(4)
+                    .striped(true)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        for position in &self.credit.related_party {
+                            ui.label(&position.kind);
+                            ui.label(position.issuer_or_asset());
+                            ui.label(&position.asset);
+                            ui.label(real(position.value));
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
+    }
+
+    fn issuer_table(&self, ui: &mut egui::Ui) {
+        let issuers = &self.credit.issuers;
+        ui.push_id("credit_issuers", |ui| {
+            TableBuilder::new(ui)
+                .column(Column::remainder().at_least(150.0).clip(true))
+                .column(Column::auto().at_least(120.0))
+                .column(Column::auto().at_least(120.0))
+                .column(Column::auto().at_least(70.0))
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .striped(true)
+                .vscroll(false)
+                .header(20.0, |mut header| {
+                    for title in ["", "CPF/CNPJ", "Valor", "% Patrim. Líq"] {
+                        header.col(|ui| {
+                            ui.label(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(20.0, issuers.len(), |mut row| {
+                        let issuer = &issuers[row.index()];
+                        row.col(|ui| {
+                            ui.label(&issuer.name);
+                            if issuer.related_party {
+                                ui.weak("(ligado)");
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(issuer.document.as_deref().unwrap_or("-"));
+                        });
+                        row.col(|ui| {
+                            ui.label(real(issuer.value));
+                        });
+                        row.col(|ui| {
+                            let text = format!("{:.2}%", issuer.share);
+                            match self.limits.classify(issuer.share) {
+                                Concentration::Normal => ui.label(text),
+                                Concentration::Warning => {
+                                    ui.colored_label(Color32::from_rgb(230, 160, 0), text)
+                                }
+                                Concentration::Exceeded => ui.colored_label(Color32::RED, text),
+                            };
+                        });
+                    });
+                });
+        });
+    }
+
+    fn maturity_grid(&self, ui: &mut egui::Ui) {
+        Grid::new(ui.id().with("credit_maturities"))
+            .num_columns(3)
+            .striped(true)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                for (label, value, share) in &self.credit.maturities {
+                    ui.label(*label);
+                    ui.label(real(*value));
+                    ui.label(format!("{:.2}%", share));
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+fn real(value: f64) -> String {
+    util::to_real(value)
+        .map(|v| v.format())
+        .unwrap_or_else(|_| "-".to_string())
+}
@@ -0,0 +1,211 @@
+use crate::{
+    analysis::portfolio::{self, Change, Evolution, Grouping, Position, PositionChange},
+    ui::{charts, loading},
+    util,
+};
+use chrono::NaiveDate;
+use egui::{Color32, ComboBox, Vec2};
+use egui_extras::{Column, TableBuilder};
+use polars::frame::DataFrame;
+
+/// Quantidades de meses disponíveis na evolução.
+pub const PERIODS: [u32; 3] = [6, 12, 24];
+
+/// Grupos exibidos individualmente no gráfico; o restante vira "Outros".
+const TOP_GROUPS: usize = 8;
+
+/// Evolução da carteira ao longo de vários meses da CDA.
+pub struct EvolutionUI {
+    pub months: u32,
+    pub grouping: Grouping,
+    pub positions: Vec<Position>,
+    pub evolution: Evolution,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub changes: Vec<PositionChange>,
+    /// Mês final e quantidade de meses da consulta em andamento ou já recebida.
+    pub request: Option<(NaiveDate, u32)>,
+    pub loading: bool,
+}
+
+impl Default for EvolutionUI {
+    fn default() -> Self {
+        EvolutionUI {
+            months: PERIODS[0],
+            grouping: Grouping::Kind,
+            positions: Vec::new(),
+            evolution: Evolution::default(),
+            from: None,
+            to: None,
+            changes: Vec::new(),
+            request: None,
+            loading: false,
+        }
+    }
+}
+
+impl EvolutionUI {
+    /// Recebe as carteiras mensais; respostas de consultas antigas são ignoradas.
+    pub fn set_portfolios(&mut self, request: (NaiveDate, u32), frames: &[DataFrame]) {
+        if self.request != Some(request) {
+            return;
+        }
+        self.positions = frames.iter().flat_map(Position::from_dataframe).collect();
+        self.evolution = Evolution::compute(&self.positions, self.grouping, TOP_GROUPS);
+        self.from = self.evolution.months.first().copied();
+        self.to = self.evolution.months.last().copied();
+        self.update_changes();
+        self.loading = false;
+    }
+
+    fn update_changes(&mut self) {
+        self.changes = match (self.from, self.to) {
+            (Some(from), Some(to)) => portfolio::diff(&self.positions, from, to),
+            _ => Vec::new(),
+        };
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.weak("Meses");
+            for months in PERIODS {
+                ui.selectable_value(&mut self.months, months, months.to_string());
+            }
+            ui.separator();
+            ui.weak("Agrupar por");
+            for grouping in Grouping::ALL {
+                if ui
+                    .selectable_value(&mut self.grouping, grouping, grouping.label())
+                    .clicked()
+                {
+                    self.evolution = Evolution::compute(&self.positions, grouping, TOP_GROUPS);
+                }
+            }
+        });
+        ui.add_space(5.0);
+
+        if self.loading {
+            loading::show(ui);
+            return;
+        }
+        if self.evolution.months.len() < 2 {
+            ui.label("Carteiras insuficientes para exibir a evolução.");
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let colors = util::generate_colors(self.evolution.groups.len());
+            ui.allocate_ui(Vec2::new(ui.available_width(), 300.0), |ui| {
+                charts::allocation::stacked_area(
+                    "plot::funds::allocation",
+                    &self.evolution.months,
+                    &self.evolution.groups,
+                    &colors,
+                    ui,
+                );
+            });
+            ui.separator();
+            self.show_changes(ui);
+        });
+    }
+
+    fn show_changes(&mut self, ui: &mut egui::Ui) {
+        let months = self.evolution.months.clone();
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.weak("Alterações de");
+            changed |= month_combobox(ui, "evolution_from", &mut self.from, &months);
+            ui.weak("para");
+            changed |= month_combobox(ui, "evolution_to", &mut self.to, &months);
+        });
+        if changed {
+            self.update_changes();
+        }
+        ui.add_space(5.0);
+
+        if self.changes.is_empty() {
+            ui.label("Nenhuma alteração entre os meses selecionados.");
+            return;
+        }
+
+        let real = |value: f64| {
+            util::to_real(value)
+                .map(|v| v.format())
+                .unwrap_or_else(|_| "-".to_string())
+        };
+        ui.push_id("evolution_changes", |ui| {
+            TableBuilder::new(ui)
+                .column(Column::auto().at_least(150.0).resizable(true).clip(true))
+                .column(Column::auto().at_least(300.0).resizable(true).clip(true))
+                .column(Column::auto().at_least(120.0))
+                .column(Column::auto().at_least(120.0))
+                .column(Column::auto().at_least(80.0))
+                .column(Column::remainder())
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .striped(true)
+                .resizable(false)
+                .header(20.0, |mut header| {
+                    for title in ["Aplicação", "Ativo", "Antes", "Depois", "Variação", ""] {
+                        header.col(|ui| {
+                            ui.label(title);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(20.0, self.changes.len(), |mut row| {
+                        let change = &self.changes[row.index()];
+                        let color = match change.change {
+                            Change::Added => Color32::DARK_GREEN,
+                            Change::Removed => Color32::RED,
+                            Change::Resized => Color32::GRAY,
+                        };
+                        row.col(|ui| {
+                            ui.label(&change.kind);
+                        });
+                        row.col(|ui| {
+                            ui.label(&change.asset);
+                        });
+                        row.col(|ui| {
+                            ui.label(real(change.before));
+                        });
+                        row.col(|ui| {
+                            ui.label(real(change.after));
+                        });
+                        row.col(|ui| {
+                            if change.before > 0.0 && change.after > 0.0 {
+                                let variation = (change.after / change.before - 1.0) * 100.0;
+                                ui.label(format!("{:+.2}%", variation));
+                            } else {
+                                ui.label("-");
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.colored_label(color, change.change.label());
+                        });
+                    });
+                });
+        });
+    }
+}
+
+fn month_combobox(
+    ui: &mut egui::Ui,
+    id: &str,
+    selected: &mut Option<NaiveDate>,
+    months: &[NaiveDate],
+) -> bool {
+    let text = selected
+        .map(|d| d.format("%m/%Y").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let mut changed = false;
+    ComboBox::from_id_source(id)
+        .selected_text(text)
+        .show_ui(ui, |ui| {
+            for month in months {
+                changed |= ui
+                    .selectable_value(selected, Some(*month), month.format("%m/%Y").to_string())
+                    .changed();
+            }
+        });
+    changed
+}
@@ -0,0 +1,218 @@
+use crate::{
+    analysis::flows::Flows,
+    message,
+    ui::{
+        charts::{
+            self,
+            compare::{self, SeriesLine},
+        },
+        loading,
+    },
+    util,
+};
+use chrono::{Months, NaiveDate};
+use egui::{Color32, Grid, Layout, Vec2};
+use polars::frame::DataFrame;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Períodos disponíveis, em meses até hoje.
+const PERIODS: [(u32, &str); 4] = [(60, "5A"), (24, "2A"), (12, "1A"), (6, "6M")];
+
+const CHART_HEIGHT: f32 = 220.0;
+
+pub struct FlowsUI {
+    pub cnpj: String,
+    pub sender: Option<UnboundedSender<message::Message>>,
+    pub loading: bool,
+    /// Período consultado, em meses até hoje.
+    pub months: u32,
+    pub flows: Flows,
+    /// Soma a captação líquida por mês em vez de exibir cada dia.
+    pub monthly: bool,
+}
+
+impl Default for FlowsUI {
+    fn default() -> Self {
+        FlowsUI {
+            cnpj: String::from(""),
+            sender: None,
+            loading: false,
+            months: 12,
+            flows: Flows::default(),
+            monthly: true,
+        }
+    }
+}
+
+impl FlowsUI {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                    ui.horizontal_centered(|ui| {
+                        ui.heading(egui::RichText::new("Patrimônio e Cotistas").size(16.0));
+                    });
+                });
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add_enabled_ui(!self.loading, |ui| {
+                        for (months, label) in PERIODS {
+                            if ui
+                                .selectable_value(&mut self.months, months, label)
+                                .clicked()
+                            {
+                                self.send_flows_message();
+                            }
+                        }
+                    });
+                });
+            });
+            ui.separator();
+            ui.add_space(5.0);
+
+            if self.loading {
+                loading::show(ui);
+            } else if self.flows.is_empty() {
+                ui.label("-");
+            } else {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    self.show_summary(ui);
+                    ui.separator();
+                    self.show_charts(ui);
+                });
+            }
+        });
+    }
+
+    pub fn send_flows_message(&mut self) {
+        let end_date = chrono::offset::Utc::now().date_naive();
+        let start_date = end_date
+            .checked_sub_months(Months::new(self.months))
+            .unwrap_or(end_date);
+        let _ = self.sender.clone().unwrap().send(message::Message::Flows(
+            self.cnpj.to_string(),
+            start_date,
+            end_date,
+        ));
+        self.loading = true;
+    }
+
+    pub fn set_flows(&mut self, df: &DataFrame) {
+        self.flows = Flows::from_dataframe(df);
+        self.loading = false;
+    }
+
+    fn show_summary(&self, ui: &mut egui::Ui) {
+        let real = |value: Option<f64>| {
+            value
+                .and_then(|v| util::to_real(v).ok())
+                .map(|v| v.format())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let change = |series| {
+            Flows::first_last(series)
+                .filter(|(first, _)| *first > 0.0)
+                .map(|(first, last)| format!("{:+.2}%", (last / first - 1.0) * 100.0))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let net_worth = Flows::first_last(&self.flows.net_worth);
+        let shareholders = Flows::first_last(&self.flows.shareholders);
+
+        let rows = [
+            ("Patrimônio líquido", real(net_worth.map(|(_, last)| last))),
+            ("Variação do patrimônio", change(&self.flows.net_worth)),
+            (
+                "Captação líquida no período",
+                real(Some(self.flows.total_net_inflow())),
+            ),
+            (
+                "Cotistas",
+                shareholders
+                    .map(|(first, last)| format!("{} ({:+})", last, last - first))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        ];
+
+        Grid::new(ui.id().with("flows_summary"))
+            .num_columns(4)
+            .striped(true)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                for (i, (label, value)) in rows.iter().enumerate() {
+                    ui.weak(*label);
+                    ui.label(value);
+                    if i % 2 == 1 {
+                        ui.end_row();
+                    }
+                }
+            });
+    }
+
+    fn show_charts(&mut self, ui: &mut egui::Ui) {
+        let size = Vec2::new(ui.available_width(), CHART_HEIGHT);
+        let millions = |series: &[(NaiveDate, f64)]| -> Vec<(NaiveDate, f64)> {
+            series
+                .iter()
+                .map(|(date, value)| (*date, value / 1_000_000.0))
+                .collect()
+        };
+
+        ui.weak("Patrimônio líquido (R$)");
+        ui.allocate_ui(size, |ui| {
+            let line = SeriesLine {
+                name: "Patrimônio líquido".to_string(),
+                color: Color32::from_rgb(0, 255, 0),
+                points: millions(&self.flows.net_worth),
+            };
+            compare::chart_with_unit("plot::funds::net_worth", &[line], " mi", ui);
+        });
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.weak("Captação líquida (R$)");
+            ui.selectable_value(&mut self.monthly, true, "Mensal");
+            ui.selectable_value(&mut self.monthly, false, "Diária");
+        });
+        ui.allocate_ui(size, |ui| {
+            // Em R$ milhões; entradas em verde e saídas em vermelho
+            let (points, days) = if self.monthly {
+                let points: Vec<(NaiveDate, f64)> = self
+                    .flows
+                    .monthly_net_inflows
+                    .iter()
+                    .filter_map(|((year, month), value)| {
+                        NaiveDate::from_ymd_opt(*year, *month, 15).map(|date| (date, *value))
+                    })
+                    .collect();
+                (millions(&points), 30.0)
+            } else {
+                (millions(&self.flows.net_inflows), 1.0)
+            };
+            charts::bars::by_date(
+                "plot::funds::net_inflows",
+                "Captação líquida",
+                &points,
+                days,
+                |value| {
+                    if value >= 0.0 {
+                        Color32::from_rgb(0, 160, 0)
+                    } else {
+                        Color32::from_rgb(200, 0, 0)
+                    }
+                },
+                " mi",
+                ui,
+            );
+        });
+
+        ui.add_space(10.0);
+        ui.weak("Número de cotistas");
+        ui.allocate_ui(size, |ui| {
+            let line = SeriesLine {
+                name: "Cotistas".to_string(),
+                color: Color32::from_rgb(0, 160, 255),
+                points: self.flows.shareholders.clone(),
+            };
+            compare::chart_with_unit("plot::funds::shareholders", &[line], "", ui);
+        });
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{analysis::look_through::LookThrough, ui::loading, util};
+use egui::Grid;
+use egui_extras::{Column, TableBuilder};
+
+/// Emissores exibidos na tabela consolidada.
+const TOP_ISSUERS: usize = 50;
+
+/// Exposição consolidada da carteira, abrindo as cotas de fundos investidos.
+#[derive(Default)]
+pub struct LookThroughUI {
+    pub look: LookThrough,
+    by_kind: Vec<(String, f64)>,
+    by_issuer: Vec<(String, f64)>,
+    /// Ano e mês da consulta em andamento ou já recebida.
+    pub request: Option<(String, String)>,
+    pub loading: bool,
+}
+
+impl LookThroughUI {
+    /// Recebe o look-through; respostas de consultas antigas são ignoradas.
+    pub fn set_look_through(&mut self, request: (String, String), look: LookThrough) {
+        if self.request != Some(request) {
+            return;
+        }
+        self.by_kind = look.by_kind();
+        self.by_issuer = look.by_issuer();
+        self.by_issuer.truncate(TOP_ISSUERS);
+        self.look = look;
+        self.loading = false;
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        if self.loading {
+            loading::show(ui);
+            return;
+        }
+        if self.look.exposures.is_empty() {
+            ui.label("-");
+            return;
+        }
+
+        let net_worth = self.look.net_worth;
+        ui.horizontal(|ui| {
+            ui.weak("Fundos investidos abertos");
+            ui.label(self.look.resolved.len().to_string());
+            ui.separator();
+            ui.weak("Não abertos");
+            ui.label(self.look.skipped.len().to_string());
+        });
+        ui.add_space(5.0);
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.columns(2, |columns| {
+                columns[0].strong("Por tipo de aplicação");
+                exposure_table(
+                    &mut columns[0],
+                    "look_through_kind",
+                    &self.by_kind,
+                    net_worth,
+                );
+                columns[1].strong("Por emissor");
+                exposure_table(
+                    &mut columns[1],
+                    "look_through_issuer",
+                    &self.by_issuer,
+                    net_worth,
+                );
+            });
+
+            if !self.look.skipped.is_empty() {
+                ui.separator();
+                ui.strong("Fundos investidos não abertos");
+                Grid::new(ui.id().with("look_through_skipped"))
+                    .num_columns(4)
+                    .striped(true)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        for skipped in &self.look.skipped {
+                            ui.label(&skipped.cnpj);
+                            ui.label(&skipped.name);
+                            ui.label(real(skipped.value));
+                            ui.weak(skipped.reason.label());
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
+    }
+}
+
+fn exposure_table(ui: &mut egui::Ui, id: &str, rows: &[(String, f64)], net_worth: f64) {
+    ui.push_id(id, |ui| {
+        TableBuilder::new(ui)
+            .column(Column::remainder().at_least(150.0).clip(true))
+            .column(Column::auto().at_least(120.0))
+            .column(Column::auto().at_least(70.0))
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .striped(true)
+            .vscroll(false)
+            .header(20.0, |mut header| {
+                for title in ["", "Valor", "% Patrim. Líq"] {
+                    header.col(|ui| {
+                        ui.label(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(20.0, rows.len(), |mut row| {
+                    let (name, value) = &rows[row.index()];
+                    row.col(|ui| {
+                        ui.label(name);
+                    });
+                    row.col(|ui| {
+                        ui.label(real(*value));
+                    });
+                    row.col(|ui| {
+                        if net_worth > 0.0 {
+                            ui.label(format!("{:.2}%", value / net_worth * 100.0));
+                        } else {
+                            ui.label("-");
+                        }
+                    });
+                });
+            });
+    });
+}
+
+fn real(value: f64) -> String {
+    util::to_real(value)
+        .map(|v| v.format())
+        .unwrap_or_else(|_| "-".to_string())
+}
@@ -1,3 +1,8 @@
+pub mod classes;
+pub mod credit;
 pub mod detail;
+pub mod evolution;
+pub mod flows;
+pub mod look_through;
 pub mod portfolio;
 pub mod profit;
@@ -1,6 +1,7 @@
-use crate::{message, ui::loading, util};
+use super::{credit::CreditUI, evolution::EvolutionUI, look_through::LookThroughUI};
+use crate::{analysis::portfolio::Position, message, ui::loading, util};
 use chrono::{Datelike, Duration, NaiveDate};
-use egui::{epaint::Hsva, Color32, ComboBox, Layout, Sense, TopBottomPanel, Ui};
+use egui::{Color32, ComboBox, Layout, Sense, TopBottomPanel, Ui};
 use egui_extras::{Column, TableBuilder};
 use polars::{
     frame::DataFrame,
@@ -11,19 +12,31 @@ use polars::{
 use std::collections::HashSet;
 use tokio::sync::mpsc::UnboundedSender;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortfolioView {
+    Composition,
+    Evolution,
+    LookThrough,
+    Credit,
+}
+
 pub struct PortfolioUI {
     pub filter_date: String,
     pub filter_year: String,
     pub filter_month: String,
     pub tp_aplic_selected: std::collections::HashSet<usize>,
 
-    pub start_date: String,
+    pub start_date: Option<NaiveDate>,
     pub pl: DataFrame,
     pub assets: DataFrame,
     pub top_assets: DataFrame,
     pub cnpj: String,
     pub sender: Option<UnboundedSender<message::Message>>,
     pub loading: bool,
+    pub view: PortfolioView,
+    pub evolution: EvolutionUI,
+    pub look_through: LookThroughUI,
+    pub credit: CreditUI,
 }
 
 impl Default for PortfolioUI {
@@ -40,9 +53,13 @@ impl Default for PortfolioUI {
             filter_year: now.year().to_string(),
             filter_month: format!("{:02}", now.month()),
             tp_aplic_selected: Default::default(),
-            start_date: "".to_string(),
+            start_date: None,
             filter_date: now_str,
             loading: false,
+            view: PortfolioView::Composition,
+            evolution: EvolutionUI::default(),
+            look_through: LookThroughUI::default(),
+            credit: CreditUI::default(),
         }
     }
 }
@@ -60,17 +77,49 @@ impl PortfolioUI {
                     ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
                         ui.horizontal(|ui| {
                             self.create_date_combobox(ui);
+                            ui.separator();
+                            ui.selectable_value(&mut self.view, PortfolioView::Credit, "Crédito");
+                            ui.selectable_value(
+                                &mut self.view,
+                                PortfolioView::LookThrough,
+                                "Look-through",
+                            );
+                            ui.selectable_value(
+                                &mut self.view,
+                                PortfolioView::Evolution,
+                                "Evolução",
+                            );
+                            ui.selectable_value(
+                                &mut self.view,
+                                PortfolioView::Composition,
+                                "Composição",
+                            );
                         });
                     });
                 });
                 ui.separator();
 
-                if self.loading {
-                    ui.vertical_centered(|ui| {
-                        loading::show(ui);
-                    });
-                } else {
-                    self.show_assets_panel(ui);
+                match self.view {
+                    PortfolioView::Composition if self.loading => {
+                        ui.vertical_centered(|ui| {
+                            loading::show(ui);
+                        });
+                    }
+                    PortfolioView::Composition => self.show_assets_panel(ui),
+                    PortfolioView::Evolution => {
+                        self.send_evolution_message();
+                        self.evolution.show(ui);
+                    }
+                    PortfolioView::LookThrough => {
+                        self.send_look_through_message();
+                        self.look_through.show(ui);
+                    }
+                    PortfolioView::Credit if self.loading => {
+                        ui.vertical_centered(|ui| {
+                            loading::show(ui);
+                        });
+                    }
+                    PortfolioView::Credit => self.credit.show(ui),
                 }
             });
         });
@@ -106,8 +155,10 @@ impl PortfolioUI {
     }
 
     fn create_date_combobox(&mut self, ui: &mut egui::Ui) {
-        //2022-09-21
-        let end_date = NaiveDate::parse_from_str(&self.start_date, "%Y-%m-%d").unwrap();
+        // Sem data de início da situação, exibe apenas os últimos 12 meses
+        let end_date = self
+            .start_date
+            .unwrap_or_else(|| chrono::Local::now().naive_local().date() - Duration::days(365));
         let available_dates = self.generate_available_dates(end_date);
 
         ComboBox::from_label("Selecione a data")
@@ -146,6 +197,62 @@ impl PortfolioUI {
         self.loading = true;
     }
 
+    /// Recalcula a exposição de crédito a partir das aplicações e do patrimônio do mês.
+    pub fn update_credit(&mut self) {
+        let net_worth = self
+            .pl
+            .column("VL_PATRIM_LIQ")
+            .ok()
+            .and_then(|col| col.f64().ok())
+            .and_then(|values| values.into_iter().next().flatten())
+            .unwrap_or(0.0);
+        self.credit
+            .set_positions(&Position::from_dataframe(&self.assets), net_worth);
+    }
+
+    /// Pede as carteiras da evolução quando o mês final ou a quantidade de meses mudam.
+    fn send_evolution_message(&mut self) {
+        let month = self.filter_month.parse::<u32>().unwrap_or(1);
+        let year = self.filter_year.parse::<i32>().unwrap_or(1970);
+        let Some(end_date) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return;
+        };
+        let request = (end_date, self.evolution.months);
+        if self.evolution.request == Some(request) {
+            return;
+        }
+        self.evolution.request = Some(request);
+        self.evolution.loading = true;
+        let _ = self
+            .sender
+            .clone()
+            .unwrap()
+            .send(message::Message::PortfolioEvolution(
+                self.cnpj.to_string(),
+                end_date,
+                self.evolution.months,
+            ));
+    }
+
+    /// Pede o look-through quando o mês selecionado muda.
+    fn send_look_through_message(&mut self) {
+        let request = (self.filter_year.clone(), self.filter_month.clone());
+        if self.look_through.request.as_ref() == Some(&request) {
+            return;
+        }
+        self.look_through.request = Some(request);
+        self.look_through.loading = true;
+        let _ = self
+            .sender
+            .clone()
+            .unwrap()
+            .send(message::Message::LookThrough(
+                self.cnpj.to_string(),
+                self.filter_year.clone(),
+                self.filter_month.clone(),
+            ));
+    }
+
     pub fn show_assets_panel(&mut self, ui: &mut Ui) {
         egui::SidePanel::left(ui.id().with("left_assets_panel"))
             .resizable(true)
@@ -155,7 +262,7 @@ impl PortfolioUI {
                 ui.add_space(10.0);
                 let nr_rows = self.top_assets.height();
                 let cols: Vec<&str> = vec!["TP_APLIC", "VL_MERC_POS_FINAL", "VL_PORCENTAGEM_PL"];
-                let colors = generate_colors(self.top_assets.height());
+                let colors = util::generate_colors(self.top_assets.height());
 
                 ui.push_id("top_assets", |ui| {
                     TopBottomPanel::top(ui.id().with("bottom_pl_panel")).show_inside(
@@ -169,9 +276,8 @@ impl PortfolioUI {
                                         self.pl
                                             .column("VL_PATRIM_LIQ")
                                             .ok()
-                                            .and_then(|col| col.get(0).ok())
-                                            .and_then(|val| val.get_str().map(|s| s.to_string()))
-                                            .and_then(|value_str| value_str.parse::<f64>().ok())
+                                            .and_then(|col| col.f64().ok())
+                                            .and_then(|values| values.into_iter().next().flatten())
                                             .and_then(|parsed_value| {
                                                 util::to_real(parsed_value).ok()
                                             })
@@ -392,18 +498,15 @@ impl PortfolioUI {
                                                                 format!("{}%", a),
                                                             );
                                                         }
-                                                    } else if let Some(value_str) = value.get_str()
+                                                    } else if col_name.contains("VL_MERC_POS_FINAL")
                                                     {
-                                                        if col_name.contains("VL_MERC_POS_FINAL") {
-                                                            let a = value_str
-                                                                .to_string()
-                                                                .parse::<f64>()
-                                                                .unwrap();
+                                                        if let Ok(a) = value.try_extract::<f64>() {
                                                             let r = util::to_real(a).unwrap();
                                                             ui.label(r.format());
-                                                        } else {
-                                                            ui.label(value_str);
                                                         }
+                                                    } else if let Some(value_str) = value.get_str()
+                                                    {
+                                                        ui.label(value_str);
                                                     }
                                                 }
                                             }
@@ -454,17 +557,6 @@ fn circle(_name: String, color: egui::Color32, ui: &mut Ui) {
     ui.painter().circle_filled(rect.center(), r, color);
 }
 
-fn generate_colors(n: usize) -> Vec<egui::Color32> {
-    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0; // 0.61803398875
-
-    (0..n)
-        .map(|i| {
-            let h = i as f32 * golden_ratio;
-            egui::Color32::from(Hsva::new(h.fract(), 0.85, 0.5, 1.0))
-        })
-        .collect()
-}
-
 fn month_name_to_i32(month_name: &str) -> i32 {
     match month_name {
         "Janeiro" => 1,
@@ -1,14 +1,28 @@
 use crate::{
+    analysis::{
+        self,
+        metrics::{self, Metrics},
+        returns::{ReturnsTable, MONTHS},
+        rolling::{self, Rolling},
+    },
     message,
+    provider::indices::benchmark::{Benchmark, BenchmarkSeries},
     ui::{
-        charts::{self, profit::Indice},
+        charts::{
+            self,
+            compare::SeriesLine,
+            profit::{Indice, Mode},
+        },
         loading,
     },
+    util,
 };
 use chrono::NaiveDate;
-use egui::{Align2, Color32, Frame, Layout, Vec2, Widget};
+use egui::{Align2, Color32, Frame, Grid, Layout, Vec2, Widget};
 use egui_extras::DatePickerButton;
+use egui_toast::{Toast, ToastKind, ToastOptions};
 use polars::frame::DataFrame;
+use std::collections::{HashMap, HashSet};
 use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Debug, PartialEq)]
@@ -25,8 +39,27 @@ pub struct ProfitUI {
     pub profit_filter_end_date: NaiveDate,
     pub open_profit_filter: bool,
     pub profit: DataFrame,
+    /// Série diária do CDI, usada nos indicadores.
     pub cdi: DataFrame,
-    pub ibov: DataFrame,
+    /// Índices de referência configurados; só os exibidos são consultados.
+    pub available_benchmarks: Vec<Benchmark>,
+    /// Séries dos índices consultados no período.
+    pub benchmarks: Vec<BenchmarkSeries>,
+    /// Índices exibidos no gráfico, pelo nome; mantido entre consultas.
+    pub visible_benchmarks: HashMap<String, bool>,
+    /// Índices exibidos depois da consulta, ainda sendo buscados.
+    pub pending_benchmarks: HashSet<String>,
+    pub metrics: Option<Metrics>,
+    pub returns: ReturnsTable,
+    /// Período da última consulta de rentabilidade.
+    pub period: (NaiveDate, NaiveDate),
+    pub chart_mode: Mode,
+    /// Janela dos indicadores móveis, em meses.
+    pub rolling_months: u32,
+    /// Indicadores móveis recebidos; `Some(None)` quando a consulta falhou.
+    pub rolling: Option<Option<Rolling>>,
+    /// Período e janela da consulta de histórico em andamento ou já recebida.
+    pub rolling_request: Option<(NaiveDate, NaiveDate, u32)>,
     pub cnpj: String,
     pub loading: bool,
     pub sender: Option<UnboundedSender<message::Message>>,
@@ -49,7 +82,17 @@ impl Default for ProfitUI {
             profit_filter_end_date: now,
             open_profit_filter: false,
             loading: false,
-            ibov: DataFrame::empty(),
+            available_benchmarks: Vec::new(),
+            benchmarks: Vec::new(),
+            visible_benchmarks: HashMap::new(),
+            pending_benchmarks: HashSet::new(),
+            metrics: None,
+            returns: ReturnsTable::default(),
+            period: (start_date, now),
+            chart_mode: Mode::Cumulative,
+            rolling_months: rolling::WINDOWS[0],
+            rolling: None,
+            rolling_request: None,
         }
     }
 }
@@ -69,6 +112,7 @@ impl ProfitUI {
                         ui.horizontal(|ui| {
                             self.create_filter_buttons(ui, &cnpj);
                             self.show_profit_filter_window(ui, &cnpj);
+                            self.show_benchmark_menu(ui);
                         });
                     });
                 });
@@ -96,32 +140,354 @@ impl ProfitUI {
                 });
                 ui.separator();
 
+                ui.collapsing("Indicadores", |ui| {
+                    self.show_metrics(ui);
+                });
+                ui.collapsing("Rentabilidade Mensal", |ui| {
+                    self.show_returns_table(ui);
+                });
+                ui.separator();
+
                 ui.add_space(5.0);
 
-                let red = Color32::from_rgb(255, 0, 0); // Vermelho
+                let indices = self
+                    .benchmarks
+                    .iter()
+                    .filter(|b| self.is_benchmark_visible(&b.benchmark.name))
+                    .map(|b| Indice {
+                        name: b.benchmark.name.clone(),
+                        color: Color32::from_hex(&b.benchmark.color).unwrap_or(Color32::GRAY),
+                        dataframe: b.cumulative.clone(),
+                    })
+                    .collect();
 
+                self.show_chart_modes(ui);
                 Frame::none().inner_margin(5.0).show(ui, |ui| {
-                    charts::profit::chart(
-                        &self.profit,
-                        vec![
-                            Indice {
-                                name: "CDI".to_string(),
-                                color: red,
-                                dataframe: self.cdi.clone(),
-                            },
-                            Indice {
-                                name: "IBOV".to_string(),
-                                color: Color32::YELLOW,
-                                dataframe: self.ibov.clone(),
-                            },
-                        ],
-                        ui,
-                    );
+                    if self.chart_mode.is_rolling() {
+                        self.show_rolling(ui);
+                    } else if self.chart_mode == Mode::Drawdown {
+                        self.show_drawdown(ui);
+                    } else {
+                        charts::profit::chart(&self.profit, indices, ui);
+                    }
                 });
             }
         });
     }
 
+    fn show_chart_modes(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for mode in Mode::ALL {
+                ui.selectable_value(&mut self.chart_mode, mode, mode.label());
+            }
+            if self.chart_mode.is_rolling() {
+                ui.separator();
+                ui.weak("Janela");
+                for months in rolling::WINDOWS {
+                    ui.selectable_value(&mut self.rolling_months, months, format!("{}M", months));
+                }
+            }
+        });
+    }
+
+    /// Drawdown do fundo, a partir do `CUMULATIVE_PRODUCT`, e dos índices exibidos.
+    fn show_drawdown(&self, ui: &mut egui::Ui) {
+        let mut series = vec![SeriesLine {
+            name: "Fundo".to_string(),
+            color: Color32::from_rgb(0, 255, 0),
+            points: metrics::underwater(&analysis::cumulative_product(&self.profit)),
+        }];
+        series.extend(
+            self.benchmarks
+                .iter()
+                .filter(|b| self.is_benchmark_visible(&b.benchmark.name))
+                .map(|b| SeriesLine {
+                    name: b.benchmark.name.clone(),
+                    color: Color32::from_hex(&b.benchmark.color).unwrap_or(Color32::GRAY),
+                    points: metrics::underwater(&analysis::benchmark_growth(&b.cumulative)),
+                }),
+        );
+        let worst = self.metrics.as_ref().and_then(|m| m.max_drawdown);
+        charts::profit::drawdown(&series, worst, ui);
+    }
+
+    /// Pede o histórico da janela móvel quando o período ou a janela mudam.
+    fn show_rolling(&mut self, ui: &mut egui::Ui) {
+        let request = (self.period.0, self.period.1, self.rolling_months);
+        if self.rolling_request != Some(request) {
+            self.rolling_request = Some(request);
+            self.rolling = None;
+            if let Some(sender) = &self.sender {
+                let _ = sender.send(message::Message::Rolling(
+                    self.cnpj.clone(),
+                    request.0,
+                    request.1,
+                    request.2,
+                ));
+            }
+        }
+
+        match &self.rolling {
+            None => loading::show(ui),
+            Some(None) => {
+                ui.label("Não foi possível obter o histórico da janela móvel.");
+            }
+            Some(Some(rolling)) if rolling.returns.is_empty() => {
+                ui.label(format!(
+                    "Histórico insuficiente para uma janela de {} meses.",
+                    rolling.months
+                ));
+            }
+            Some(Some(rolling)) => charts::profit::rolling(rolling, self.chart_mode, ui),
+        }
+    }
+
+    /// Recebe o histórico da janela móvel (cotas e CDI), ou `None` se a consulta falhou;
+    /// respostas de consultas antigas são ignoradas.
+    pub fn set_rolling(
+        &mut self,
+        request: (NaiveDate, NaiveDate, u32),
+        frames: Option<&(DataFrame, DataFrame)>,
+    ) {
+        if self.rolling_request != Some(request) {
+            return;
+        }
+        let Some((profit, cdi)) = frames else {
+            self.rolling = Some(None);
+            return;
+        };
+        let (start_date, _, months) = request;
+        let quotas = analysis::quotas(profit);
+        let cdi = analysis::cdi_rates(cdi);
+        let mut rolling = Rolling::compute(&quotas, &cdi, months);
+        // Somente as janelas que terminam dentro do período selecionado
+        for series in [
+            &mut rolling.returns,
+            &mut rolling.cdi_returns,
+            &mut rolling.volatility,
+            &mut rolling.sharpe,
+        ] {
+            series.retain(|(date, _)| *date >= start_date);
+        }
+        self.rolling = Some(Some(rolling));
+    }
+
+    /// Substitui os índices carregados pelos da nova consulta de rentabilidade.
+    pub fn set_benchmarks(&mut self, benchmarks: Vec<BenchmarkSeries>) {
+        self.benchmarks = benchmarks;
+        self.pending_benchmarks.clear();
+    }
+
+    /// Acrescenta o índice buscado ao ser exibido, se ainda for do período consultado.
+    pub fn add_benchmark(
+        &mut self,
+        period: (NaiveDate, NaiveDate),
+        name: &str,
+        series: Option<BenchmarkSeries>,
+    ) {
+        if !self.pending_benchmarks.remove(name) || period != self.period {
+            return;
+        }
+        match series {
+            Some(series) => {
+                self.benchmarks.retain(|b| b.benchmark.name != name);
+                self.benchmarks.push(series);
+            }
+            // Falhou: volta a ficar oculto para poder ser pedido de novo
+            None => {
+                self.visible_benchmarks.insert(name.to_string(), false);
+            }
+        }
+    }
+
+    fn is_benchmark_visible(&self, name: &str) -> bool {
+        self.visible_benchmarks.get(name).copied().unwrap_or(false)
+    }
+
+    fn show_benchmark_menu(&mut self, ui: &mut egui::Ui) {
+        ui.add_enabled_ui(
+            !self.loading && !self.available_benchmarks.is_empty(),
+            |ui| {
+                ui.menu_button(egui_phosphor::regular::CHART_LINE.to_string(), |ui| {
+                    ui.weak("Índices de referência");
+                    for b in &self.available_benchmarks {
+                        let visible = self
+                            .visible_benchmarks
+                            .entry(b.name.clone())
+                            .or_insert(b.visible);
+                        let loaded = self.benchmarks.iter().any(|s| s.benchmark.name == b.name);
+                        let pending = self.pending_benchmarks.contains(&b.name);
+                        let label = if pending {
+                            format!("{} (carregando)", b.name)
+                        } else {
+                            b.name.clone()
+                        };
+                        if ui.checkbox(visible, label).changed() && *visible && !loaded && !pending
+                        {
+                            if let Some(sender) = &self.sender {
+                                let _ = sender.send(message::Message::Benchmark(
+                                    self.cnpj.clone(),
+                                    self.period.0,
+                                    self.period.1,
+                                    b.clone(),
+                                ));
+                            }
+                            self.pending_benchmarks.insert(b.name.clone());
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Índices exibidos no gráfico");
+            },
+        );
+    }
+
+    /// Recalcula indicadores e tabela mensal a partir das cotas e do CDI carregados.
+    pub fn update_analysis(&mut self) {
+        let quotas = analysis::quotas(&self.profit);
+        let cdi = analysis::cdi_rates(&self.cdi);
+        self.metrics = Metrics::compute(&quotas, &cdi);
+        self.returns = ReturnsTable::compute(&quotas, &cdi);
+    }
+
+    fn show_metrics(&self, ui: &mut egui::Ui) {
+        let Some(metrics) = &self.metrics else {
+            ui.label("-");
+            return;
+        };
+
+        let percent = |value: Option<f64>| {
+            value
+                .map(|v| format!("{:.2}%", v * 100.0))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let ratio = |value: Option<f64>| {
+            value
+                .map(|v| format!("{:.2}", v))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let month = |value: Option<analysis::metrics::MonthReturn>| {
+            value
+                .map(|m| format!("{:02}/{} ({:.2}%)", m.month, m.year, m.value * 100.0))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let drawdown = metrics
+            .max_drawdown
+            .map(|dd| {
+                let recovery = dd
+                    .recovery
+                    .map(|d| d.format("%d/%m/%Y").to_string())
+                    .unwrap_or_else(|| "não recuperado".to_string());
+                format!(
+                    "{:.2}% ({} a {}, {})",
+                    dd.depth * 100.0,
+                    dd.start.format("%d/%m/%Y"),
+                    dd.trough.format("%d/%m/%Y"),
+                    recovery
+                )
+            })
+            .unwrap_or_else(|| "-".to_string());
+
+        let rows = [
+            ("Retorno no período", percent(Some(metrics.total_return))),
+            (
+                "Retorno anualizado",
+                percent(Some(metrics.annualized_return)),
+            ),
+            (
+                "Volatilidade anualizada",
+                percent(metrics.annualized_volatility),
+            ),
+            ("CDI no período", percent(Some(metrics.cdi_return))),
+            ("% do CDI", percent(metrics.percent_of_cdi)),
+            ("Sharpe", ratio(metrics.sharpe)),
+            ("Sortino", ratio(metrics.sortino)),
+            ("Meses acima do CDI", percent(metrics.hit_ratio)),
+            ("Melhor mês", month(metrics.best_month)),
+            ("Pior mês", month(metrics.worst_month)),
+            ("Drawdown máximo", drawdown),
+        ];
+
+        Grid::new(ui.id().with("profit_metrics"))
+            .num_columns(4)
+            .striped(true)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                for (i, (label, value)) in rows.iter().enumerate() {
+                    ui.weak(*label);
+                    ui.label(value);
+                    if i % 2 == 1 {
+                        ui.end_row();
+                    }
+                }
+            });
+    }
+
+    fn show_returns_table(&self, ui: &mut egui::Ui) {
+        if self.returns.is_empty() {
+            ui.label("-");
+            return;
+        }
+
+        let scale = self.returns.max_abs_month();
+        let strong = ui.visuals().strong_text_color();
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            Grid::new(ui.id().with("returns_table"))
+                .striped(false)
+                .spacing([2.0, 2.0])
+                .min_col_width(58.0)
+                .show(ui, |ui| {
+                    ui.strong("Ano");
+                    for month in MONTHS {
+                        ui.strong(month);
+                    }
+                    ui.strong("No ano");
+                    ui.strong("12 meses");
+                    ui.end_row();
+
+                    for row in &self.returns.years {
+                        ui.strong(row.year.to_string());
+                        for cell in &row.months {
+                            returns_cell(ui, *cell, scale, strong);
+                        }
+                        returns_cell(ui, row.ytd, 0.0, strong);
+                        returns_cell(ui, row.twelve_months, 0.0, strong);
+                        ui.end_row();
+                    }
+                });
+        });
+
+        ui.add_space(5.0);
+        if ui
+            .small_button(format!("{} Exportar CSV", egui_phosphor::regular::EXPORT))
+            .clicked()
+        {
+            self.export_returns_table();
+        }
+    }
+
+    /// Envia a tabela mensal para exportação em CSV.
+    fn export_returns_table(&self) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        match self.returns.to_dataframe() {
+            Ok(df) => {
+                let digits: String = self.cnpj.chars().filter(char::is_ascii_digit).collect();
+                let file_name = format!("rentabilidade_mensal_{}.csv", digits);
+                let _ = sender.send(message::Message::ExportCsv(file_name, df));
+            }
+            Err(err) => {
+                log::error!("Erro ao montar tabela mensal: {}", err);
+                util::toaster().add(Toast {
+                    kind: ToastKind::Error,
+                    text: "Erro ao exportar tabela mensal".into(),
+                    options: ToastOptions::default().duration_in_seconds(3.0),
+                });
+            }
+        }
+    }
+
     fn create_filter_buttons(&mut self, ui: &mut egui::Ui, cnpj: &str) {
         ui.add_enabled_ui(!self.loading, |ui| {
             if ui
@@ -187,11 +553,19 @@ impl ProfitUI {
         start_date: chrono::NaiveDate,
         end_date: chrono::NaiveDate,
     ) {
+        let benchmarks = self
+            .available_benchmarks
+            .iter()
+            .filter(|b| self.is_benchmark_visible(&b.name))
+            .cloned()
+            .collect();
         let _ = self.sender.clone().unwrap().send(message::Message::Profit(
             cnpj.to_string(),
             start_date,
             end_date,
+            benchmarks,
         ));
+        self.period = (start_date, end_date);
         self.loading = true;
     }
 
@@ -239,3 +613,43 @@ impl ProfitUI {
         self.open_profit_filter = open_profit && other;
     }
 }
+
+/// Célula da tabela mensal: retorno do fundo colorido pela intensidade e o CDI abaixo.
+/// Com `scale` igual a zero a célula não recebe cor de fundo.
+fn returns_cell(
+    ui: &mut egui::Ui,
+    cell: Option<analysis::returns::Cell>,
+    scale: f64,
+    text_color: Color32,
+) {
+    let Some(cell) = cell else {
+        ui.weak("-");
+        return;
+    };
+
+    let fill = if scale > 0.0 {
+        let alpha = (40.0 + 160.0 * (cell.fund.abs() / scale).min(1.0)) as u8;
+        if cell.fund >= 0.0 {
+            Color32::from_rgba_unmultiplied(0, 160, 0, alpha)
+        } else {
+            Color32::from_rgba_unmultiplied(200, 0, 0, alpha)
+        }
+    } else {
+        Color32::TRANSPARENT
+    };
+
+    Frame::none()
+        .fill(fill)
+        .inner_margin(3.0)
+        .rounding(2.0)
+        .show(ui, |ui| {
+            ui.vertical(|ui| {
+                ui.colored_label(text_color, format!("{:.2}%", cell.fund * 100.0));
+                let cdi = cell
+                    .cdi
+                    .map(|v| format!("CDI {:.2}%", v * 100.0))
+                    .unwrap_or_else(|| "CDI -".to_string());
+                ui.small(cdi);
+            });
+        });
+}
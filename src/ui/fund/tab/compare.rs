@@ -0,0 +1,357 @@
+use crate::{
+    analysis::{
+        compare::{common_start, FundSeries},
+        metrics::Metrics,
+    },
+    history::History,
+    message::Message,
+    provider::cvm::fund::Fund,
+    ui::{
+        charts::compare::{self, SeriesLine},
+        loading,
+        tabs::Tab,
+    },
+    util,
+};
+use chrono::NaiveDate;
+use egui::{Color32, Frame, Layout, Sense, Ui, Widget, WidgetText};
+use egui_extras::{Column, DatePickerButton, TableBuilder};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Linha da tabela comparativa, calculada a partir da data base comum.
+struct CompareRow {
+    cnpj: String,
+    name: String,
+    color: Color32,
+    total_return: Option<f64>,
+    volatility: Option<f64>,
+    max_drawdown: Option<f64>,
+    net_worth: Option<f64>,
+}
+
+pub struct CompareTab {
+    pub title: String,
+    pub sender: UnboundedSender<Message>,
+    pub history: History,
+    pub cnpjs: Vec<String>,
+    pub input: String,
+    /// Fundos do cadastro que casam com `input`.
+    suggestions: Vec<Fund>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub loading: bool,
+    lines: Vec<SeriesLine>,
+    rows: Vec<CompareRow>,
+    base_date: Option<NaiveDate>,
+}
+
+impl CompareTab {
+    pub fn new(title: String, sender: UnboundedSender<Message>, history: History) -> Self {
+        let end_date = chrono::offset::Utc::now().date_naive();
+        let start_date = end_date
+            .checked_sub_signed(chrono::Duration::days(12 * 30))
+            .unwrap();
+
+        CompareTab {
+            title,
+            sender,
+            history,
+            cnpjs: Vec::new(),
+            input: String::new(),
+            suggestions: Vec::new(),
+            start_date,
+            end_date,
+            loading: false,
+            lines: Vec::new(),
+            rows: Vec::new(),
+            base_date: None,
+        }
+    }
+
+    pub fn add_cnpj(&mut self, cnpj: String) {
+        let cnpj = cnpj.trim().to_string();
+        if !cnpj.is_empty() && !self.cnpjs.contains(&cnpj) {
+            self.cnpjs.push(cnpj);
+        }
+    }
+
+    /// Sugestões da busca, ignoradas se o texto já mudou.
+    pub fn set_suggestions(&mut self, query: &str, funds: Vec<Fund>) {
+        if query == self.input.trim() {
+            self.suggestions = funds;
+        }
+    }
+
+    pub fn set_funds(&mut self, funds: Vec<FundSeries>) {
+        self.loading = false;
+        self.base_date = common_start(&funds);
+        let colors = util::generate_colors(funds.len());
+        let Some(base_date) = self.base_date else {
+            self.lines.clear();
+            self.rows.clear();
+            return;
+        };
+
+        self.lines = funds
+            .iter()
+            .zip(colors.iter())
+            .map(|(fund, color)| SeriesLine {
+                name: fund.name.clone(),
+                color: *color,
+                points: fund.normalized(base_date),
+            })
+            .collect();
+
+        self.rows = funds
+            .iter()
+            .zip(colors)
+            .map(|(fund, color)| {
+                let metrics = Metrics::compute(&fund.quotas_from(base_date), &[]);
+                CompareRow {
+                    cnpj: fund.cnpj.clone(),
+                    name: fund.name.clone(),
+                    color,
+                    total_return: metrics.as_ref().map(|m| m.total_return),
+                    volatility: metrics.as_ref().and_then(|m| m.annualized_volatility),
+                    max_drawdown: metrics
+                        .as_ref()
+                        .and_then(|m| m.max_drawdown)
+                        .map(|dd| dd.depth),
+                    net_worth: fund.last_net_worth(),
+                }
+            })
+            .collect();
+    }
+
+    fn send_compare_message(&mut self) {
+        let _ = self.sender.send(Message::Compare(
+            self.cnpjs.clone(),
+            self.start_date,
+            self.end_date,
+        ));
+        self.loading = true;
+    }
+
+    fn show_selection(&mut self, ui: &mut Ui) {
+        ui.heading(egui::RichText::new("Fundos").size(14.0));
+        ui.horizontal(|ui| {
+            let response = egui::TextEdit::singleline(&mut self.input)
+                .hint_text("Nome, gestor ou CNPJ do fundo")
+                .desired_width(ui.available_width() - 30.0)
+                .ui(ui);
+            if response.changed() {
+                let query = self.input.trim().to_string();
+                if query.chars().count() >= 3 {
+                    let _ = self.sender.send(Message::CompareSearch(query));
+                } else {
+                    self.suggestions.clear();
+                }
+            }
+            let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.small_button(egui_phosphor::regular::PLUS).clicked() || enter {
+                // Com sugestões, adiciona a mais relevante; sem elas, o texto é o CNPJ
+                let cnpj = match self.suggestions.first() {
+                    Some(fund) => fund.cnpj.clone(),
+                    None => self.input.clone(),
+                };
+                self.input.clear();
+                self.suggestions.clear();
+                self.add_cnpj(cnpj);
+            }
+        });
+
+        let mut selected = None;
+        for fund in &self.suggestions {
+            let link = ui
+                .link(format!(
+                    "{} {}",
+                    egui_phosphor::regular::PLUS,
+                    fund.denomination
+                ))
+                .on_hover_ui(|ui| {
+                    ui.label(&fund.cnpj);
+                });
+            if link.clicked() {
+                selected = Some(fund.cnpj.clone());
+            }
+        }
+        if let Some(cnpj) = selected {
+            self.input.clear();
+            self.suggestions.clear();
+            self.add_cnpj(cnpj);
+        }
+
+        ui.add_space(5.0);
+        let mut remove = None;
+        for (i, cnpj) in self.cnpjs.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button(egui_phosphor::regular::X).clicked() {
+                    remove = Some(i);
+                }
+                ui.label(cnpj);
+            });
+        }
+        if let Some(i) = remove {
+            self.cnpjs.remove(i);
+        }
+
+        ui.add_space(10.0);
+        ui.weak("Visto Recentemente");
+        for (cnpj, name) in self.history.get_most_accesseds() {
+            if self.cnpjs.contains(&cnpj) {
+                continue;
+            }
+            let link = ui
+                .link(format!("{} {}", egui_phosphor::regular::PLUS, cnpj))
+                .on_hover_ui(|ui| {
+                    ui.label(name.clone());
+                });
+            if link.clicked() {
+                self.add_cnpj(cnpj);
+            }
+        }
+
+        ui.separator();
+        ui.heading(egui::RichText::new("Período").size(14.0));
+        ui.horizontal(|ui| {
+            for (label, months) in [("6M", 6), ("1A", 12), ("2A", 24)] {
+                if ui.small_button(label).clicked() {
+                    self.end_date = chrono::offset::Utc::now().date_naive();
+                    self.start_date = self
+                        .end_date
+                        .checked_sub_signed(chrono::Duration::days(months * 30))
+                        .unwrap();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Data Inicial:");
+            DatePickerButton::new(&mut self.start_date)
+                .id_source("compare_data_ini")
+                .ui(ui);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Data Final:  ");
+            DatePickerButton::new(&mut self.end_date)
+                .id_source("compare_data_fim")
+                .ui(ui);
+        });
+
+        ui.add_space(10.0);
+        let enabled = !self.loading && !self.cnpjs.is_empty();
+        if ui
+            .add_enabled(enabled, egui::Button::new("Comparar"))
+            .clicked()
+        {
+            self.send_compare_message();
+        }
+    }
+
+    fn show_table(&self, ui: &mut Ui) {
+        let percent = |value: Option<f64>| {
+            value
+                .map(|v| format!("{:.2}%", v * 100.0))
+                .unwrap_or_else(|| "-".to_string())
+        };
+
+        TableBuilder::new(ui)
+            .column(Column::auto().at_least(300.0).resizable(true).clip(true))
+            .columns(Column::auto().at_least(100.0), 3)
+            .column(Column::remainder())
+            .cell_layout(Layout::left_to_right(egui::Align::Center))
+            .striped(true)
+            .header(20.0, |mut header| {
+                for title in [
+                    "Fundo",
+                    "Retorno",
+                    "Volatilidade",
+                    "Drawdown máx.",
+                    "Patrim. Líq.",
+                ] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|mut body| {
+                for row in &self.rows {
+                    body.row(20.0, |mut table_row| {
+                        table_row.col(|ui| {
+                            let (rect, _) =
+                                ui.allocate_exact_size(egui::vec2(12.0, 12.0), Sense::hover());
+                            ui.painter().circle_filled(rect.center(), 5.0, row.color);
+                            ui.label(&row.name).on_hover_text(&row.cnpj);
+                        });
+                        table_row.col(|ui| {
+                            ui.label(percent(row.total_return));
+                        });
+                        table_row.col(|ui| {
+                            ui.label(percent(row.volatility));
+                        });
+                        table_row.col(|ui| {
+                            ui.label(percent(row.max_drawdown));
+                        });
+                        table_row.col(|ui| {
+                            let value = row
+                                .net_worth
+                                .and_then(|v| util::to_real(v).ok())
+                                .map(|v| v.format())
+                                .unwrap_or_else(|| "-".to_string());
+                            ui.label(value);
+                        });
+                    });
+                }
+            });
+    }
+}
+
+impl Tab for CompareTab {
+    fn title(&self) -> WidgetText {
+        self.title.clone().into()
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn ui(&mut self, ui: &mut Ui) {
+        egui::SidePanel::left(ui.id().with("compare_selection_panel"))
+            .resizable(true)
+            .default_width(280.0)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    Frame::none().inner_margin(10.0).show(ui, |ui| {
+                        self.show_selection(ui);
+                    });
+                });
+            });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            Frame::none().inner_margin(10.0).show(ui, |ui| {
+                if self.loading {
+                    loading::show(ui);
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.heading(egui::RichText::new("Comparação de Rentabilidade").size(16.0));
+                    if let Some(base_date) = self.base_date {
+                        ui.weak(format!("base em {}", base_date.format("%d/%m/%Y")));
+                    }
+                });
+                ui.separator();
+
+                egui::TopBottomPanel::bottom(ui.id().with("compare_table_panel"))
+                    .resizable(true)
+                    .default_height(150.0)
+                    .show_inside(ui, |ui| {
+                        egui::ScrollArea::horizontal().show(ui, |ui| {
+                            self.show_table(ui);
+                        });
+                    });
+
+                compare::chart("plot::funds::compare", &self.lines, ui);
+            });
+        });
+    }
+}
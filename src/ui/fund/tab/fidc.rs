@@ -0,0 +1,265 @@
+use crate::{
+    analysis::fidc::{FidcHistory, QuotaKind, OVERDUE_GROUPS},
+    message::Message,
+    provider::cvm::fidc::OVERDUE_COLUMNS,
+    ui::{
+        charts::compare::{self, SeriesLine},
+        tabs::Tab,
+    },
+    util,
+};
+use chrono::NaiveDate;
+use egui::{Color32, Grid, RichText, Ui, Vec2, WidgetText};
+use polars::frame::DataFrame;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::monthly::{real, MonthlyReport, CHART_HEIGHT};
+
+/// Informes mensais de um FIDC: subordinação, inadimplência e rentabilidade das classes.
+pub struct FidcTab {
+    pub report: MonthlyReport,
+    pub history: FidcHistory,
+}
+
+impl FidcTab {
+    pub fn new(cnpj: String, name: String, sender: UnboundedSender<Message>) -> Self {
+        FidcTab {
+            report: MonthlyReport::new(Self::title_for(&cnpj), cnpj, name, sender, Message::Fidc),
+            history: FidcHistory::default(),
+        }
+    }
+
+    pub fn title_for(cnpj: &str) -> String {
+        format!("FIDC {}", cnpj)
+    }
+
+    pub fn set_monthly(&mut self, monthly: &DataFrame, quotas: &DataFrame) {
+        self.history = FidcHistory::from_dataframes(monthly, quotas);
+        self.report.loading = false;
+    }
+}
+
+fn show_summary(history: &FidcHistory, ui: &mut Ui) {
+    let last = history.last();
+    let last_value = |series: Vec<(NaiveDate, f64)>| series.last().map(|(_, value)| *value);
+    let rows = [
+        ("Patrimônio líquido", real(last.and_then(|m| m.net_worth))),
+        (
+            "Direitos creditórios",
+            real(last.and_then(|m| m.receivables)),
+        ),
+        (
+            "Inadimplência",
+            percent(last.and_then(|m| m.delinquency()).map(|v| v * 100.0)),
+        ),
+        ("Inadimplência (R$)", real(last.map(|m| m.overdue_total()))),
+        (
+            "Subordinação total",
+            percent(last_value(history.subordination(&[QuotaKind::Senior]))),
+        ),
+        (
+            "Subordinação júnior",
+            percent(last_value(
+                history.subordination(&[QuotaKind::Senior, QuotaKind::Mezzanine]),
+            )),
+        ),
+        ("Classes de cotas", history.quotas.len().to_string()),
+        (
+            "Último informe",
+            last.map(|m| m.date.format("%m/%Y").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+    ];
+
+    Grid::new(ui.id().with("fidc_summary"))
+        .num_columns(4)
+        .striped(true)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            for (i, (label, value)) in rows.iter().enumerate() {
+                ui.weak(*label);
+                ui.label(value);
+                if i % 2 == 1 {
+                    ui.end_row();
+                }
+            }
+        });
+}
+
+fn show_subordination(history: &FidcHistory, ui: &mut Ui) {
+    ui.weak("Índice de subordinação (% do patrimônio das classes)");
+    ui.allocate_ui(Vec2::new(ui.available_width(), CHART_HEIGHT), |ui| {
+        let lines = [
+            SeriesLine {
+                name: "Total (mezanino + subordinada)".to_string(),
+                color: Color32::from_rgb(0, 160, 255),
+                points: history.subordination(&[QuotaKind::Senior]),
+            },
+            SeriesLine {
+                name: "Júnior (subordinada)".to_string(),
+                color: Color32::from_rgb(255, 140, 0),
+                points: history.subordination(&[QuotaKind::Senior, QuotaKind::Mezzanine]),
+            },
+        ];
+        compare::chart("plot::fidc::subordination", &lines, ui);
+    });
+}
+
+fn show_delinquency(history: &FidcHistory, ui: &mut Ui) {
+    ui.weak("Inadimplência por faixa de atraso (% dos direitos creditórios)");
+    ui.allocate_ui(Vec2::new(ui.available_width(), CHART_HEIGHT), |ui| {
+        let colors = util::generate_colors(OVERDUE_GROUPS.len());
+        let lines: Vec<SeriesLine> = OVERDUE_GROUPS
+            .iter()
+            .enumerate()
+            .map(|(group, (label, _))| SeriesLine {
+                name: label.to_string(),
+                color: colors[group],
+                points: history
+                    .months
+                    .iter()
+                    .filter_map(|month| {
+                        let share = month.delinquency_by_group()[group]?;
+                        Some((month.date, share * 100.0))
+                    })
+                    .collect(),
+            })
+            .collect();
+        compare::chart("plot::fidc::delinquency", &lines, ui);
+    });
+
+    let Some(last) = history.last() else {
+        return;
+    };
+    ui.add_space(5.0);
+    ui.weak(format!("Inadimplência em {}", last.date.format("%m/%Y")));
+    Grid::new(ui.id().with("fidc_overdue"))
+        .num_columns(3)
+        .striped(true)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            ui.weak("Atraso");
+            ui.weak("Valor");
+            ui.weak("% dos direitos creditórios");
+            ui.end_row();
+            let receivables = last.receivables.filter(|v| *v > 0.0);
+            for ((_, label), value) in OVERDUE_COLUMNS.iter().zip(&last.overdue) {
+                ui.label(*label);
+                ui.label(real(Some(*value)));
+                ui.label(percent(receivables.map(|r| value / r * 100.0)));
+                ui.end_row();
+            }
+        });
+}
+
+fn show_quotas(history: &FidcHistory, ui: &mut Ui) {
+    ui.weak("Rentabilidade acumulada por classe de cotas");
+    let colors = util::generate_colors(history.quotas.len());
+    ui.allocate_ui(Vec2::new(ui.available_width(), CHART_HEIGHT), |ui| {
+        let lines: Vec<SeriesLine> = history
+            .quotas
+            .iter()
+            .zip(&colors)
+            .map(|(series, color)| SeriesLine {
+                name: series.class.clone(),
+                color: *color,
+                points: series.cumulative_return(),
+            })
+            .collect();
+        compare::chart("plot::fidc::quotas", &lines, ui);
+    });
+
+    ui.add_space(5.0);
+    let total: f64 = history
+        .quotas
+        .iter()
+        .filter_map(|series| series.points.last()?.net_worth())
+        .sum();
+    Grid::new(ui.id().with("fidc_quotas"))
+        .num_columns(8)
+        .striped(true)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            for title in [
+                "Classe/série",
+                "Tipo",
+                "Valor da cota",
+                "Patrimônio",
+                "% do total",
+                "Rentab. no mês",
+                "Rentab. no período",
+                "Cotistas",
+            ] {
+                ui.weak(title);
+            }
+            ui.end_row();
+
+            for (series, color) in history.quotas.iter().zip(&colors) {
+                let last = series.points.last();
+                let net_worth = last.and_then(|point| point.net_worth());
+                ui.label(RichText::new(&series.class).color(*color));
+                ui.label(series.kind.label());
+                ui.label(
+                    last.and_then(|point| point.value)
+                        .map(|v| format!("{:.6}", v))
+                        .unwrap_or_else(|| "-".to_string()),
+                );
+                ui.label(real(net_worth));
+                ui.label(percent(
+                    net_worth.filter(|_| total > 0.0).map(|v| v / total * 100.0),
+                ));
+                ui.label(percent(last.and_then(|point| point.monthly_return)));
+                ui.label(percent(
+                    series.cumulative_return().last().map(|(_, value)| *value),
+                ));
+                ui.label(
+                    last.and_then(|point| point.shareholders)
+                        .map(|v| format!("{:.0}", v))
+                        .unwrap_or_else(|| "-".to_string()),
+                );
+                ui.end_row();
+            }
+        });
+}
+
+fn percent(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{:.2}%", v))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+impl Tab for FidcTab {
+    fn title(&self) -> WidgetText {
+        self.report.title.clone().into()
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn ui(&mut self, ui: &mut Ui) {
+        let history = &self.history;
+        let heading = history
+            .name
+            .as_deref()
+            .unwrap_or(&self.report.name)
+            .to_string();
+        let cnpj = self.report.cnpj.clone();
+        self.report.show(
+            ui,
+            &heading,
+            &[("CNPJ:", Some(cnpj.as_str()))],
+            history.is_empty(),
+            "Nenhum informe mensal de FIDC encontrado para este CNPJ.",
+            |ui| {
+                show_summary(history, ui);
+                ui.separator();
+                show_subordination(history, ui);
+                ui.add_space(10.0);
+                show_delinquency(history, ui);
+                ui.add_space(10.0);
+                show_quotas(history, ui);
+            },
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    analysis::fii::{FiiHistory, FiiMonth},
+    message::Message,
+    ui::{
+        charts::{
+            bars,
+            compare::{self, SeriesLine},
+        },
+        tabs::Tab,
+    },
+};
+use chrono::NaiveDate;
+use egui::{Color32, Grid, Ui, Vec2, WidgetText};
+use polars::frame::DataFrame;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::monthly::{real, MonthlyReport, CHART_HEIGHT};
+
+/// Informes mensais de um fundo imobiliário: rendimentos, dividend yield e cotistas.
+pub struct FiiTab {
+    pub report: MonthlyReport,
+    pub history: FiiHistory,
+}
+
+impl FiiTab {
+    pub fn new(cnpj: String, name: String, sender: UnboundedSender<Message>) -> Self {
+        FiiTab {
+            report: MonthlyReport::new(Self::title_for(&cnpj), cnpj, name, sender, Message::Fii),
+            history: FiiHistory::default(),
+        }
+    }
+
+    pub fn title_for(cnpj: &str) -> String {
+        format!("FII {}", cnpj)
+    }
+
+    pub fn set_monthly(&mut self, df: &DataFrame) {
+        self.history = FiiHistory::from_dataframe(df);
+        self.report.loading = false;
+    }
+}
+
+fn show_summary(history: &FiiHistory, ui: &mut Ui) {
+    let last = history.last();
+    let field = |f: fn(&FiiMonth) -> Option<f64>| last.and_then(f);
+
+    let rows = [
+        ("Patrimônio líquido", real(field(|m| m.net_worth))),
+        ("Valor patrimonial da cota", real(field(|m| m.quota_value))),
+        (
+            "Dividend yield no mês",
+            percent(field(|m| m.dividend_yield)),
+        ),
+        (
+            "Dividend yield 12 meses",
+            percent(history.dividend_yield_12m()),
+        ),
+        (
+            "Rendimento por cota no mês",
+            real(last.and_then(FiiMonth::distribution_per_quota)),
+        ),
+        (
+            "Rendimentos no período",
+            real(Some(history.total_distribution())),
+        ),
+        (
+            "Cotistas",
+            field(|m| m.shareholders)
+                .map(|v| format!("{:.0}", v))
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "Último informe",
+            last.map(|m| m.date.format("%m/%Y").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+    ];
+
+    Grid::new(ui.id().with("fii_summary"))
+        .num_columns(4)
+        .striped(true)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            for (i, (label, value)) in rows.iter().enumerate() {
+                ui.weak(*label);
+                ui.label(value);
+                if i % 2 == 1 {
+                    ui.end_row();
+                }
+            }
+        });
+}
+
+fn show_charts(history: &FiiHistory, ui: &mut Ui) {
+    let size = Vec2::new(ui.available_width(), CHART_HEIGHT);
+    let series = |f: fn(&FiiMonth) -> Option<f64>, scale: f64| -> Vec<(NaiveDate, f64)> {
+        history
+            .months
+            .iter()
+            .filter_map(|month| Some((month.date, f(month)? * scale)))
+            .collect()
+    };
+
+    ui.weak("Dividend yield mensal");
+    ui.allocate_ui(size, |ui| {
+        bars::by_date(
+            "plot::fii::dividend_yield",
+            "Dividend yield",
+            &series(|m| m.dividend_yield, 100.0),
+            30.0,
+            |_| Color32::from_rgb(0, 160, 0),
+            "%",
+            ui,
+        );
+    });
+
+    ui.add_space(10.0);
+    ui.weak("Rendimento por cota (R$)");
+    ui.allocate_ui(size, |ui| {
+        bars::by_date(
+            "plot::fii::distribution",
+            "Rendimento por cota",
+            &series(FiiMonth::distribution_per_quota, 1.0),
+            30.0,
+            |_| Color32::from_rgb(0, 160, 255),
+            "",
+            ui,
+        );
+    });
+
+    ui.add_space(10.0);
+    ui.weak("Valor patrimonial da cota (R$)");
+    ui.allocate_ui(size, |ui| {
+        let line = SeriesLine {
+            name: "Valor patrimonial".to_string(),
+            color: Color32::from_rgb(255, 200, 0),
+            points: series(|m| m.quota_value, 1.0),
+        };
+        compare::chart_with_unit("plot::fii::quota_value", &[line], "", ui);
+    });
+}
+
+fn show_table(history: &FiiHistory, ui: &mut Ui) {
+    Grid::new(ui.id().with("fii_months"))
+        .num_columns(7)
+        .striped(true)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            for title in [
+                "Mês",
+                "Patrimônio líquido",
+                "Valor da cota",
+                "DY",
+                "Rendimento por cota",
+                "Rentab. efetiva",
+                "Cotistas",
+            ] {
+                ui.weak(title);
+            }
+            ui.end_row();
+
+            for month in history.months.iter().rev() {
+                ui.label(month.date.format("%m/%Y").to_string());
+                ui.label(real(month.net_worth));
+                ui.label(real(month.quota_value));
+                ui.label(percent(month.dividend_yield));
+                ui.label(real(month.distribution_per_quota()));
+                ui.label(percent(month.effective_return));
+                ui.label(
+                    month
+                        .shareholders
+                        .map(|v| format!("{:.0}", v))
+                        .unwrap_or_else(|| "-".to_string()),
+                );
+                ui.end_row();
+            }
+        });
+}
+
+/// Percentual a partir da fração informada pela CVM.
+fn percent(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{:.2}%", v * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+impl Tab for FiiTab {
+    fn title(&self) -> WidgetText {
+        self.report.title.clone().into()
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn ui(&mut self, ui: &mut Ui) {
+        let history = &self.history;
+        let profile = &history.profile;
+        let heading = profile
+            .name
+            .as_deref()
+            .unwrap_or(&self.report.name)
+            .to_string();
+        let cnpj = self.report.cnpj.clone();
+        let details = [
+            ("CNPJ:", Some(cnpj.as_str())),
+            ("Segmento:", profile.segment.as_deref()),
+            ("Mandato:", profile.mandate.as_deref()),
+            ("Gestão:", profile.management.as_deref()),
+            ("Público-alvo:", profile.target_audience.as_deref()),
+        ];
+        self.report.show(
+            ui,
+            &heading,
+            &details,
+            history.is_empty(),
+            "Nenhum informe mensal de FII encontrado para este CNPJ.",
+            |ui| {
+                show_summary(history, ui);
+                ui.separator();
+                show_charts(history, ui);
+                ui.separator();
+                show_table(history, ui);
+            },
+        );
+    }
+}
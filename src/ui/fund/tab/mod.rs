@@ -1,6 +1,22 @@
-use crate::{message, ui::tabs::Tab};
+use crate::{
+    analysis::credit::Limits,
+    message,
+    provider::{
+        cvm::fund::{Class, Fund, FundType},
+        indices::benchmark::{Benchmark, BenchmarkSeries},
+    },
+    ui::tabs::Tab,
+};
+pub mod compare;
 pub mod dashboard;
-use super::panel::{self, portfolio::PortfolioUI, profit::ProfitUI};
+pub mod fidc;
+pub mod fii;
+pub mod monthly;
+pub mod screener;
+use super::panel::{
+    self, classes::ClassesUI, credit::CreditUI, flows::FlowsUI, portfolio::PortfolioUI,
+    profit::ProfitUI,
+};
 use egui::{Frame, Ui, WidgetText};
 use polars::frame::DataFrame;
 use tokio::sync::mpsc::UnboundedSender;
@@ -9,7 +25,9 @@ use tokio::sync::mpsc::UnboundedSender;
 pub enum Panel {
     Details,
     Profit,
+    Flows,
     Assets,
+    Classes,
 }
 
 impl Default for Panel {
@@ -20,11 +38,14 @@ impl Default for Panel {
 
 pub struct FundTab {
     pub title: String,
-    pub fund: DataFrame,
+    pub fund: Fund,
+    pub details: DataFrame,
     pub open_panel: Panel,
     pub sender: Option<UnboundedSender<message::Message>>,
     pub profit_ui: ProfitUI,
     pub portfolio_ui: PortfolioUI,
+    pub flows_ui: FlowsUI,
+    pub classes_ui: ClassesUI,
 }
 
 impl Default for FundTab {
@@ -32,46 +53,69 @@ impl Default for FundTab {
         FundTab {
             title: String::from(""),
             open_panel: Panel::default(),
-            fund: DataFrame::empty(),
+            fund: Fund::default(),
+            details: DataFrame::empty(),
             sender: None,
             profit_ui: ProfitUI::default(),
             portfolio_ui: PortfolioUI::default(),
+            flows_ui: FlowsUI::default(),
+            classes_ui: ClassesUI::default(),
         }
     }
 }
 
 impl FundTab {
-    pub fn new(title: String, fund: DataFrame, sender: UnboundedSender<message::Message>) -> Self {
+    pub fn new(
+        fund: Fund,
+        details: DataFrame,
+        limits: Limits,
+        benchmarks: Vec<Benchmark>,
+        sender: UnboundedSender<message::Message>,
+    ) -> Self {
+        let title = fund.cnpj.clone();
         let profit_ui = ProfitUI {
             sender: Some(sender.clone()),
             cnpj: title.clone(),
+            visible_benchmarks: benchmarks
+                .iter()
+                .map(|b| (b.name.clone(), b.visible))
+                .collect(),
+            available_benchmarks: benchmarks,
             ..Default::default()
         };
 
-        let mut portfolio_ui = PortfolioUI {
+        let portfolio_ui = PortfolioUI {
             sender: Some(sender.clone()),
             cnpj: title.clone(),
+            start_date: fund.situation_start_date,
+            credit: CreditUI {
+                limits,
+                ..Default::default()
+            },
             ..Default::default()
         };
 
-        fund.clone()
-            .column("DT_INI_SIT")
-            .ok()
-            .and_then(|col| col.get(0).ok())
-            .and_then(|val| val.get_str().map(|s| s.to_string()))
-            .map(|v| {
-                portfolio_ui.start_date = v;
-            })
-            .unwrap_or_else(|| {
-                portfolio_ui.start_date = "".to_string();
-            });
+        let flows_ui = FlowsUI {
+            sender: Some(sender.clone()),
+            cnpj: title.clone(),
+            ..Default::default()
+        };
+
+        let classes_ui = ClassesUI {
+            sender: Some(sender.clone()),
+            cnpj: title.clone(),
+            ..Default::default()
+        };
 
         FundTab {
             title,
             fund,
+            details,
             sender: Some(sender),
             portfolio_ui,
             profit_ui,
+            flows_ui,
+            classes_ui,
             ..Default::default()
         }
     }
@@ -84,8 +128,8 @@ impl FundTab {
         self.profit_ui.cdi = df;
     }
 
-    pub fn set_ibov_dataframe(&mut self, df: DataFrame) {
-        self.profit_ui.ibov = df;
+    pub fn set_benchmarks(&mut self, benchmarks: Vec<BenchmarkSeries>) {
+        self.profit_ui.set_benchmarks(benchmarks);
     }
 
     pub fn set_profit_dataframe(&mut self, df: DataFrame) {
@@ -111,6 +155,23 @@ impl FundTab {
     pub fn set_assets_loading(&mut self, value: bool) {
         self.portfolio_ui.loading = value;
     }
+
+    /// FIIs e FIDCs têm informe mensal próprio em vez do informe diário; retorna a
+    /// mensagem que abre a aba correspondente.
+    fn monthly_report(&self) -> Option<(&'static str, message::Message)> {
+        let cnpj = self.fund.cnpj.clone();
+        let name = self.fund.denomination.clone();
+        match (&self.fund.class, &self.fund.fund_type) {
+            (Some(Class::Fii), _) | (_, Some(FundType::Fii)) => {
+                Some(("FII", message::Message::OpenFiiTab(cnpj, name)))
+            }
+            (Some(Class::Fidc | Class::FidcNp), _)
+            | (_, Some(FundType::Fidc | FundType::FidcNp)) => {
+                Some(("FIDC", message::Message::OpenFidcTab(cnpj, name)))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Tab for FundTab {
@@ -125,13 +186,28 @@ impl Tab for FundTab {
     fn ui(&mut self, ui: &mut Ui) {
         let _sender = self.sender().clone();
         egui::TopBottomPanel::top(ui.id().with("fund_tab_bottom_panel")).show_inside(ui, |ui| {
-            if let Ok(s) = self.fund.column("DENOM_SOCIAL") {
-                ui.heading(s.get(0).unwrap().get_str().unwrap());
-            }
+            ui.heading(&self.fund.denomination);
             ui.horizontal(|ui| {
-                display_column_value(ui, "CNPJ:", "CNPJ_FUNDO", &self.fund);
+                display_value(ui, "CNPJ:", Some(&self.fund.cnpj));
                 ui.separator();
-                display_column_value(ui, "Administrador:", "ADMIN", &self.fund);
+                display_value(
+                    ui,
+                    "Administrador:",
+                    self.fund.admin.as_ref().map(|admin| &admin.name),
+                );
+                if let Some((kind, message)) = self.monthly_report() {
+                    ui.separator();
+                    if ui
+                        .small_button(format!(
+                            "{} Informe mensal {}",
+                            egui_phosphor::regular::CALENDAR,
+                            kind
+                        ))
+                        .clicked()
+                    {
+                        let _ = self.sender().send(message);
+                    }
+                }
             });
         });
         ui.add_space(5.0);
@@ -159,6 +235,18 @@ impl Tab for FundTab {
                 )
             }
 
+            if ui
+                .selectable_value(
+                    &mut self.open_panel,
+                    Panel::Flows,
+                    format!("{} Patrimônio", egui_phosphor::regular::USERS),
+                )
+                .clicked()
+                && self.flows_ui.flows.is_empty()
+            {
+                self.flows_ui.send_flows_message();
+            }
+
             if ui
                 .selectable_value(
                     &mut self.open_panel,
@@ -170,6 +258,17 @@ impl Tab for FundTab {
             {
                 self.portfolio_ui.send_assets_message();
             }
+
+            if ui
+                .selectable_value(
+                    &mut self.open_panel,
+                    Panel::Classes,
+                    format!("{} Classes", egui_phosphor::regular::TREE_STRUCTURE),
+                )
+                .clicked()
+            {
+                self.classes_ui.send_registry_message();
+            }
         });
 
         ui.painter().rect_filled(
@@ -188,28 +287,29 @@ impl Tab for FundTab {
 
             match self.open_panel {
                 Panel::Details => {
-                    panel::detail::show_ui(self.fund.clone(), ui);
+                    panel::detail::show_ui(self.details.clone(), ui);
                 }
                 Panel::Profit => {
                     self.profit_ui.show(ui);
                 }
+                Panel::Flows => {
+                    self.flows_ui.show(ui);
+                }
                 Panel::Assets => {
                     self.portfolio_ui.show(ui);
                 }
+                Panel::Classes => {
+                    self.classes_ui.show(ui);
+                }
             };
         });
     }
 }
 
-fn display_column_value(ui: &mut Ui, label: &str, column_name: &str, fund: &DataFrame) {
+fn display_value(ui: &mut Ui, label: &str, value: Option<&String>) {
     ui.label(label);
-    if let Ok(column) = fund.column(column_name) {
-        if let Ok(value) = column.get(0) {
-            if let Some(str_value) = value.get_str() {
-                ui.weak(str_value);
-                return;
-            }
-        }
-    }
-    ui.weak("-");
+    match value {
+        Some(value) => ui.weak(value),
+        None => ui.weak("-"),
+    };
 }
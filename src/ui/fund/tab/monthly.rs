@@ -0,0 +1,116 @@
+use crate::{message::Message, ui::loading, util};
+use chrono::{Months, NaiveDate};
+use egui::{Frame, Layout, RichText, Ui};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Períodos disponíveis, em meses até hoje.
+const PERIODS: [(u32, &str); 4] = [(60, "5A"), (36, "3A"), (24, "2A"), (12, "1A")];
+
+pub const CHART_HEIGHT: f32 = 220.0;
+
+/// Fundo, período e carregamento das abas de informe mensal (FII e FIDC).
+pub struct MonthlyReport {
+    pub title: String,
+    pub cnpj: String,
+    /// Denominação do cadastro, usada até o informe trazer o nome.
+    pub name: String,
+    pub sender: UnboundedSender<Message>,
+    pub loading: bool,
+    pub months: u32,
+    /// Mensagem que pede os informes do CNPJ entre as duas datas.
+    request: fn(String, NaiveDate, NaiveDate) -> Message,
+}
+
+impl MonthlyReport {
+    pub fn new(
+        title: String,
+        cnpj: String,
+        name: String,
+        sender: UnboundedSender<Message>,
+        request: fn(String, NaiveDate, NaiveDate) -> Message,
+    ) -> Self {
+        MonthlyReport {
+            title,
+            cnpj,
+            name,
+            sender,
+            loading: false,
+            months: 24,
+            request,
+        }
+    }
+
+    pub fn send_message(&mut self) {
+        let end_date = chrono::offset::Utc::now().date_naive();
+        let start_date = end_date
+            .checked_sub_months(Months::new(self.months))
+            .unwrap_or(end_date);
+        let _ = self
+            .sender
+            .send((self.request)(self.cnpj.clone(), start_date, end_date));
+        self.loading = true;
+    }
+
+    /// Cabeçalho com `heading` e os pares de `details`, seletor de período e
+    /// `content` quando há informes; sem informes, exibe `empty`.
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        heading: &str,
+        details: &[(&str, Option<&str>)],
+        is_empty: bool,
+        empty: &str,
+        content: impl FnOnce(&mut Ui),
+    ) {
+        egui::TopBottomPanel::top(ui.id().with("monthly_tab_top_panel")).show_inside(ui, |ui| {
+            ui.heading(heading);
+            ui.horizontal(|ui| {
+                for (label, value) in details {
+                    ui.label(*label);
+                    ui.weak(value.unwrap_or("-"));
+                    ui.separator();
+                }
+            });
+        });
+
+        Frame::none().inner_margin(30.0).show(ui, |ui| {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                        ui.heading(RichText::new("Informe Mensal").size(16.0));
+                    });
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_enabled_ui(!self.loading, |ui| {
+                            for (months, label) in PERIODS {
+                                if ui
+                                    .selectable_value(&mut self.months, months, label)
+                                    .clicked()
+                                {
+                                    self.send_message();
+                                }
+                            }
+                        });
+                    });
+                });
+                ui.separator();
+                ui.add_space(5.0);
+
+                if self.loading {
+                    loading::show(ui);
+                } else if is_empty {
+                    ui.label(empty);
+                } else {
+                    egui::ScrollArea::vertical().show(ui, content);
+                }
+            });
+        });
+    }
+}
+
+/// Valor em reais, ou "-" sem valor.
+pub fn real(value: Option<f64>) -> String {
+    value
+        .and_then(|v| util::to_real(v).ok())
+        .map(|v| v.format())
+        .unwrap_or_else(|| "-".to_string())
+}
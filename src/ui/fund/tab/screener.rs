@@ -0,0 +1,425 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use crate::{
+    analysis::screener::{Filter, Range, Screen, ScreenerRow, SortKey},
+    message::Message,
+    provider::cvm::fund::{AnbimaClass, Class, Situation},
+    screens::Screens,
+    ui::{loading, tabs::Tab},
+    util,
+};
+use egui::{ComboBox, DragValue, Frame, Ui, WidgetText};
+use egui_extras::{Column, TableBuilder};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Colunas da tabela de resultados, com a ordenação associada.
+const COLUMNS: [(&str, Option<SortKey>); 9] = [
+    ("CNPJ", None),
+    ("Nome", Some(SortKey::Name)),
+    ("Classe", None),
+    ("Gestor", None),
+    ("Tx. Adm.", Some(SortKey::AdminFee)),
+    ("Constituição", Some(SortKey::Constitution)),
+    ("Rent. 12M", Some(SortKey::Return12m)),
+    ("Patrim. Líq.", Some(SortKey::NetWorth)),
+    ("Cotistas", Some(SortKey::Shareholders)),
+];
+
+/// Filtro de fundos por colunas do cadastro e indicadores dos informes, com telas
+/// nomeadas salvas em disco.
+pub struct ScreenerTab {
+    pub title: String,
+    pub sender: UnboundedSender<Message>,
+    pub loading: bool,
+    screens: Screens,
+    saved: Vec<Screen>,
+    screen: Screen,
+    rows: Vec<ScreenerRow>,
+    results: Vec<usize>,
+    /// Valores existentes no cadastro para cada filtro de escolha, na ordem de `Filter::all`.
+    choices: Vec<(Filter, Vec<String>)>,
+}
+
+impl ScreenerTab {
+    pub fn new(title: String, sender: UnboundedSender<Message>) -> Self {
+        let screens = Screens::new();
+        let saved = screens.load().unwrap_or_default();
+        ScreenerTab {
+            title,
+            sender,
+            loading: false,
+            screens,
+            saved,
+            screen: Screen::default(),
+            rows: Vec::new(),
+            results: Vec::new(),
+            choices: Vec::new(),
+        }
+    }
+
+    pub fn send_screener_message(&mut self) {
+        let _ = self.sender.send(Message::Screener);
+        self.loading = true;
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<ScreenerRow>) {
+        self.choices = Filter::all()
+            .into_iter()
+            .filter_map(|filter| {
+                let values: BTreeSet<&str> =
+                    rows.iter().filter_map(|r| filter.choice(&r.fund)).collect();
+                if values.is_empty() {
+                    return None;
+                }
+                let values = values.into_iter().map(str::to_string).collect();
+                Some((filter, values))
+            })
+            .collect();
+        self.rows = rows;
+        self.loading = false;
+        self.apply();
+    }
+
+    fn apply(&mut self) {
+        self.results = self.screen.apply(&self.rows);
+    }
+
+    fn save_screen(&mut self) {
+        let name = self.screen.name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        self.screen.name = name;
+        match self.saved.iter_mut().find(|s| s.name == self.screen.name) {
+            Some(saved) => *saved = self.screen.clone(),
+            None => self.saved.push(self.screen.clone()),
+        }
+        if let Err(err) = self.screens.save(&self.saved) {
+            log::error!("Erro ao salvar telas do screener: {}", err);
+        }
+    }
+
+    fn delete_screen(&mut self) {
+        self.saved.retain(|s| s.name != self.screen.name);
+        if let Err(err) = self.screens.save(&self.saved) {
+            log::error!("Erro ao salvar telas do screener: {}", err);
+        }
+    }
+
+    fn show_screens(&mut self, ui: &mut Ui) {
+        ui.heading(egui::RichText::new("Telas").size(14.0));
+        let mut selected = None;
+        ComboBox::from_id_source("screener_saved")
+            .selected_text(if self.saved.is_empty() {
+                "Nenhuma tela salva"
+            } else {
+                "Abrir tela"
+            })
+            .show_ui(ui, |ui| {
+                for screen in &self.saved {
+                    if ui.selectable_label(false, &screen.name).clicked() {
+                        selected = Some(screen.clone());
+                    }
+                }
+            });
+        if let Some(screen) = selected {
+            self.screen = screen;
+            self.apply();
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.screen.name)
+                    .hint_text("Nome da tela")
+                    .desired_width(140.0),
+            );
+            if ui.button("Salvar").clicked() {
+                self.save_screen();
+            }
+            if ui.button("Excluir").clicked() {
+                self.delete_screen();
+            }
+        });
+    }
+
+    fn show_filters(&mut self, ui: &mut Ui) {
+        ui.heading(egui::RichText::new("Filtros").size(14.0));
+        let mut changed = false;
+        let mut remove = None;
+        for (i, filter) in self.screen.filters.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(filter.label());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button(egui_phosphor::regular::TRASH).clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                });
+                let choices = self
+                    .choices
+                    .iter()
+                    .find(|(f, _)| std::mem::discriminant(f) == std::mem::discriminant(filter))
+                    .map(|(_, values)| values.as_slice())
+                    .unwrap_or_default();
+                changed |= filter_editor(ui, filter, choices);
+            });
+            ui.add_space(5.0);
+        }
+        if let Some(i) = remove {
+            self.screen.filters.remove(i);
+            changed = true;
+        }
+
+        ui.menu_button("Adicionar filtro", |ui| {
+            for filter in Filter::all() {
+                if ui.button(filter.label()).clicked() {
+                    self.screen.filters.push(filter);
+                    changed = true;
+                    ui.close_menu();
+                }
+            }
+        });
+
+        if changed {
+            self.apply();
+        }
+    }
+
+    fn show_table(&mut self, ui: &mut Ui) {
+        let mut sort = None;
+        TableBuilder::new(ui)
+            .column(Column::auto().at_least(130.0))
+            .column(Column::auto().at_least(300.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(90.0).resizable(true).clip(true))
+            .column(Column::auto().at_least(150.0).resizable(true).clip(true))
+            .columns(Column::auto().at_least(80.0), 2)
+            .columns(Column::auto().at_least(90.0), 2)
+            .column(Column::remainder().at_least(70.0))
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .striped(true)
+            .header(20.0, |mut header| {
+                for (title, key) in COLUMNS {
+                    header.col(|ui| match key {
+                        Some(key) => {
+                            let arrow = match (self.screen.sort == key, self.screen.descending) {
+                                (true, true) => egui_phosphor::regular::CARET_DOWN,
+                                (true, false) => egui_phosphor::regular::CARET_UP,
+                                _ => "",
+                            };
+                            if ui.link(format!("{} {}", title, arrow)).clicked() {
+                                sort = Some(key);
+                            }
+                        }
+                        None => {
+                            ui.label(title);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(20.0, self.results.len(), |mut row| {
+                    let screener_row = &self.rows[self.results[row.index()]];
+                    let fund = &screener_row.fund;
+                    row.col(|ui| {
+                        if ui.link(&fund.cnpj).clicked() {
+                            let _ = self.sender.send(Message::NewTab(fund.cnpj.clone()));
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(&fund.denomination);
+                    });
+                    row.col(|ui| {
+                        ui.label(fund.class.as_ref().map_or("-", |c| c.label()));
+                    });
+                    row.col(|ui| {
+                        ui.label(fund.gestor.as_ref().map_or("-", |g| g.name.as_str()));
+                    });
+                    row.col(|ui| {
+                        ui.label(percent(fund.admin_fee));
+                    });
+                    row.col(|ui| {
+                        ui.label(
+                            fund.constitution_date
+                                .map(|d| d.format("%d/%m/%Y").to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                        );
+                    });
+                    row.col(|ui| {
+                        ui.label(percent(screener_row.return_12m));
+                    });
+                    row.col(|ui| {
+                        ui.label(
+                            screener_row
+                                .net_worth
+                                .and_then(|v| util::to_real(v).ok())
+                                .map(|v| v.format())
+                                .unwrap_or_else(|| "-".to_string()),
+                        );
+                    });
+                    row.col(|ui| {
+                        ui.label(
+                            screener_row
+                                .shareholders
+                                .map(|v| format!("{:.0}", v))
+                                .unwrap_or_else(|| "-".to_string()),
+                        );
+                    });
+                });
+            });
+
+        if let Some(key) = sort {
+            if self.screen.sort == key {
+                self.screen.descending = !self.screen.descending;
+            } else {
+                self.screen.sort = key;
+                self.screen.descending = key != SortKey::Name;
+            }
+            self.apply();
+        }
+    }
+}
+
+/// Editor de um filtro; retorna `true` quando o valor muda.
+fn filter_editor(ui: &mut Ui, filter: &mut Filter, choices: &[String]) -> bool {
+    let speed = match filter {
+        Filter::NetWorth(_) => 1_000_000.0,
+        Filter::Shareholders(_) => 10.0,
+        Filter::Constitution(_) => 1.0,
+        _ => 0.1,
+    };
+    match filter {
+        Filter::Situation(value) => choice_editor(ui, value, choices, Situation::label),
+        Filter::Class(value) => choice_editor(ui, value, choices, Class::label),
+        Filter::AnbimaClass(value) => choice_editor(ui, value, choices, AnbimaClass::as_str),
+        Filter::Condominium(value) | Filter::TargetAudience(value) => {
+            choice_editor(ui, value, choices, String::as_str)
+        }
+        Filter::Exclusive(value) => flag_editor(ui, value),
+        Filter::Gestor(text) | Filter::Admin(text) => ui
+            .add(egui::TextEdit::singleline(text).hint_text("Parte do nome"))
+            .changed(),
+        Filter::AdminFee(range)
+        | Filter::PerformanceFee(range)
+        | Filter::Constitution(range)
+        | Filter::Return12m(range)
+        | Filter::NetWorth(range)
+        | Filter::Shareholders(range) => range_editor(ui, range, speed),
+    }
+}
+
+/// Escolha entre os valores existentes no cadastro; "Todos" não restringe.
+fn choice_editor<T: Clone + PartialEq + FromStr>(
+    ui: &mut Ui,
+    value: &mut Option<T>,
+    choices: &[String],
+    text: impl Fn(&T) -> &str,
+) -> bool {
+    let mut changed = false;
+    ComboBox::from_id_source("choice")
+        .selected_text(value.as_ref().map_or("Todos", &text))
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            changed |= ui.selectable_value(value, None, "Todos").changed();
+            for choice in choices.iter().filter_map(|c| c.parse::<T>().ok()) {
+                let label = text(&choice).to_string();
+                changed |= ui.selectable_value(value, Some(choice), label).changed();
+            }
+        });
+    changed
+}
+
+/// Sim ou não; "Todos" não restringe.
+fn flag_editor(ui: &mut Ui, value: &mut Option<bool>) -> bool {
+    let text = |value: &Option<bool>| match value {
+        None => "Todos",
+        Some(true) => "Sim",
+        Some(false) => "Não",
+    };
+    let mut changed = false;
+    ComboBox::from_id_source("flag")
+        .selected_text(text(value))
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            for choice in [None, Some(true), Some(false)] {
+                changed |= ui.selectable_value(value, choice, text(&choice)).changed();
+            }
+        });
+    changed
+}
+
+fn range_editor(ui: &mut Ui, range: &mut Range, speed: f64) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        for (label, bound) in [("mín.", &mut range.min), ("máx.", &mut range.max)] {
+            let mut enabled = bound.is_some();
+            if ui.checkbox(&mut enabled, label).changed() {
+                *bound = if enabled { Some(0.0) } else { None };
+                changed = true;
+            }
+            if let Some(value) = bound {
+                changed |= ui.add(DragValue::new(value).speed(speed)).changed();
+            }
+        }
+    });
+    changed
+}
+
+fn percent(value: Option<f64>) -> String {
+    value
+        .map(|v| format!("{:.2}%", v))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+impl Tab for ScreenerTab {
+    fn title(&self) -> WidgetText {
+        self.title.clone().into()
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn ui(&mut self, ui: &mut Ui) {
+        egui::SidePanel::left(ui.id().with("screener_filters_panel"))
+            .resizable(true)
+            .default_width(280.0)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    Frame::none().inner_margin(10.0).show(ui, |ui| {
+                        self.show_screens(ui);
+                        ui.separator();
+                        self.show_filters(ui);
+                    });
+                });
+            });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            Frame::none().inner_margin(10.0).show(ui, |ui| {
+                if self.loading {
+                    loading::show(ui);
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.heading(egui::RichText::new("Screener de Fundos").size(16.0));
+                    ui.weak(format!(
+                        "{} de {} fundos",
+                        self.results.len(),
+                        self.rows.len()
+                    ));
+                    if ui
+                        .small_button(egui_phosphor::regular::ARROWS_CLOCKWISE)
+                        .clicked()
+                    {
+                        self.send_screener_message();
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::horizontal().show(ui, |ui| {
+                    self.show_table(ui);
+                });
+            });
+        });
+    }
+}
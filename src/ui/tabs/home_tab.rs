@@ -56,6 +56,15 @@ impl Tab for HomeTab {
                             {
                                 let _ = self.sender.send(Message::OpenDashboardTab);
                             }
+                            if ui
+                                .small_button(format!(
+                                    "{} Comparar Fundos...",
+                                    egui_phosphor::regular::CHART_LINE
+                                ))
+                                .clicked()
+                            {
+                                let _ = self.sender.send(Message::OpenCompareTab);
+                            }
                         });
 
                         ui.add_space(50.0);
@@ -7,7 +7,10 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::message::Message;
 
-use super::fund::tab::{dashboard::DashboardTab, FundTab};
+use super::fund::tab::{
+    compare::CompareTab, dashboard::DashboardTab, fidc::FidcTab, fii::FiiTab,
+    screener::ScreenerTab, FundTab,
+};
 
 pub trait Tab {
     fn title(&self) -> WidgetText;
@@ -19,6 +22,10 @@ pub enum TabType {
     Fund(FundTab),
     Home(HomeTab),
     Dashboard(DashboardTab),
+    Compare(CompareTab),
+    Screener(ScreenerTab),
+    Fii(FiiTab),
+    Fidc(FidcTab),
 }
 
 impl Tab for TabType {
@@ -27,6 +34,10 @@ impl Tab for TabType {
             TabType::Fund(tab) => tab.title(),
             TabType::Home(tab) => tab.title(),
             TabType::Dashboard(tab) => tab.title(),
+            TabType::Compare(tab) => tab.title(),
+            TabType::Screener(tab) => tab.title(),
+            TabType::Fii(tab) => tab.title(),
+            TabType::Fidc(tab) => tab.title(),
             // Adicione outros tipos de tabs aqui
         }
     }
@@ -42,6 +53,10 @@ impl Tab for TabType {
                         TabType::Fund(tab) => tab.ui(ui),
                         TabType::Home(tab) => tab.ui(ui),
                         TabType::Dashboard(tab) => tab.ui(ui),
+                        TabType::Compare(tab) => tab.ui(ui),
+                        TabType::Screener(tab) => tab.ui(ui),
+                        TabType::Fii(tab) => tab.ui(ui),
+                        TabType::Fidc(tab) => tab.ui(ui),
                         // Adicione outros tipos de tabs aqui
                     }
                 });
@@ -53,6 +68,10 @@ impl Tab for TabType {
             TabType::Fund(tab) => tab.closeable(),
             TabType::Home(tab) => tab.closeable(),
             TabType::Dashboard(tab) => tab.closeable(),
+            TabType::Compare(tab) => tab.closeable(),
+            TabType::Screener(tab) => tab.closeable(),
+            TabType::Fii(tab) => tab.closeable(),
+            TabType::Fidc(tab) => tab.closeable(),
             // Adicione outros tipos de tabs aqui
         }
     }
@@ -1,4 +1,6 @@
-use egui::Align2;
+use std::{env, path::PathBuf};
+
+use egui::{epaint::Hsva, Align2};
 use egui_toast::Toasts;
 
 pub fn to_real(value: f64) -> Result<currency_rs::Currency, currency_rs::CurrencyErr> {
@@ -22,3 +24,45 @@ static TOASTS: once_cell::sync::Lazy<egui::mutex::Mutex<Toasts>> =
 pub fn toaster() -> egui::mutex::MutexGuard<'static, Toasts> {
     TOASTS.lock()
 }
+
+/// Gera `n` cores bem distribuídas no círculo cromático (razão áurea).
+pub fn generate_colors(n: usize) -> Vec<egui::Color32> {
+    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0; // 0.61803398875
+
+    (0..n)
+        .map(|i| {
+            let h = i as f32 * golden_ratio;
+            egui::Color32::from(Hsva::new(h.fract(), 0.85, 0.5, 1.0))
+        })
+        .collect()
+}
+
+/// Tamanho em bytes em texto curto, ex.: "12,3 MB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit]).replace('.', ",")
+    }
+}
+
+/// Caminho para um arquivo exportado: a pasta Downloads do usuário, se existir, senão a
+/// pasta pessoal ou, na falta dela, o diretório temporário.
+pub fn export_path(file_name: &str) -> PathBuf {
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    let dir = match home {
+        Some(home) if home.join("Downloads").is_dir() => home.join("Downloads"),
+        Some(home) => home,
+        None => env::temp_dir(),
+    };
+    dir.join(file_name)
+}